@@ -0,0 +1,123 @@
+//! Measures `GeneticAlgorithm::evolve()` for a few population and
+//! chromosome sizes - with methods stored as they are, behind pointers
+//! (see `GeneticAlgorithm::boxed()`), and with children built inside the
+//! previous ones' chromosomes (see `GeneticAlgorithm::evolve_into()`).
+//!
+//! ```text
+//! cargo bench -p lib-genetic-algorithm --bench evolve
+//! ```
+
+use lib_genetic_algorithm::{
+    Chromosome, CrossoverMethod, Float, GaussianMutation, GeneticAlgorithm, Individual,
+    MutationMethod, RouletteWheelSelection, UniformCrossover,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 20;
+
+struct BenchIndividual {
+    fitness: Float,
+    chromosome: Chromosome,
+}
+
+impl Individual for BenchIndividual {
+    fn create(chromosome: Chromosome) -> Self {
+        Self {
+            fitness: 0.0,
+            chromosome,
+        }
+    }
+
+    fn fitness(&self) -> Float {
+        self.fitness
+    }
+
+    fn chromosome(&self) -> &Chromosome {
+        &self.chromosome
+    }
+
+    fn into_chromosome(self) -> Chromosome {
+        self.chromosome
+    }
+}
+
+fn main() {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+    let ga = GeneticAlgorithm::new(
+        RouletteWheelSelection,
+        UniformCrossover,
+        GaussianMutation::new(0.01, 0.3),
+    );
+
+    let boxed = GeneticAlgorithm::boxed(
+        RouletteWheelSelection,
+        UniformCrossover,
+        GaussianMutation::new(0.01, 0.3),
+    );
+
+    for population_size in [40, 200, 1_000] {
+        for genes in [100, 1_000, 10_000] {
+            let population: Vec<_> = (0..population_size)
+                .map(|_| BenchIndividual {
+                    fitness: rng.gen(),
+                    chromosome: (0..genes).map(|_| rng.gen_range(-1.0..=1.0)).collect(),
+                })
+                .collect();
+
+            println!(
+                "evolve {population_size} individuals, {genes} genes: {:>10.1} us/iter, \
+                 boxed: {:>10.1} us/iter, into: {:>10.1} us/iter",
+                measure(&ga, &mut rng, &population),
+                measure(&boxed, &mut rng, &population),
+                measure_into(&ga, &mut rng, &population),
+            );
+        }
+    }
+}
+
+/// Returns average time of an `evolve()`, in microseconds.
+fn measure<C, M>(
+    ga: &GeneticAlgorithm<RouletteWheelSelection, C, M>,
+    rng: &mut ChaCha8Rng,
+    population: &[BenchIndividual],
+) -> f64
+where
+    C: CrossoverMethod,
+    M: MutationMethod,
+{
+    let started_at = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        black_box(ga.evolve(rng, black_box(population)));
+    }
+
+    started_at.elapsed().as_micros() as f64 / ITERATIONS as f64
+}
+
+/// Returns average time of an `evolve_into()`, with each iteration's
+/// children recycled into the next one's, in microseconds.
+fn measure_into<C, M>(
+    ga: &GeneticAlgorithm<RouletteWheelSelection, C, M>,
+    rng: &mut ChaCha8Rng,
+    population: &[BenchIndividual],
+) -> f64
+where
+    C: CrossoverMethod,
+    M: MutationMethod,
+{
+    let mut recycled = Vec::new();
+    let started_at = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        let (children, stats) = ga.evolve_into(rng, black_box(population), &mut recycled);
+
+        black_box(stats);
+        recycled.extend(children.into_iter().map(Individual::into_chromosome));
+    }
+
+    started_at.elapsed().as_micros() as f64 / ITERATIONS as f64
+}
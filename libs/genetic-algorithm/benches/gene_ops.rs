@@ -0,0 +1,56 @@
+//! Measures bulk gene operations, to compare them with and without the
+//! `simd` feature:
+//!
+//! ```text
+//! cargo bench -p lib-genetic-algorithm --bench gene_ops
+//! cargo bench -p lib-genetic-algorithm --bench gene_ops --features simd
+//! ```
+
+use lib_genetic_algorithm::{
+    BlendCrossover, Chromosome, CrossoverMethod, GaussianPerturbation, MutationMethod,
+};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 1_000;
+
+fn main() {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+    for genes in [100, 1_000, 10_000] {
+        let mut random =
+            || -> Chromosome { (0..genes).map(|_| rng.gen_range(-1.0..=1.0)).collect() };
+        let (a, b, mut child) = (random(), random(), random());
+        let perturbation = GaussianPerturbation::new(0.1);
+
+        println!("{genes} genes:");
+
+        measure("blend crossover", || {
+            BlendCrossover.crossover_into(&mut rng, &a, &b, &mut child);
+        });
+
+        measure("gaussian perturbation", || {
+            perturbation.mutate(&mut rng, &mut child);
+        });
+
+        measure("clamp", || child.clamp(-0.5, 0.5));
+
+        measure("distance", || {
+            black_box(a.distance(black_box(&b)));
+        });
+    }
+}
+
+fn measure(name: &str, mut f: impl FnMut()) {
+    let started_at = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        f();
+    }
+
+    let elapsed = started_at.elapsed().as_nanos() as f64 / ITERATIONS as f64;
+
+    println!("    {name:<24} {elapsed:>10.1} ns/iter");
+}
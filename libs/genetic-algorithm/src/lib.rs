@@ -1,31 +1,116 @@
+mod gene_ops;
+
+use rand::distributions::{Distribution, WeightedIndex};
 use rand::{seq::SliceRandom, Rng, RngCore};
-use std::ops::Index;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut, Index, Range};
+
+/// Precision of genes and fitness scores - `f32` by default, `f64` with the
+/// `f64` feature enabled.
+#[cfg(not(feature = "f64"))]
+pub type Float = f32;
+
+/// Precision of genes and fitness scores - `f32` by default, `f64` with the
+/// `f64` feature enabled.
+#[cfg(feature = "f64")]
+pub type Float = f64;
 
 pub trait Individual {
     fn create(chromosome: Chromosome) -> Self;
-    fn fitness(&self) -> f32;
+    fn fitness(&self) -> Float;
     fn chromosome(&self) -> &Chromosome;
+
+    /// Gives the chromosome back, e.g. for `GeneticAlgorithm::evolve_into()`
+    /// to reuse; individuals owning their chromosome should override it,
+    /// since by default it's cloned.
+    fn into_chromosome(self) -> Chromosome
+    where
+        Self: Sized,
+    {
+        self.chromosome().clone()
+    }
 }
 
 pub trait SelectionMethod {
     fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
     where
         I: Individual;
+
+    /// Returns a function selecting from `population` over and over (like
+    /// `select()` would), which methods can override to precompute what
+    /// doesn't change between the selections.
+    ///
+    /// It's returned as it is, so that the compiler can inline it into
+    /// `GeneticAlgorithm::evolve()`.
+    fn selector<'a, I>(&'a self, population: &'a [I]) -> impl FnMut(&mut dyn RngCore) -> &'a I + 'a
+    where
+        I: Individual,
+    {
+        move |rng| self.select(rng, population)
+    }
 }
 
-pub trait CrossoverMethod {
+/// Crossover and mutation methods are `Send`, so that genetic algorithms
+/// (and simulations built on them) can be moved between threads.
+pub trait CrossoverMethod: Send {
     fn crossover(
         &self,
         rng: &mut dyn RngCore,
         parent_a: &Chromosome,
         parent_b: &Chromosome,
     ) -> Chromosome;
+
+    /// Like `crossover()`, but overwrites `child` - which methods can
+    /// override to reuse its allocation.
+    fn crossover_into(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+        child: &mut Chromosome,
+    ) {
+        *child = self.crossover(rng, parent_a, parent_b);
+    }
 }
 
-pub trait MutationMethod {
+pub trait MutationMethod: Send {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
 }
 
+impl<T> CrossoverMethod for Box<T>
+where
+    T: CrossoverMethod + ?Sized,
+{
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        (**self).crossover(rng, parent_a, parent_b)
+    }
+
+    fn crossover_into(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+        child: &mut Chromosome,
+    ) {
+        (**self).crossover_into(rng, parent_a, parent_b, child)
+    }
+}
+
+impl<T> MutationMethod for Box<T>
+where
+    T: MutationMethod + ?Sized,
+{
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        (**self).mutate(rng, child)
+    }
+}
+
 pub struct RouletteWheelSelection;
 
 impl SelectionMethod for RouletteWheelSelection {
@@ -33,48 +118,145 @@ impl SelectionMethod for RouletteWheelSelection {
     where
         I: Individual,
     {
+        assert!(!population.is_empty(), "got an empty population");
+
+        // When nobody has scored anything, everybody's equally good
         population
-            .choose_weighted(rng, |individual| individual.fitness())
-            .expect("got an empty population")
+            .choose_weighted(&mut *rng, |individual| individual.fitness())
+            .unwrap_or_else(|_| &population[rng.gen_range(0..population.len())])
+    }
+
+    /// Sums the fitness scores up just once, making each selection a binary
+    /// search instead of going through the entire population.
+    fn selector<'a, I>(&'a self, population: &'a [I]) -> impl FnMut(&mut dyn RngCore) -> &'a I + 'a
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+
+        let weights =
+            WeightedIndex::new(population.iter().map(|individual| individual.fitness())).ok();
+
+        move |rng| match &weights {
+            Some(weights) => &population[weights.sample(rng)],
+            None => &population[rng.gen_range(0..population.len())],
+        }
+    }
+}
+
+/// Picks the fittest out of `size` individuals drawn at random (with
+/// replacement) - the bigger the tournament, the stronger the pressure;
+/// unlike the roulette wheel, it depends only on how individuals rank.
+#[derive(Clone, Debug)]
+pub struct TournamentSelection {
+    size: usize,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+
+        Self { size }
     }
 }
 
-pub struct GeneticAlgorithm<S> {
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Individual,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+
+        (0..self.size)
+            .map(|_| &population[rng.gen_range(0..population.len())])
+            .max_by(|a, b| a.fitness().total_cmp(&b.fitness()))
+            .unwrap()
+    }
+}
+
+/// Methods are stored as they are, so that the compiler can inline them
+/// into `evolve()`; when they have to be picked at runtime, there's
+/// `GeneticAlgorithm::boxed()`.
+pub struct GeneticAlgorithm<S, C = Box<dyn CrossoverMethod>, M = Box<dyn MutationMethod>> {
     selection_method: S,
-    crossover_method: Box<dyn CrossoverMethod>,
-    mutation_method: Box<dyn MutationMethod>,
+    crossover_method: C,
+    mutation_method: M,
 }
 
 impl<S> GeneticAlgorithm<S>
 where
     S: SelectionMethod,
 {
-    pub fn new(
+    /// Creates a genetic algorithm with crossover and mutation methods
+    /// behind pointers, so that its type doesn't depend on them.
+    ///
+    /// It costs an indirect call per child, which (see `benches/evolve.rs`)
+    /// is lost in the noise next to the per-gene work.
+    pub fn boxed(
         selection_method: S,
         crossover_method: impl CrossoverMethod + 'static,
         mutation_method: impl MutationMethod + 'static,
     ) -> Self {
+        Self::new(
+            selection_method,
+            Box::new(crossover_method),
+            Box::new(mutation_method),
+        )
+    }
+}
+
+impl<S, C, M> GeneticAlgorithm<S, C, M> {
+    pub fn new(selection_method: S, crossover_method: C, mutation_method: M) -> Self {
         Self {
             selection_method,
-            crossover_method: Box::new(crossover_method),
-            mutation_method: Box::new(mutation_method),
+            crossover_method,
+            mutation_method,
         }
     }
 
     pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
     where
         I: Individual,
+        S: SelectionMethod,
+        C: CrossoverMethod,
+        M: MutationMethod,
+    {
+        self.evolve_into(rng, population, &mut Vec::new())
+    }
+
+    /// Like `evolve()`, but builds children inside chromosomes taken from
+    /// `recycled` (e.g. those of the previous generation, given back by
+    /// `Individual::into_chromosome()`), allocating new ones only once it
+    /// runs out of them.
+    pub fn evolve_into<I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &[I],
+        recycled: &mut Vec<Chromosome>,
+    ) -> (Vec<I>, Statistics)
+    where
+        I: Individual,
+        S: SelectionMethod,
+        C: CrossoverMethod,
+        M: MutationMethod,
     {
         assert!(!population.is_empty());
 
+        let mut select = self.selection_method.selector(population);
+
         let new_population = (0..population.len())
             .map(|_| {
                 // selection
-                let parent_a = self.selection_method.select(rng, population).chromosome();
-                let parent_b = self.selection_method.select(rng, population).chromosome();
+                let parent_a = select(rng).chromosome();
+                let parent_b = select(rng).chromosome();
 
                 // crossover
-                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+                let mut child = recycled
+                    .pop()
+                    .unwrap_or_else(|| std::iter::empty().collect());
+
+                self.crossover_method
+                    .crossover_into(rng, parent_a, parent_b, &mut child);
 
                 // mutation
                 self.mutation_method.mutate(rng, &mut child);
@@ -89,12 +271,30 @@ where
     }
 }
 
+/// Storage of a chromosome's genes - `Vec<Float>` by default, or
+/// `InlineGenes` for short genomes (e.g. of evolvable traits), sparing a
+/// heap allocation per chromosome.
+///
+/// Methods work on the default storage, so that their traits don't have to
+/// mention it; the rest of `Chromosome` works with any.
+pub trait Genes:
+    Clone
+    + Debug
+    + Deref<Target = [Float]>
+    + DerefMut
+    + FromIterator<Float>
+    + IntoIterator<Item = Float>
+{
+}
+
+impl Genes for Vec<Float> {}
+
 #[derive(Clone, Debug)]
-pub struct Chromosome {
-    genes: Vec<f32>,
+pub struct Chromosome<G = Vec<Float>> {
+    genes: G,
 }
 
-impl Chromosome {
+impl<G: Genes> Chromosome<G> {
     pub fn len(&self) -> usize {
         self.genes.len()
     }
@@ -103,40 +303,102 @@ impl Chromosome {
         self.genes.len() == 0
     }
 
-    pub fn iter(&self) -> impl Iterator<Item = &f32> {
+    pub fn iter(&self) -> impl Iterator<Item = &Float> {
         self.genes.iter()
     }
 
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut f32> {
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Float> {
         self.genes.iter_mut()
     }
+
+    /// Returns euclidean distance between genes of both chromosomes - e.g.
+    /// to tell how diverse a population is.
+    pub fn distance(&self, other: &Self) -> Float {
+        gene_ops::distance_squared(&self.genes, &other.genes).sqrt()
+    }
+
+    /// Keeps all the genes within `min..=max`.
+    pub fn clamp(&mut self, min: Float, max: Float) {
+        gene_ops::clamp(&mut self.genes, min, max);
+    }
 }
 
-impl Index<usize> for Chromosome {
-    type Output = f32;
+impl<G: Genes> Index<usize> for Chromosome<G> {
+    type Output = Float;
 
     fn index(&self, index: usize) -> &Self::Output {
         &self.genes[index]
     }
 }
 
-impl FromIterator<f32> for Chromosome {
-    fn from_iter<T: IntoIterator<Item = f32>>(iter: T) -> Self {
+impl<G: Genes> FromIterator<Float> for Chromosome<G> {
+    fn from_iter<T: IntoIterator<Item = Float>>(iter: T) -> Self {
         Self {
             genes: iter.into_iter().collect(),
         }
     }
 }
 
-impl IntoIterator for Chromosome {
-    type Item = f32;
-    type IntoIter = std::vec::IntoIter<f32>;
+impl<G: Genes> IntoIterator for Chromosome<G> {
+    type Item = Float;
+    type IntoIter = G::IntoIter;
 
     fn into_iter(self) -> Self::IntoIter {
         self.genes.into_iter()
     }
 }
 
+/// Genes stored right inside the chromosome, up to `N` of them; collecting
+/// more panics.
+#[derive(Clone, Copy, Debug)]
+pub struct InlineGenes<const N: usize> {
+    genes: [Float; N],
+    len: usize,
+}
+
+impl<const N: usize> Genes for InlineGenes<N> {}
+
+impl<const N: usize> Deref for InlineGenes<N> {
+    type Target = [Float];
+
+    fn deref(&self) -> &Self::Target {
+        &self.genes[..self.len]
+    }
+}
+
+impl<const N: usize> DerefMut for InlineGenes<N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.genes[..self.len]
+    }
+}
+
+impl<const N: usize> FromIterator<Float> for InlineGenes<N> {
+    fn from_iter<T: IntoIterator<Item = Float>>(iter: T) -> Self {
+        let mut this = Self {
+            genes: [0.0; N],
+            len: 0,
+        };
+
+        for gene in iter {
+            assert!(this.len < N, "got more than {N} genes");
+
+            this.genes[this.len] = gene;
+            this.len += 1;
+        }
+
+        this
+    }
+}
+
+impl<const N: usize> IntoIterator for InlineGenes<N> {
+    type Item = Float;
+    type IntoIter = std::iter::Take<std::array::IntoIter<Float, N>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.genes.into_iter().take(self.len)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct UniformCrossover;
 
@@ -147,24 +409,205 @@ impl CrossoverMethod for UniformCrossover {
         parent_a: &Chromosome,
         parent_b: &Chromosome,
     ) -> Chromosome {
+        let mut child = std::iter::empty().collect();
+        self.crossover_into(rng, parent_a, parent_b, &mut child);
+        child
+    }
+
+    fn crossover_into(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+        child: &mut Chromosome,
+    ) {
         assert_eq!(parent_a.len(), parent_b.len());
 
-        parent_a
-            .iter()
-            .zip(parent_b.iter())
-            .map(|(&a, &b)| if rng.gen_bool(0.5) { a } else { b })
-            .collect()
+        child.genes.clear();
+
+        child
+            .genes
+            .extend(parent_a.iter().zip(parent_b.iter()).map(|(&a, &b)| {
+                if rng.gen_bool(0.5) {
+                    a
+                } else {
+                    b
+                }
+            }));
+    }
+}
+
+/// Creates children in between their parents - at a random point of the
+/// line going from one parent to the other.
+#[derive(Clone, Debug)]
+pub struct BlendCrossover;
+
+impl CrossoverMethod for BlendCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        let mut child = std::iter::empty().collect();
+        self.crossover_into(rng, parent_a, parent_b, &mut child);
+        child
+    }
+
+    fn crossover_into(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+        child: &mut Chromosome,
+    ) {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        child.genes.clear();
+        child.genes.extend(std::iter::repeat_n(0.0, parent_a.len()));
+
+        gene_ops::lerp(
+            &mut child.genes,
+            &parent_a.genes,
+            &parent_b.genes,
+            rng.gen(),
+        );
+    }
+}
+
+/// Describes how a chromosome's flat gene vector splits into consecutive
+/// segments - e.g. one segment per each layer of a neural network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SegmentLayout {
+    lengths: Vec<usize>,
+}
+
+impl SegmentLayout {
+    pub fn new(lengths: Vec<usize>) -> Self {
+        Self { lengths }
+    }
+
+    /// Returns the number of segments.
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    /// Returns the number of genes a chromosome of this layout has.
+    pub fn gene_count(&self) -> usize {
+        self.lengths.iter().sum()
+    }
+
+    /// Returns ranges of genes belonging to each segment.
+    pub fn segments(&self) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.lengths.iter().scan(0, |start, &len| {
+            let range = *start..(*start + len);
+            *start += len;
+            Some(range)
+        })
+    }
+
+    /// Returns index of the segment given gene belongs to.
+    pub fn segment_of(&self, gene: usize) -> Option<usize> {
+        self.segments().position(|segment| segment.contains(&gene))
+    }
+}
+
+impl FromIterator<usize> for SegmentLayout {
+    fn from_iter<T: IntoIterator<Item = usize>>(iter: T) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+/// Crossover that inherits each segment as a whole from one of the
+/// parents, so that e.g. entire layers of a network are passed down
+/// together instead of being shuffled gene-by-gene.
+#[derive(Clone, Debug)]
+pub struct SegmentedCrossover {
+    layout: SegmentLayout,
+}
+
+impl SegmentedCrossover {
+    pub fn new(layout: SegmentLayout) -> Self {
+        Self { layout }
+    }
+}
+
+impl CrossoverMethod for SegmentedCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        let mut child = std::iter::empty().collect();
+        self.crossover_into(rng, parent_a, parent_b, &mut child);
+        child
+    }
+
+    fn crossover_into(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+        child: &mut Chromosome,
+    ) {
+        assert_eq!(parent_a.len(), parent_b.len());
+        assert_eq!(parent_a.len(), self.layout.gene_count());
+
+        child.genes.clear();
+
+        for segment in self.layout.segments() {
+            let parent = if rng.gen_bool(0.5) {
+                parent_a
+            } else {
+                parent_b
+            };
+
+            child.genes.extend(parent.genes[segment].iter().copied());
+        }
+    }
+}
+
+/// Mutation that applies a different method to each segment - e.g. to
+/// mutate output layers more gently than hidden ones.
+pub struct SegmentedMutation {
+    layout: SegmentLayout,
+    methods: Vec<Box<dyn MutationMethod>>,
+}
+
+impl SegmentedMutation {
+    pub fn new(layout: SegmentLayout, methods: Vec<Box<dyn MutationMethod>>) -> Self {
+        assert_eq!(layout.len(), methods.len());
+
+        Self { layout, methods }
+    }
+}
+
+impl MutationMethod for SegmentedMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        assert_eq!(child.len(), self.layout.gene_count());
+
+        for (segment, method) in self.layout.segments().zip(&self.methods) {
+            let mut genes: Chromosome = child.genes[segment.clone()].iter().copied().collect();
+
+            method.mutate(rng, &mut genes);
+            child.genes[segment].copy_from_slice(&genes.genes);
+        }
     }
 }
 
 #[derive(Clone, Debug)]
 pub struct GaussianMutation {
-    chance: f32,
-    coeff: f32,
+    chance: Float,
+    coeff: Float,
 }
 
 impl GaussianMutation {
-    pub fn new(chance: f32, coeff: f32) -> Self {
+    pub fn new(chance: Float, coeff: Float) -> Self {
         assert!(chance >= 0.0);
         assert!(chance <= 1.0);
 
@@ -174,19 +617,67 @@ impl GaussianMutation {
 
 impl MutationMethod for GaussianMutation {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        // No-op for the `f64` precision
+        #[allow(clippy::unnecessary_cast)]
+        let chance = self.chance as f64;
+
         for gene in child.iter_mut() {
             let sign = if rng.gen_bool(0.5) { -1.0 } else { 1.0 };
 
-            if rng.gen_bool(self.chance as f64) {
-                *gene += sign * self.coeff * rng.gen::<f32>();
+            if rng.gen_bool(chance) {
+                *gene += sign * self.coeff * rng.gen::<Float>();
             }
         }
     }
 }
 
+/// Nudges every gene by noise drawn from the normal distribution with
+/// standard deviation of `sigma`.
+#[derive(Clone, Debug)]
+pub struct GaussianPerturbation {
+    sigma: Float,
+
+    /// Noise of the chromosome being mutated, kept around between children
+    noise: RefCell<Vec<Float>>,
+}
+
+impl GaussianPerturbation {
+    pub fn new(sigma: Float) -> Self {
+        assert!(sigma >= 0.0);
+
+        Self {
+            sigma,
+            noise: Default::default(),
+        }
+    }
+}
+
+impl MutationMethod for GaussianPerturbation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        // No-op for the `f64` precision
+        #[allow(clippy::unnecessary_cast)]
+        let tau = std::f64::consts::TAU as Float;
+
+        let mut noise = self.noise.borrow_mut();
+        noise.clear();
+
+        // Box-Muller transform, giving two samples per pair of uniform ones
+        while noise.len() < child.len() {
+            let radius = (-2.0 * (1.0 - rng.gen::<Float>()).ln()).sqrt();
+            let angle = tau * rng.gen::<Float>();
+
+            noise.extend([radius * angle.cos(), radius * angle.sin()]);
+        }
+
+        noise.truncate(child.len());
+        gene_ops::add_scaled(&mut child.genes, &noise, self.sigma);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use approx::assert_relative_eq;
     use rand::SeedableRng;
     use rand_chacha::ChaCha8Rng;
     use std::collections::BTreeMap;
@@ -195,7 +686,7 @@ mod tests {
     #[derive(Clone, Debug, PartialEq)]
     enum TestIndividual {
         WithChromosome { chromosome: Chromosome },
-        WithFitness { fitness: f32 },
+        WithFitness { fitness: Float },
     }
 
     impl PartialEq for Chromosome {
@@ -205,7 +696,7 @@ mod tests {
     }
 
     impl TestIndividual {
-        fn new(fitness: f32) -> Self {
+        fn new(fitness: Float) -> Self {
             Self::WithFitness { fitness }
         }
     }
@@ -215,7 +706,7 @@ mod tests {
             Self::WithChromosome { chromosome }
         }
 
-        fn fitness(&self) -> f32 {
+        fn fitness(&self) -> Float {
             match self {
                 Self::WithChromosome { chromosome } => chromosome.iter().sum(),
                 Self::WithFitness { fitness } => *fitness,
@@ -253,16 +744,60 @@ mod tests {
             *actual_histogram.entry(fitness).or_insert(0) += 1;
         }
 
+        #[cfg(not(feature = "f64"))]
         let expected_histogram = BTreeMap::from_iter([(1, 98), (2, 202), (3, 278), (4, 422)]);
 
+        #[cfg(feature = "f64")]
+        let expected_histogram = BTreeMap::from_iter([(1, 98), (2, 191), (3, 280), (4, 431)]);
+
+        assert_eq!(actual_histogram, expected_histogram);
+
+        // Selector makes the same choices, just faster
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut select = RouletteWheelSelection.selector(&population);
+        let mut selector_histogram = BTreeMap::new();
+
+        for _ in 0..1000 {
+            *selector_histogram
+                .entry(select(&mut rng).fitness() as i32)
+                .or_insert(0) += 1;
+        }
+
+        assert_eq!(selector_histogram, expected_histogram);
+    }
+
+    #[test]
+    fn tournament_selection() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let population = vec![
+            TestIndividual::new(2.0),
+            TestIndividual::new(1.0),
+            TestIndividual::new(4.0),
+            TestIndividual::new(3.0),
+        ];
+
+        let mut actual_histogram = BTreeMap::new();
+
+        for _ in 0..1000 {
+            let fitness = TournamentSelection::new(2)
+                .select(&mut rng, &population)
+                .fitness() as i32;
+
+            *actual_histogram.entry(fitness).or_insert(0) += 1;
+        }
+
+        // Worst one wins only when it gets drawn twice
+        let expected_histogram = BTreeMap::from_iter([(1, 75), (2, 177), (3, 300), (4, 448)]);
+
         assert_eq!(actual_histogram, expected_histogram);
     }
 
     #[test]
     fn uniform_crossover() {
         let mut rng = ChaCha8Rng::from_seed(Default::default());
-        let parent_a = (1..=100).map(|n| n as f32).collect();
-        let parent_b = (1..=100).map(|n| -n as f32).collect();
+        let parent_a = (1..=100).map(|n| n as Float).collect();
+        let parent_b = (1..=100).map(|n| -n as Float).collect();
         let child = UniformCrossover.crossover(&mut rng, &parent_a, &parent_b);
 
         let diff_a = child.iter().zip(parent_a).filter(|(c, p)| *c != p).count();
@@ -272,10 +807,97 @@ mod tests {
         assert_eq!(diff_b, 51);
     }
 
+    mod segment_layout {
+        use super::*;
+
+        fn layout() -> SegmentLayout {
+            [2, 3, 1].into_iter().collect()
+        }
+
+        #[test]
+        fn segments() {
+            let actual: Vec<_> = layout().segments().collect();
+
+            assert_eq!(actual, vec![0..2, 2..5, 5..6]);
+            assert_eq!(layout().gene_count(), 6);
+        }
+
+        #[test]
+        fn segment_of() {
+            let actual: Vec<_> = (0..7).map(|gene| layout().segment_of(gene)).collect();
+
+            assert_eq!(
+                actual,
+                vec![Some(0), Some(0), Some(1), Some(1), Some(1), Some(2), None]
+            );
+        }
+
+        #[test]
+        fn segmented_crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let parent_a: Chromosome = (1..=6).map(|n| n as Float).collect();
+            let parent_b: Chromosome = (1..=6).map(|n| -n as Float).collect();
+
+            let child = SegmentedCrossover::new(layout()).crossover(&mut rng, &parent_a, &parent_b);
+
+            for segment in layout().segments() {
+                let genes = &child.genes[segment];
+
+                assert!(
+                    genes.iter().all(|gene| *gene > 0.0) || genes.iter().all(|gene| *gene < 0.0)
+                );
+            }
+        }
+
+        #[test]
+        fn segmented_crossover_into() {
+            let crossover = SegmentedCrossover::new(layout());
+            let parent_a: Chromosome = (1..=6).map(|n| n as Float).collect();
+            let parent_b: Chromosome = (1..=6).map(|n| -n as Float).collect();
+
+            let expected = crossover.crossover(
+                &mut ChaCha8Rng::from_seed(Default::default()),
+                &parent_a,
+                &parent_b,
+            );
+
+            let mut child = vec![9.0; 2].into_iter().collect();
+
+            crossover.crossover_into(
+                &mut ChaCha8Rng::from_seed(Default::default()),
+                &parent_a,
+                &parent_b,
+                &mut child,
+            );
+
+            assert_eq!(child, expected);
+        }
+
+        #[test]
+        fn segmented_mutation() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child: Chromosome = (1..=6).map(|n| n as Float).collect();
+
+            SegmentedMutation::new(
+                layout(),
+                vec![
+                    Box::new(GaussianMutation::new(0.0, 0.0)),
+                    Box::new(GaussianMutation::new(1.0, 0.5)),
+                    Box::new(GaussianMutation::new(0.0, 0.0)),
+                ],
+            )
+            .mutate(&mut rng, &mut child);
+
+            assert_eq!(&child.genes[0..2], &[1.0, 2.0]);
+            assert_ne!(&child.genes[2..5], &[3.0, 4.0, 5.0]);
+            assert_eq!(&child.genes[5..6], &[6.0]);
+        }
+    }
+
     mod gaussian_mutation {
         use super::*;
 
-        fn actual(chance: f32, coeff: f32) -> Vec<f32> {
+        fn actual(chance: Float, coeff: Float) -> Vec<Float> {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
             let mut child = vec![1.0, 2.0, 3.0, 4.0, 5.0].into_iter().collect();
 
@@ -285,9 +907,10 @@ mod tests {
         }
 
         mod given_zero_chance {
+            use crate::Float;
             use approx::assert_relative_eq;
 
-            fn actual(coeff: f32) -> Vec<f32> {
+            fn actual(coeff: Float) -> Vec<Float> {
                 super::actual(0.0, coeff)
             }
 
@@ -317,9 +940,10 @@ mod tests {
         }
 
         mod given_fifty_fifty_chance {
+            use crate::Float;
             use approx::assert_relative_eq;
 
-            fn actual(coeff: f32) -> Vec<f32> {
+            fn actual(coeff: Float) -> Vec<Float> {
                 super::actual(0.5, coeff)
             }
 
@@ -341,17 +965,28 @@ mod tests {
                 #[test]
                 fn slightly_change_the_original_chromosome() {
                     let actual = actual(0.5);
+                    #[cfg(not(feature = "f64"))]
                     let expected = vec![1.0, 1.7756249, 3.0, 4.1596804, 5.0];
 
+                    #[cfg(feature = "f64")]
+                    let expected = vec![
+                        1.0,
+                        1.8721990071413166,
+                        2.8691555244472555,
+                        4.0,
+                        5.298544100860873,
+                    ];
+
                     assert_relative_eq!(actual.as_slice(), expected.as_slice());
                 }
             }
         }
 
         mod given_max_chance {
+            use crate::Float;
             use approx::assert_relative_eq;
 
-            fn actual(coeff: f32) -> Vec<f32> {
+            fn actual(coeff: Float) -> Vec<Float> {
                 super::actual(1.0, coeff)
             }
 
@@ -373,8 +1008,18 @@ mod tests {
                 #[test]
                 fn entirely_change_the_original_chromosome() {
                     let actual = actual(0.5);
+                    #[cfg(not(feature = "f64"))]
                     let expected = vec![1.4545316, 2.1162078, 2.7756248, 3.9505124, 4.638691];
 
+                    #[cfg(feature = "f64")]
+                    let expected = vec![
+                        1.3157122470332616,
+                        1.9412044901942502,
+                        2.950512490537528,
+                        3.8691555244472555,
+                        4.660843312574409,
+                    ];
+
                     assert_relative_eq!(actual.as_slice(), expected.as_slice());
                 }
             }
@@ -383,7 +1028,7 @@ mod tests {
 
     #[test]
     fn genetic_algorithm() {
-        fn individual(genes: &[f32]) -> TestIndividual {
+        fn individual(genes: &[Float]) -> TestIndividual {
             TestIndividual::create(genes.iter().cloned().collect())
         }
 
@@ -403,9 +1048,10 @@ mod tests {
         ];
 
         for _ in 0..10 {
-            population = ga.evolve(&mut rng, &population);
+            population = ga.evolve(&mut rng, &population).0;
         }
 
+        #[cfg(not(feature = "f64"))]
         let expected_population = vec![
             individual(&[0.4476949, 2.0648358, 4.3058133]),
             individual(&[1.2126867, 1.5538777, 2.886911]),
@@ -413,15 +1059,165 @@ mod tests {
             individual(&[0.95909685, 2.4618788, 4.024733]),
         ];
 
+        #[cfg(feature = "f64")]
+        let expected_population = vec![
+            individual(&[1.064055903649652, 2.0072487420816967, 5.077696026901516]),
+            individual(&[1.1510998995734667, 1.1675076280104353, 4.129851371695811]),
+            individual(&[1.4931699769516658, 1.9945567776389714, 5.077696026901516]),
+            individual(&[1.5463183523168809, 1.7135153225819943, 5.077696026901516]),
+        ];
+
         assert_eq!(population, expected_population);
     }
+
+    #[test]
+    fn boxed_genetic_algorithm() {
+        fn evolve<C, M>(ga: &GeneticAlgorithm<RouletteWheelSelection, C, M>) -> Vec<TestIndividual>
+        where
+            C: CrossoverMethod,
+            M: MutationMethod,
+        {
+            let population: Vec<_> = (0..4)
+                .map(|idx| {
+                    TestIndividual::create((0..3).map(|gene| (idx + gene) as Float).collect())
+                })
+                .collect();
+
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            ga.evolve(&mut rng, &population).0
+        }
+
+        let ga = GeneticAlgorithm::new(
+            RouletteWheelSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        let boxed = GeneticAlgorithm::boxed(
+            RouletteWheelSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        assert_eq!(evolve(&ga), evolve(&boxed));
+    }
+
+    #[test]
+    fn blend_crossover() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let parent_a: Chromosome = (0..20).map(|n| n as Float).collect();
+        let parent_b: Chromosome = (0..20).map(|n| -n as Float).collect();
+        let child = BlendCrossover.crossover(&mut rng, &parent_a, &parent_b);
+
+        // All genes come from the same point between the parents
+        let t = (1.0 - child[1]) / 2.0;
+
+        assert!(t > 0.0 && t < 1.0);
+
+        for idx in 0..20 {
+            assert_relative_eq!(child[idx], idx as Float * (1.0 - 2.0 * t), epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn gaussian_perturbation() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut child: Chromosome = vec![1.0; 10_001].into_iter().collect();
+
+        GaussianPerturbation::new(0.0).mutate(&mut rng, &mut child);
+
+        assert!(child.iter().all(|&gene| gene == 1.0));
+
+        GaussianPerturbation::new(0.5).mutate(&mut rng, &mut child);
+
+        let n = child.len() as Float;
+        let mean = child.iter().sum::<Float>() / n;
+        let variance = child
+            .iter()
+            .map(|gene| (gene - mean).powi(2))
+            .sum::<Float>()
+            / n;
+
+        assert_relative_eq!(mean, 1.0, epsilon = 0.02);
+        assert_relative_eq!(variance.sqrt(), 0.5, epsilon = 0.02);
+    }
+
+    #[test]
+    fn distance_and_clamp() {
+        let mut a: Chromosome = vec![0.0, 3.0, -1.0].into_iter().collect();
+        let b: Chromosome = vec![4.0, 0.0, -1.0].into_iter().collect();
+
+        assert_relative_eq!(a.distance(&b), 5.0);
+
+        a.clamp(-0.5, 2.0);
+
+        assert_eq!(a.iter().copied().collect::<Vec<_>>(), [0.0, 2.0, -0.5]);
+    }
+
+    #[test]
+    fn evolve_into() {
+        let population: Vec<_> = (0..4)
+            .map(|idx| TestIndividual::create((0..3).map(|gene| (idx * gene) as Float).collect()))
+            .collect();
+
+        let ga = GeneticAlgorithm::new(
+            RouletteWheelSelection,
+            UniformCrossover,
+            GaussianMutation::new(0.5, 0.5),
+        );
+
+        let expected = ga
+            .evolve(&mut ChaCha8Rng::from_seed(Default::default()), &population)
+            .0;
+
+        // Recycled genes get overwritten, whatever they were
+        let mut recycled: Vec<Chromosome> =
+            (0..3).map(|_| vec![9.0; 3].into_iter().collect()).collect();
+
+        let actual = ga
+            .evolve_into(
+                &mut ChaCha8Rng::from_seed(Default::default()),
+                &population,
+                &mut recycled,
+            )
+            .0;
+
+        assert_eq!(actual, expected);
+        assert!(recycled.is_empty());
+    }
+
+    #[test]
+    fn inline_genes() {
+        let genes = [1.0, -2.0, 3.0];
+
+        let heap: Chromosome = genes.into_iter().collect();
+        let mut inline: Chromosome<InlineGenes<4>> = genes.into_iter().collect();
+
+        assert_eq!(inline.len(), 3);
+        assert_eq!(inline[1], -2.0);
+
+        let other: Chromosome<InlineGenes<4>> = [0.0; 3].into_iter().collect();
+        assert_relative_eq!(
+            inline.distance(&other),
+            heap.distance(&vec![0.0; 3].into_iter().collect())
+        );
+
+        inline.clamp(-1.0, 1.0);
+        assert_eq!(inline.into_iter().collect::<Vec<_>>(), [1.0, -1.0, 1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "got more than 2 genes")]
+    fn inline_genes_overflow() {
+        let _: Chromosome<InlineGenes<2>> = [1.0, 2.0, 3.0].into_iter().collect();
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct Statistics {
-    pub min_fitness: f32,
-    pub max_fitness: f32,
-    pub avg_fitness: f32,
+    pub min_fitness: Float,
+    pub max_fitness: Float,
+    pub avg_fitness: Float,
 }
 
 impl Statistics {
@@ -446,7 +1242,7 @@ impl Statistics {
         Self {
             min_fitness,
             max_fitness,
-            avg_fitness: sum_fitness / (population.len() as f32),
+            avg_fitness: sum_fitness / (population.len() as Float),
         }
     }
 }
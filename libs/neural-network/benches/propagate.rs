@@ -0,0 +1,61 @@
+//! Measures `Network::propagate()` and `Network::propagate_with()` for a
+//! few network sizes.
+//!
+//! Compare the default (scalar) and the vectorized path with:
+//!
+//! ```text
+//! cargo bench -p lib-neural-network --bench propagate
+//! cargo bench -p lib-neural-network --bench propagate --features simd
+//! ```
+
+use lib_neural_network::{Float, LayerTopology, Network, PropagationScratch};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::hint::black_box;
+use std::time::Instant;
+
+const ITERATIONS: u32 = 20_000;
+
+fn main() {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+    for &(inputs, hidden, outputs) in &[(9, 18, 2), (32, 64, 4), (128, 256, 8)] {
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(inputs),
+                LayerTopology::new(hidden),
+                LayerTopology::new(outputs),
+            ],
+        );
+
+        let input: Vec<Float> = (0..inputs).map(|_| rng.gen()).collect();
+
+        let started_at = Instant::now();
+
+        for _ in 0..ITERATIONS {
+            black_box(network.propagate(black_box(input.clone())));
+        }
+
+        let elapsed = started_at.elapsed();
+
+        println!(
+            "propagate {inputs}x{hidden}x{outputs}: {:>10.1} ns/iter",
+            elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        );
+
+        let mut scratch = PropagationScratch::new();
+        let started_at = Instant::now();
+
+        for _ in 0..ITERATIONS {
+            black_box(network.propagate_with(black_box(input.iter().copied()), &mut scratch));
+        }
+
+        let elapsed = started_at.elapsed();
+
+        println!(
+            "propagate_with {inputs}x{hidden}x{outputs}: {:>10.1} ns/iter",
+            elapsed.as_nanos() as f64 / ITERATIONS as f64,
+        );
+    }
+}
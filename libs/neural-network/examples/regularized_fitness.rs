@@ -0,0 +1,94 @@
+//! Evolves networks approximating `y = 0.5 * x`, with an L2 penalty
+//! wired into `Individual::fitness()` so that the evolved weights stay
+//! small.
+//!
+//! ```text
+//! cargo run -p lib-neural-network --example regularized_fitness
+//! ```
+
+use lib_genetic_algorithm as ga;
+use lib_neural_network as nn;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+const PENALTY: nn::Regularization = nn::Regularization::L2(0.01);
+
+fn topology() -> [nn::LayerTopology; 3] {
+    [
+        nn::LayerTopology::new(1),
+        nn::LayerTopology::new(4),
+        nn::LayerTopology::new(1),
+    ]
+}
+
+struct NetworkIndividual {
+    chromosome: ga::Chromosome,
+    fitness: ga::Float,
+}
+
+impl ga::Individual for NetworkIndividual {
+    fn create(chromosome: ga::Chromosome) -> Self {
+        // Genes and weights can be of different precisions, depending on
+        // the features enabled in both crates
+        let weights = chromosome.iter().map(|&gene| gene as nn::Float);
+        let network = nn::Network::from_weights(&topology(), weights);
+
+        let error: nn::Float = (0..10)
+            .map(|n| {
+                let x = n as nn::Float / 10.0;
+                let y = network.propagate(vec![x])[0];
+
+                (y - 0.5 * x).abs()
+            })
+            .sum();
+
+        // Roulette wheel selection requires non-negative fitness scores,
+        // so we turn "lower is better" into "higher is better"
+        let fitness = (10.0 - error - network.penalty(PENALTY)).max(0.0);
+
+        Self {
+            chromosome,
+            fitness: fitness as ga::Float,
+        }
+    }
+
+    fn fitness(&self) -> ga::Float {
+        self.fitness
+    }
+
+    fn chromosome(&self) -> &ga::Chromosome {
+        &self.chromosome
+    }
+}
+
+fn main() {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+    let ga = ga::GeneticAlgorithm::new(
+        ga::RouletteWheelSelection,
+        ga::UniformCrossover,
+        ga::GaussianMutation::new(0.1, 0.3),
+    );
+
+    let mut population: Vec<_> = (0..50)
+        .map(|_| {
+            let network = nn::Network::random(&mut rng, &topology());
+            let genes = network.weights().map(|weight| weight as ga::Float);
+
+            <NetworkIndividual as ga::Individual>::create(genes.collect())
+        })
+        .collect();
+
+    for generation in 0..=100 {
+        let (evolved, stats) = ga.evolve(&mut rng, &population);
+
+        if generation % 20 == 0 {
+            println!(
+                "generation {generation:>3}: min={:.2}, max={:.2}, avg={:.2}",
+                stats.min_fitness, stats.max_fitness, stats.avg_fitness,
+            );
+        }
+
+        population = evolved;
+    }
+}
@@ -0,0 +1,135 @@
+use crate::*;
+
+/// Function applied to each neuron's output (i.e. the weighted sum of its
+/// inputs plus its bias).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Activation {
+    /// f(x) = x
+    Identity,
+
+    /// f(x) = max(x, 0)
+    #[default]
+    Relu,
+
+    /// f(x) = tanh(x), ranging over (-1, 1) - handy for outputs that
+    /// steer in both directions.
+    Tanh,
+
+    /// f(x) = 1 / (1 + e^-x), ranging over (0, 1)
+    Sigmoid,
+}
+
+impl Activation {
+    pub fn apply(self, x: Float) -> Float {
+        match self {
+            Activation::Identity => x,
+            Activation::Relu => x.max(0.0),
+            Activation::Tanh => 1.0 - 2.0 / (exp(2.0 * x) + 1.0),
+            Activation::Sigmoid => 1.0 / (1.0 + exp(-x)),
+        }
+    }
+
+    /// Returns f'(x), expressed through y = f(x) - which is what
+    /// backpropagation has at hand.
+    pub(crate) fn derivative(self, y: Float) -> Float {
+        match self {
+            Activation::Identity => 1.0,
+            Activation::Relu => {
+                if y > 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Activation::Tanh => 1.0 - y * y,
+            Activation::Sigmoid => y * (1.0 - y),
+        }
+    }
+}
+
+#[cfg(all(feature = "std", not(feature = "deterministic")))]
+fn exp(x: Float) -> Float {
+    x.exp()
+}
+
+#[cfg(any(not(feature = "std"), feature = "deterministic"))]
+fn exp(x: Float) -> Float {
+    portable_exp(x)
+}
+
+/// e^x using only basic arithmetic, so that it's available without `std`
+/// and gives the same results on every platform (unlike `exp()`, which is
+/// provided by the platform's math library).
+///
+/// Inputs are clamped to ±700, which is far beyond where `Tanh` and
+/// `Sigmoid` saturate anyway.
+#[cfg(any(test, not(feature = "std"), feature = "deterministic"))]
+fn portable_exp(x: Float) -> Float {
+    use core::f64::consts::{LN_2, LOG2_E};
+
+    // No-op for the `f64` precision
+    #[allow(clippy::unnecessary_cast)]
+    let x = (x as f64).clamp(-700.0, 700.0);
+
+    // e^x = 2^k * e^r, where |r| < ln 2
+    let k = (x * LOG2_E) as i64;
+    let r = x - k as f64 * LN_2;
+
+    let mut term = 1.0;
+    let mut sum = 1.0;
+
+    for n in 1..20 {
+        term *= r / n as f64;
+        sum += term;
+    }
+
+    let scale = f64::from_bits(((k + 1023) as u64) << 52);
+
+    (sum * scale) as Float
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn apply() {
+        assert_relative_eq!(Activation::Identity.apply(-2.0), -2.0);
+        assert_relative_eq!(Activation::Relu.apply(-2.0), 0.0);
+        assert_relative_eq!(Activation::Relu.apply(2.0), 2.0);
+        assert_relative_eq!(Activation::Tanh.apply(0.5), 0.46211716, max_relative = 1e-6);
+        assert_relative_eq!(Activation::Tanh.apply(-50.0), -1.0);
+        assert_relative_eq!(Activation::Sigmoid.apply(0.0), 0.5);
+        assert_relative_eq!(
+            Activation::Sigmoid.apply(2.0),
+            0.880797,
+            max_relative = 1e-6
+        );
+    }
+
+    #[test]
+    fn derivative() {
+        let x: Float = 0.3;
+        let h: Float = 1e-3;
+
+        for activation in [
+            Activation::Identity,
+            Activation::Relu,
+            Activation::Tanh,
+            Activation::Sigmoid,
+        ] {
+            let expected = (activation.apply(x + h) - activation.apply(x - h)) / (2.0 * h);
+            let actual = activation.derivative(activation.apply(x));
+
+            assert_relative_eq!(actual, expected, max_relative = 1e-2);
+        }
+    }
+
+    #[test]
+    fn portable_exp_matches_std() {
+        for x in [-30.0, -2.5, -0.1, 0.0, 0.7, 1.0, 4.2, 20.0, 80.0] {
+            assert_relative_eq!(portable_exp(x), Float::exp(x), max_relative = 1e-6);
+        }
+    }
+}
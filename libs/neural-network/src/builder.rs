@@ -0,0 +1,199 @@
+use crate::*;
+
+/// Fluent alternative to spelling out `LayerTopology` slices:
+///
+/// ```
+/// # use lib_neural_network::*;
+/// # #[cfg(feature = "rand")] {
+/// # use rand::SeedableRng;
+/// # let mut rng = rand_chacha::ChaCha8Rng::from_seed(Default::default());
+/// let network = Network::builder()
+///     .input(9)
+///     .hidden(12, Activation::Relu)
+///     .output(2, Activation::Tanh)
+///     .bias(Bias::Zero)
+///     .build(&mut rng);
+///
+/// assert_eq!(network.input_size(), 9);
+/// assert_eq!(network.output_size(), 2);
+/// # }
+/// ```
+///
+/// `bias()`, `init()` and `plasticity()` configure the most recently
+/// added layer.
+#[derive(Clone, Debug, Default)]
+pub struct NetworkBuilder {
+    input: Option<usize>,
+    layers: Vec<LayerTopology>,
+    has_output: bool,
+    pub(crate) input_names: Option<Vec<String>>,
+    pub(crate) output_names: Option<Vec<String>>,
+}
+
+impl Network {
+    pub fn builder() -> NetworkBuilder {
+        NetworkBuilder::default()
+    }
+}
+
+impl NetworkBuilder {
+    pub fn input(mut self, neurons: usize) -> Self {
+        self.input = Some(neurons);
+        self.input_names = None;
+        self
+    }
+
+    pub fn hidden(mut self, neurons: usize, activation: Activation) -> Self {
+        self.layers
+            .push(LayerTopology::new(neurons).with_activation(activation));
+
+        self.has_output = false;
+        self.output_names = None;
+        self
+    }
+
+    pub fn output(mut self, neurons: usize, activation: Activation) -> Self {
+        self = self.hidden(neurons, activation);
+        self.has_output = true;
+        self.output_names = None;
+        self
+    }
+
+    pub fn bias(self, bias: Bias) -> Self {
+        self.configure(|layer| layer.with_bias(bias))
+    }
+
+    pub fn init(self, init: WeightInit) -> Self {
+        self.configure(|layer| layer.with_init(init))
+    }
+
+    pub fn plasticity(self, plasticity: bool) -> Self {
+        self.configure(|layer| layer.with_plasticity(plasticity))
+    }
+
+    /// Returns the topology described so far, e.g. to compute
+    /// `Network::weight_count()` or to restore a network with
+    /// `Network::from_weights()`.
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        self.try_topology().unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_topology(&self) -> Result<Vec<LayerTopology>, TopologyError> {
+        let input = self.input.ok_or(TopologyError::MissingInput)?;
+
+        if !self.has_output {
+            return Err(TopologyError::MissingOutput);
+        }
+
+        let layers: Vec<_> = core::iter::once(LayerTopology::new(input))
+            .chain(self.layers.iter().copied())
+            .collect();
+
+        Network::validate(&layers)?;
+
+        Ok(layers)
+    }
+
+    #[cfg(feature = "rand")]
+    pub fn build(&self, rng: &mut dyn rand::RngCore) -> Network {
+        self.try_build(rng).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    #[cfg(feature = "rand")]
+    pub fn try_build(&self, rng: &mut dyn rand::RngCore) -> Result<Network, TopologyError> {
+        let network = Network::try_random(rng, &self.try_topology()?)?;
+
+        // Names were collected together with the layers, so they always fit
+        Ok(self.name(network).unwrap())
+    }
+
+    fn configure(mut self, f: impl FnOnce(LayerTopology) -> LayerTopology) -> Self {
+        let layer = self
+            .layers
+            .last_mut()
+            .expect("add a hidden or an output layer first");
+
+        *layer = f(*layer);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topology() {
+        let actual = Network::builder()
+            .input(3)
+            .hidden(4, Activation::Relu)
+            .plasticity(true)
+            .output(2, Activation::Sigmoid)
+            .bias(Bias::Disabled)
+            .topology();
+
+        assert_eq!(actual.len(), 3);
+        assert_eq!(actual[0].neurons, 3);
+
+        assert_eq!(actual[1].neurons, 4);
+        assert_eq!(actual[1].activation, Activation::Relu);
+        assert!(actual[1].plasticity);
+
+        assert_eq!(actual[2].neurons, 2);
+        assert_eq!(actual[2].activation, Activation::Sigmoid);
+        assert_eq!(actual[2].bias, Bias::Disabled);
+        assert!(!actual[2].plasticity);
+    }
+
+    #[test]
+    fn validation() {
+        let builder = Network::builder().input(3);
+
+        assert_eq!(
+            builder.clone().try_topology(),
+            Err(TopologyError::MissingOutput)
+        );
+
+        assert_eq!(
+            builder
+                .clone()
+                .output(1, Activation::Tanh)
+                .hidden(2, Activation::Relu)
+                .try_topology(),
+            Err(TopologyError::MissingOutput)
+        );
+
+        assert_eq!(
+            builder
+                .clone()
+                .hidden(0, Activation::Relu)
+                .output(1, Activation::Tanh)
+                .try_topology(),
+            Err(TopologyError::EmptyLayer { layer: 1 })
+        );
+
+        assert_eq!(
+            Network::builder()
+                .output(1, Activation::Tanh)
+                .try_topology(),
+            Err(TopologyError::MissingInput)
+        );
+    }
+
+    #[test]
+    fn build() {
+        let network = Network::from_weights(
+            &Network::builder()
+                .input(2)
+                .output(1, Activation::Tanh)
+                .topology(),
+            vec![0.0, 1.0, -1.0],
+        );
+
+        approx::assert_relative_eq!(
+            network.propagate(vec![0.0, 0.5]).as_slice(),
+            [-0.46211716].as_ref(),
+            max_relative = 1e-6
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use crate::*;
+
+/// How `Conv1d` treats the edges of its input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Padding {
+    /// Kernel is applied only where it fits entirely, so each filter
+    /// yields `input_size - kernel_size + 1` outputs.
+    Valid,
+
+    /// Input is padded with zeros, so each filter yields `input_size`
+    /// outputs.
+    #[default]
+    Same,
+}
+
+/// One-dimensional convolution - a set of small kernels slid over the
+/// input, so that neighbouring inputs (e.g. adjacent eye cells) share
+/// weights instead of each being connected separately.
+///
+/// Each filter carries a bias and `kernel_size` weights, which makes for
+/// `filters * (kernel_size + 1)` genes no matter how large the input is.
+/// Outputs are grouped by filter (all outputs of the first filter, then
+/// all outputs of the second one etc.) and go through ReLU, just like
+/// dense layers' outputs do.
+#[derive(Clone, Debug)]
+pub struct Conv1d {
+    input_size: usize,
+    kernel_size: usize,
+    padding: Padding,
+    biases: Vec<Float>,
+
+    /// `kernels[filter * kernel_size + offset]`
+    kernels: Vec<Float>,
+}
+
+impl Conv1d {
+    /// Creates a convolution with all weights set to zero - use
+    /// `load_weights()` (or `Network::load_weights()`) to fill them.
+    pub fn new(input_size: usize, kernel_size: usize, filters: usize, padding: Padding) -> Self {
+        assert!(kernel_size > 0);
+        assert!(filters > 0);
+
+        if padding == Padding::Valid {
+            assert!(kernel_size <= input_size);
+        }
+
+        Self {
+            input_size,
+            kernel_size,
+            padding,
+            biases: vec![0.0; filters],
+            kernels: vec![0.0; filters * kernel_size],
+        }
+    }
+
+    pub fn filters(&self) -> usize {
+        self.biases.len()
+    }
+
+    fn outputs_per_filter(&self) -> usize {
+        match self.padding {
+            Padding::Valid => self.input_size - self.kernel_size + 1,
+            Padding::Same => self.input_size,
+        }
+    }
+
+    /// Offset of the first input covered by the kernel, relative to its
+    /// output position.
+    fn kernel_offset(&self) -> isize {
+        match self.padding {
+            Padding::Valid => 0,
+            Padding::Same => -((self.kernel_size as isize - 1) / 2),
+        }
+    }
+}
+
+impl Layer for Conv1d {
+    fn input_size(&self) -> usize {
+        self.input_size
+    }
+
+    fn output_size(&self) -> usize {
+        self.filters() * self.outputs_per_filter()
+    }
+
+    fn propagate(&self, inputs: Vec<Float>) -> Vec<Float> {
+        let mut outputs = Vec::with_capacity(self.output_size());
+        self.propagate_into(&inputs, &mut outputs);
+        outputs
+    }
+
+    fn propagate_into(&self, inputs: &[Float], outputs: &mut Vec<Float>) {
+        assert_eq!(inputs.len(), self.input_size);
+
+        let offset = self.kernel_offset();
+
+        outputs.extend((0..self.filters()).flat_map(|filter| {
+            let kernel = &self.kernels[filter * self.kernel_size..][..self.kernel_size];
+            let bias = self.biases[filter];
+
+            (0..self.outputs_per_filter()).map(move |position| {
+                let output = kernel
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(idx, weight)| {
+                        let input = position as isize + offset + idx as isize;
+                        let input = inputs.get(usize::try_from(input).ok()?)?;
+
+                        Some(input * weight)
+                    })
+                    .fold(0.0, |sum, value| sum + value);
+
+                (bias + output).max(0.0)
+            })
+        }));
+    }
+
+    fn weights(&self) -> Vec<Float> {
+        (0..self.filters())
+            .flat_map(|filter| {
+                let kernel = &self.kernels[filter * self.kernel_size..][..self.kernel_size];
+                core::iter::once(self.biases[filter]).chain(kernel.iter().copied())
+            })
+            .collect()
+    }
+
+    fn weight_count(&self) -> usize {
+        self.biases.len() + self.kernels.len()
+    }
+
+    fn load_weights(&mut self, weights: &mut dyn Iterator<Item = Float>) {
+        let mut next = || weights.next().expect("got not enough weights");
+
+        for filter in 0..self.filters() {
+            self.biases[filter] = next();
+
+            for weight in &mut self.kernels[filter * self.kernel_size..][..self.kernel_size] {
+                *weight = next();
+            }
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("conv1d(k={})", self.kernel_size)
+    }
+
+    fn clone_box(&self) -> Box<dyn Layer> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl Conv1d {
+    /// Creates a convolution with biases and weights drawn from U(-1, 1).
+    pub fn random(
+        rng: &mut dyn rand::RngCore,
+        input_size: usize,
+        kernel_size: usize,
+        filters: usize,
+        padding: Padding,
+    ) -> Self {
+        use rand::Rng;
+
+        let mut conv = Self::new(input_size, kernel_size, filters, padding);
+        let count = conv.weight_count();
+
+        conv.load_weights(&mut (0..count).map(|_| rng.gen_range(-1.0..=1.0)));
+        conv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn conv(padding: Padding) -> Conv1d {
+        let mut conv = Conv1d::new(5, 3, 2, padding);
+
+        // Filter #1 sums its neighbourhood, filter #2 detects rising edges
+        conv.load_weights(&mut vec![0.0, 1.0, 1.0, 1.0, 0.5, -1.0, 0.0, 1.0].into_iter());
+        conv
+    }
+
+    #[test]
+    fn propagate_with_same_padding() {
+        let conv = conv(Padding::Same);
+        let actual = conv.propagate(vec![0.0, 1.0, 0.0, 0.0, 1.0]);
+
+        assert_eq!(conv.output_size(), 10);
+
+        assert_relative_eq!(
+            actual.as_slice(),
+            [1.0, 1.0, 1.0, 1.0, 1.0, 1.5, 0.5, 0.0, 1.5, 0.5].as_ref()
+        );
+    }
+
+    #[test]
+    fn propagate_with_valid_padding() {
+        let conv = conv(Padding::Valid);
+        let actual = conv.propagate(vec![0.0, 1.0, 0.0, 0.0, 1.0]);
+
+        assert_eq!(conv.output_size(), 6);
+        assert_relative_eq!(actual.as_slice(), [1.0, 1.0, 1.0, 0.5, 0.0, 1.5].as_ref());
+    }
+
+    #[test]
+    fn weights() {
+        let actual = conv(Padding::Same).weights();
+
+        assert_relative_eq!(
+            actual.as_slice(),
+            [0.0, 1.0, 1.0, 1.0, 0.5, -1.0, 0.0, 1.0].as_ref()
+        );
+    }
+
+    #[test]
+    fn inside_network() {
+        let layers = [LayerTopology::new(10), LayerTopology::new(1)];
+        let mut network = Network::from_weights(&layers, vec![0.0; 11]);
+
+        network.insert_layer(0, conv(Padding::Same)).unwrap();
+
+        assert_eq!(network.input_size(), 5);
+        assert_eq!(network.weights().count(), 8 + 11);
+
+        assert_eq!(
+            network.insert_layer(0, Conv1d::new(5, 3, 2, Padding::Same)),
+            Err(TopologyError::LayerSizeMismatch {
+                layer: 1,
+                expected: 5,
+                got: 10
+            })
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use alloc::string::String;
+use core::fmt;
+
+/// Reasons why a slice of `LayerTopology` can't describe a network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TopologyError {
+    /// Network needs at least an input and an output layer.
+    NotEnoughLayers { got: usize },
+
+    /// Layer at index `layer` has zero neurons.
+    EmptyLayer { layer: usize },
+
+    /// `NetworkBuilder` was given no input layer.
+    MissingInput,
+
+    /// `NetworkBuilder`'s last layer wasn't added with `output()`.
+    MissingOutput,
+
+    /// Layer at index `layer` doesn't fit its neighbour.
+    LayerSizeMismatch {
+        layer: usize,
+        expected: usize,
+        got: usize,
+    },
+}
+
+impl fmt::Display for TopologyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughLayers { got } => {
+                write!(f, "network needs at least two layers, got {got}")
+            }
+            Self::EmptyLayer { layer } => write!(f, "layer #{layer} has no neurons"),
+            Self::MissingInput => write!(f, "network has no input layer"),
+            Self::MissingOutput => write!(f, "network has to end with an output layer"),
+            Self::LayerSizeMismatch {
+                layer,
+                expected,
+                got,
+            } => write!(
+                f,
+                "layer #{layer} has wrong size (expected {expected}, got {got})"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for TopologyError {}
+
+/// Reasons why a network can't be restored from given weights.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WeightsError {
+    Topology(TopologyError),
+    NotEnoughWeights { expected: usize, got: usize },
+    TooManyWeights { expected: usize, got: usize },
+    WrongMaskLength { expected: usize, got: usize },
+}
+
+impl fmt::Display for WeightsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Topology(err) => err.fmt(f),
+            Self::NotEnoughWeights { expected, got } => {
+                write!(f, "got not enough weights (expected {expected}, got {got})")
+            }
+            Self::TooManyWeights { expected, got } => {
+                write!(f, "got too many weights (expected {expected}, got {got})")
+            }
+            Self::WrongMaskLength { expected, got } => {
+                write!(
+                    f,
+                    "got mask of wrong length (expected {expected}, got {got})"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for WeightsError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Topology(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TopologyError> for WeightsError {
+    fn from(err: TopologyError) -> Self {
+        Self::Topology(err)
+    }
+}
+
+/// Reasons why names can't be attached to, or used with, a network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NamesError {
+    WrongCount {
+        expected: usize,
+        got: usize,
+    },
+    Duplicate {
+        name: String,
+    },
+
+    /// Network's inputs or outputs have no names.
+    Unnamed,
+
+    Unknown {
+        name: String,
+    },
+    Missing {
+        name: String,
+    },
+}
+
+impl fmt::Display for NamesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongCount { expected, got } => {
+                write!(
+                    f,
+                    "got wrong number of names (expected {expected}, got {got})"
+                )
+            }
+            Self::Duplicate { name } => write!(f, "name `{name}` is used more than once"),
+            Self::Unnamed => write!(f, "network's inputs or outputs have no names"),
+            Self::Unknown { name } => write!(f, "network has no input named `{name}`"),
+            Self::Missing { name } => write!(f, "got no value for input `{name}`"),
+        }
+    }
+}
+
+impl core::error::Error for NamesError {}
@@ -0,0 +1,91 @@
+use crate::*;
+use core::fmt;
+
+/// Anything that can sit between two layers of a `Network`.
+///
+/// Besides the built-in fully connected layers created from
+/// `LayerTopology`, networks can contain user-defined ones (see
+/// `Network::insert_layer()`) - e.g. normalization or a fixed
+/// preprocessing step.
+///
+/// Layers may carry their own genes: whatever `weights()` returns becomes
+/// a part of `Network::weights()` and gets handed back through
+/// `load_weights()` by `Network::load_weights()`.
+pub trait Layer: fmt::Debug + Send + Sync {
+    fn input_size(&self) -> usize;
+
+    fn output_size(&self) -> usize;
+
+    fn propagate(&self, inputs: Vec<Float>) -> Vec<Float>;
+
+    /// Same as `propagate()`, but appends the outputs to `outputs`
+    /// (which `Network::propagate_with()` hands in empty); override it
+    /// if the layer can avoid allocating.
+    fn propagate_into(&self, inputs: &[Float], outputs: &mut Vec<Float>) {
+        outputs.extend(self.propagate(inputs.to_vec()));
+    }
+
+    fn weights(&self) -> Vec<Float> {
+        Vec::new()
+    }
+
+    fn weight_count(&self) -> usize {
+        self.weights().len()
+    }
+
+    /// Replaces this layer's genes, consuming exactly `weight_count()`
+    /// items from `weights`.
+    fn load_weights(&mut self, weights: &mut dyn Iterator<Item = Float>) {
+        _ = weights;
+    }
+
+    /// Label used by `Network::to_dot()`.
+    fn name(&self) -> String {
+        "custom".into()
+    }
+
+    fn clone_box(&self) -> Box<dyn Layer>;
+}
+
+/// Layer as stored inside a `Network`.
+///
+/// Dense layers are kept apart from the custom ones, because features such
+/// as masks, plasticity, quantization or training need to see their
+/// neurons.
+#[derive(Debug)]
+pub(crate) enum NetworkLayer {
+    Dense(DenseLayer),
+    Custom(Box<dyn Layer>),
+}
+
+impl NetworkLayer {
+    pub(crate) fn as_layer(&self) -> &dyn Layer {
+        match self {
+            Self::Dense(layer) => layer,
+            Self::Custom(layer) => layer.as_ref(),
+        }
+    }
+
+    pub(crate) fn as_dense(&self) -> Option<&DenseLayer> {
+        match self {
+            Self::Dense(layer) => Some(layer),
+            Self::Custom(_) => None,
+        }
+    }
+
+    pub(crate) fn as_dense_mut(&mut self) -> Option<&mut DenseLayer> {
+        match self {
+            Self::Dense(layer) => Some(layer),
+            Self::Custom(_) => None,
+        }
+    }
+}
+
+impl Clone for NetworkLayer {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Dense(layer) => Self::Dense(layer.clone()),
+            Self::Custom(layer) => Self::Custom(layer.clone_box()),
+        }
+    }
+}
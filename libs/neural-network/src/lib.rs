@@ -1,187 +1,1102 @@
-use rand::{Rng, RngCore};
-use std::iter::once;
+//! Evolvable feed-forward neural networks.
+//!
+//! Without the default `std` feature this crate is `no_std` (it still
+//! needs `alloc`), so evolved networks can be restored with
+//! `Network::from_weights()` and propagated on targets without an
+//! operating system; creating random networks requires the `rand`
+//! feature (which implies `std`).
+//!
+//! The `deterministic` feature guarantees that the same weights and inputs
+//! produce bit-identical outputs on every platform (e.g. native and wasm):
+//! it always sums in the same, sequential order (overriding `simd`) and
+//! never fuses multiplications with additions.
 
-#[derive(Debug)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod activation;
+mod builder;
+mod conv1d;
+mod error;
+mod layer;
+mod names;
+mod quantized;
+#[cfg(feature = "rand")]
+mod random;
+mod regularization;
+mod scratch;
+mod training;
+
+pub use self::{
+    activation::*, builder::*, conv1d::*, error::*, layer::Layer, quantized::*, regularization::*,
+    scratch::*, training::*,
+};
+
+use self::{layer::NetworkLayer, names::Names};
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
+
+/// Precision of weights, inputs and outputs - `f32` by default, `f64` with the
+/// `f64` feature enabled.
+#[cfg(not(feature = "f64"))]
+pub type Float = f32;
+
+/// Precision of weights, inputs and outputs - `f32` by default, `f64` with the
+/// `f64` feature enabled.
+#[cfg(feature = "f64")]
+pub type Float = f64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct LayerTopology {
     pub neurons: usize,
+    pub bias: Bias,
+    pub init: WeightInit,
+
+    /// Ignored for the input layer, since it doesn't compute anything.
+    pub activation: Activation,
+
+    /// Whether neurons of this layer adjust their weights while
+    /// propagating (see `Network::propagate_plastic()`).
+    pub plasticity: bool,
+}
+
+impl LayerTopology {
+    pub fn new(neurons: usize) -> Self {
+        Self {
+            neurons,
+            bias: Bias::default(),
+            init: WeightInit::default(),
+            activation: Activation::default(),
+            plasticity: false,
+        }
+    }
+
+    pub fn with_bias(self, bias: Bias) -> Self {
+        Self { bias, ..self }
+    }
+
+    pub fn with_init(self, init: WeightInit) -> Self {
+        Self { init, ..self }
+    }
+
+    pub fn with_activation(self, activation: Activation) -> Self {
+        Self { activation, ..self }
+    }
+
+    pub fn with_plasticity(self, plasticity: bool) -> Self {
+        Self { plasticity, ..self }
+    }
+}
+
+/// Whether neurons of a layer carry a bias and, if so, how it's
+/// initialized by `Network::random()`.
+///
+/// Bias-free neurons don't contribute any bias to `Network::weights()`
+/// and don't expect one in `Network::from_weights()`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Bias {
+    Disabled,
+    Zero,
+    #[default]
+    Uniform,
+    Normal,
+}
+
+/// How `Network::random()` initializes weights of the neurons in a layer.
+///
+/// `fan_in` is the size of the layer's input, `fan_out` the number of
+/// its neurons.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WeightInit {
+    /// U(-1, 1)
+    #[default]
+    Uniform,
+
+    /// Xavier/Glorot: U(-a, a), where a = sqrt(6 / (fan_in + fan_out))
+    XavierUniform,
+
+    /// Xavier/Glorot: N(0, σ²), where σ = sqrt(2 / (fan_in + fan_out))
+    XavierNormal,
+
+    /// He/Kaiming: U(-a, a), where a = sqrt(6 / fan_in)
+    HeUniform,
+
+    /// He/Kaiming: N(0, σ²), where σ = sqrt(2 / fan_in)
+    HeNormal,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Network {
-    layers: Vec<Layer>,
+    layers: Vec<NetworkLayer>,
+    names: Names,
 }
 
 impl Network {
-    pub fn random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Self {
+    /// Returns how many weights (biases and learning rates included) a
+    /// network of given topology has - i.e. how long its `weights()` are.
+    pub fn weight_count(layers: &[LayerTopology]) -> usize {
+        Self::layer_weight_counts(layers).sum()
+    }
+
+    /// Returns how many weights each layer contributes to `weights()`.
+    ///
+    /// Since `weights()` lists layers one after another, this describes
+    /// how the flat weight vector (e.g. a chromosome) maps onto the
+    /// network's structure - which is what genome-aware operators, such
+    /// as `SegmentedCrossover` from the genetic algorithm crate, need.
+    pub fn layer_weight_counts(layers: &[LayerTopology]) -> impl Iterator<Item = usize> + '_ {
+        layers.windows(2).map(|layers| {
+            let has_bias = layers[1].bias != Bias::Disabled;
+            let has_learning_rate = layers[1].plasticity;
+
+            layers[1].neurons * (layers[0].neurons + has_bias as usize + has_learning_rate as usize)
+        })
+    }
+
+    /// Returns how many connections (i.e. weights without biases) a
+    /// network of given topology has - i.e. how long its `mask()` is.
+    pub fn connection_count(layers: &[LayerTopology]) -> usize {
+        layers
+            .windows(2)
+            .map(|layers| layers[1].neurons * layers[0].neurons)
+            .sum()
+    }
+
+    fn validate(layers: &[LayerTopology]) -> Result<(), TopologyError> {
         // Network with just one layer is technically
         // doable, but doesn't make much sense
-        assert!(layers.len() > 1);
+        if layers.len() < 2 {
+            return Err(TopologyError::NotEnoughLayers { got: layers.len() });
+        }
 
-        let layers = layers
-            .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
-            .collect();
+        if let Some(layer) = layers.iter().position(|layer| layer.neurons == 0) {
+            return Err(TopologyError::EmptyLayer { layer });
+        }
+
+        Ok(())
+    }
+
+    pub fn input_size(&self) -> usize {
+        self.layers[0].as_layer().input_size()
+    }
+
+    pub fn output_size(&self) -> usize {
+        self.layers.last().unwrap().as_layer().output_size()
+    }
 
-        Self { layers }
+    /// Inserts a custom layer, so that it becomes the `index`-th one
+    /// (`0` meaning before all the others).
+    ///
+    /// Layer has to fit in-between its neighbours, i.e. its input size
+    /// has to match the output size of the layer before it and its output
+    /// size has to match the input size of the layer after it. A layer
+    /// inserted at `0` becomes the new input layer, so it can accept any
+    /// number of inputs (that's how e.g. `Conv1d` gets put in front of a
+    /// network); the same goes for outputs of a layer inserted at the end.
+    ///
+    /// When this changes the number of inputs or outputs, their names (if
+    /// any) get forgotten.
+    pub fn insert_layer(
+        &mut self,
+        index: usize,
+        layer: impl Layer + 'static,
+    ) -> Result<(), TopologyError> {
+        assert!(index <= self.layers.len());
+
+        if let Some(prev) = index.checked_sub(1) {
+            let expected_input = self.layers[prev].as_layer().output_size();
+
+            if layer.input_size() != expected_input {
+                return Err(TopologyError::LayerSizeMismatch {
+                    layer: index,
+                    expected: expected_input,
+                    got: layer.input_size(),
+                });
+            }
+        }
+
+        if let Some(next) = self.layers.get(index) {
+            let expected_output = next.as_layer().input_size();
+
+            if layer.output_size() != expected_output {
+                return Err(TopologyError::LayerSizeMismatch {
+                    layer: index + 1,
+                    expected: expected_output,
+                    got: layer.output_size(),
+                });
+            }
+        }
+
+        self.layers
+            .insert(index, NetworkLayer::Custom(Box::new(layer)));
+
+        self.refresh_names();
+
+        Ok(())
     }
 
-    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+    pub fn propagate(&self, inputs: Vec<Float>) -> Vec<Float> {
         self.layers
             .iter()
-            .fold(inputs, |inputs, layer| layer.propagate(inputs))
+            .fold(inputs, |inputs, layer| layer.as_layer().propagate(inputs))
+    }
+
+    /// Propagates inputs, recording what each layer has produced along
+    /// the way - e.g. to visualize what's going on inside the network.
+    pub fn propagate_traced(&self, inputs: Vec<Float>) -> Trace {
+        let mut activations = Vec::with_capacity(self.layers.len() + 1);
+        activations.push(inputs);
+
+        for layer in &self.layers {
+            let outputs = layer
+                .as_layer()
+                .propagate(activations.last().unwrap().clone());
+
+            activations.push(outputs);
+        }
+
+        Trace { activations }
+    }
+
+    /// Propagates inputs and then lets neurons of plastic layers learn
+    /// from what they've just seen, using Hebb's rule with Oja's
+    /// normalization:
+    ///
+    /// ```text
+    /// Δw = learning_rate * output * (input - output * w)
+    /// ```
+    ///
+    /// (the normalization term keeps the weights from growing without
+    /// bounds.)
+    ///
+    /// Learning rates are regular genes - they are a part of `weights()`
+    /// and so they evolve together with everything else. Note that since
+    /// this function actually changes the weights, a network that's been
+    /// learning returns different `weights()` than it was created with.
+    pub fn propagate_plastic(&mut self, inputs: Vec<Float>) -> Vec<Float> {
+        self.layers
+            .iter_mut()
+            .fold(inputs, |inputs, layer| match layer {
+                NetworkLayer::Dense(layer) => layer.propagate_plastic(inputs),
+                NetworkLayer::Custom(layer) => layer.propagate(inputs),
+            })
+    }
+
+    /// Propagates many inputs through the same network at once.
+    pub fn propagate_batch(&self, inputs: &[Vec<Float>]) -> Vec<Vec<Float>> {
+        let Some(input_size) = inputs.first().map(Vec::len) else {
+            return Vec::new();
+        };
+
+        assert!(inputs.iter().all(|input| input.len() == input_size));
+
+        let outputs = self.propagate_flat(inputs.concat(), inputs.len());
+        let output_size = outputs.len() / inputs.len();
+
+        outputs
+            .chunks_exact(output_size)
+            .map(<[Float]>::to_vec)
+            .collect()
     }
 
-    pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+    /// Propagates `batch_size` inputs stored row-major in one flat buffer,
+    /// returning the outputs in the same layout.
+    ///
+    /// Each neuron gets applied to the entire batch before moving on to
+    /// the next one, so its weights stay in cache for the whole batch.
+    pub fn propagate_flat(&self, inputs: Vec<Float>, batch_size: usize) -> Vec<Float> {
+        if batch_size == 0 {
+            return Vec::new();
+        }
+
+        assert_eq!(inputs.len() % batch_size, 0);
+
         self.layers
             .iter()
-            .flat_map(|layer| layer.neurons.iter())
-            .flat_map(|neuron| once(&neuron.bias).chain(&neuron.weights))
-            .copied()
+            .fold(inputs, |inputs, layer| match layer {
+                NetworkLayer::Dense(layer) => layer.propagate_flat(&inputs, batch_size),
+                NetworkLayer::Custom(layer) => inputs
+                    .chunks_exact(inputs.len() / batch_size)
+                    .flat_map(|row| layer.propagate(row.to_vec()))
+                    .collect(),
+            })
+    }
+
+    /// Describes this network in Graphviz's DOT language.
+    ///
+    /// Nodes are neurons (labeled with their biases), edges are weights -
+    /// blue for positive, red for negative, thicker for larger magnitudes.
+    /// Render with e.g. `dot -Tsvg network.dot > network.svg`.
+    pub fn to_dot(&self) -> String {
+        use core::fmt::Write;
+
+        let input_size = self.input_size();
+        let mut dot = String::new();
+
+        dot.push_str("digraph network {\n");
+        dot.push_str("    rankdir=LR;\n");
+        dot.push_str("    node [shape=circle];\n");
+
+        for input in 0..input_size {
+            _ = writeln!(dot, "    l0_{input} [label=\"in{input}\", shape=box];");
+        }
+
+        for (layer_idx, layer) in self.layers.iter().enumerate() {
+            let layer_idx = layer_idx + 1;
+
+            let layer = match layer {
+                NetworkLayer::Dense(layer) => layer,
+
+                // We don't know what's going on inside custom layers, so
+                // we render just their outputs
+                NetworkLayer::Custom(layer) => {
+                    for output in 0..layer.output_size() {
+                        _ = writeln!(
+                            dot,
+                            "    l{layer_idx}_{output} [label=\"{}\", shape=box, style=dashed];",
+                            layer.name(),
+                        );
+                    }
+
+                    continue;
+                }
+            };
+
+            for (neuron_idx, neuron) in layer.neurons.iter().enumerate() {
+                let label = match neuron.bias {
+                    Some(bias) => format!("{bias:.2}"),
+                    None => String::new(),
+                };
+
+                _ = writeln!(dot, "    l{layer_idx}_{neuron_idx} [label=\"{label}\"];");
+
+                for (input, weight) in neuron.weights.iter().enumerate() {
+                    if !neuron.is_connected(input) {
+                        continue;
+                    }
+
+                    let color = if *weight >= 0.0 { "blue" } else { "red" };
+                    let width = 0.5 + weight.abs().min(4.0);
+
+                    _ = writeln!(
+                        dot,
+                        "    l{}_{input} -> l{layer_idx}_{neuron_idx} \
+                         [label=\"{weight:.2}\", color={color}, penwidth={width:.2}];",
+                        layer_idx - 1,
+                    );
+                }
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
     }
 
-    pub fn from_weights(layers: &[LayerTopology], weights: impl IntoIterator<Item = f32>) -> Self {
-        assert!(layers.len() > 1);
+    pub fn weights(&self) -> impl Iterator<Item = Float> + '_ {
+        self.layers
+            .iter()
+            .flat_map(|layer| -> Box<dyn Iterator<Item = Float>> {
+                match layer {
+                    NetworkLayer::Dense(layer) => Box::new(layer.genes()),
+                    NetworkLayer::Custom(layer) => Box::new(layer.weights().into_iter()),
+                }
+            })
+    }
+
+    /// Replaces all weights of this network, keeping its structure.
+    ///
+    /// Contrary to `from_weights()`, this works for networks containing
+    /// custom layers, too: restore such networks by first re-creating
+    /// their structure and then loading weights into it.
+    pub fn load_weights(
+        &mut self,
+        weights: impl IntoIterator<Item = Float>,
+    ) -> Result<(), WeightsError> {
+        let weights: Vec<_> = weights.into_iter().collect();
+
+        let expected: usize = self
+            .layers
+            .iter()
+            .map(|layer| layer.as_layer().weight_count())
+            .sum();
+
+        let got = weights.len();
+
+        if got < expected {
+            return Err(WeightsError::NotEnoughWeights { expected, got });
+        }
+
+        if got > expected {
+            return Err(WeightsError::TooManyWeights { expected, got });
+        }
+
+        let mut weights = weights.into_iter();
+
+        for layer in &mut self.layers {
+            match layer {
+                NetworkLayer::Dense(layer) => layer.load_weights(&mut weights),
+                NetworkLayer::Custom(layer) => layer.load_weights(&mut weights),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn dense_layers(&self) -> impl Iterator<Item = &DenseLayer> {
+        self.layers.iter().filter_map(NetworkLayer::as_dense)
+    }
+
+    fn dense_layers_mut(&mut self) -> impl Iterator<Item = &mut DenseLayer> {
+        self.layers
+            .iter_mut()
+            .filter_map(NetworkLayer::as_dense_mut)
+    }
+
+    pub fn from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = Float>,
+    ) -> Self {
+        Self::try_from_weights(layers, weights).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = Float>,
+    ) -> Result<Self, WeightsError> {
+        Self::validate(layers)?;
+
+        let weights: Vec<_> = weights.into_iter().collect();
+        let expected = Self::weight_count(layers);
+        let got = weights.len();
+
+        if got < expected {
+            return Err(WeightsError::NotEnoughWeights { expected, got });
+        }
+
+        if got > expected {
+            return Err(WeightsError::TooManyWeights { expected, got });
+        }
 
         let mut weights = weights.into_iter();
 
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::from_weights(layers[0].neurons, layers[1].neurons, &mut weights))
+            .map(|layers| {
+                NetworkLayer::Dense(DenseLayer::from_weights(
+                    layers[0].neurons,
+                    &layers[1],
+                    &mut weights,
+                ))
+            })
             .collect();
 
-        if weights.next().is_some() {
-            panic!("got too many weights");
+        Ok(Self {
+            layers,
+            names: Names::default(),
+        })
+    }
+
+    /// Tells which connections are active, one item per each weight
+    /// (biases excluded), in the same order as `weights()`.
+    ///
+    /// Custom layers don't have masks and so they're skipped.
+    pub fn mask(&self) -> impl Iterator<Item = bool> + '_ {
+        self.dense_layers()
+            .flat_map(|layer| layer.neurons.iter())
+            .flat_map(|neuron| (0..neuron.weights.len()).map(|input| neuron.is_connected(input)))
+    }
+
+    /// Returns number of connections that weren't pruned.
+    pub fn active_connections(&self) -> usize {
+        self.mask().filter(|&connected| connected).count()
+    }
+
+    /// Disconnects all connections whose weights' magnitude is below
+    /// `threshold`, returning how many connections got pruned.
+    ///
+    /// Pruned connections keep their weight at zero and stay disconnected
+    /// even when passed through `mask()` and `from_weights_and_mask()`.
+    pub fn prune(&mut self, threshold: Float) -> usize {
+        self.dense_layers_mut()
+            .flat_map(|layer| layer.neurons.iter_mut())
+            .map(|neuron| neuron.prune(threshold))
+            .sum()
+    }
+
+    pub fn from_weights_and_mask(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = Float>,
+        mask: impl IntoIterator<Item = bool>,
+    ) -> Self {
+        Self::try_from_weights_and_mask(layers, weights, mask).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Restores a sparse network.
+    ///
+    /// To evolve the connectivity together with the weights, the mask can
+    /// be stored in the chromosome as extra genes, e.g. `gene > 0.0` for
+    /// connected and `gene <= 0.0` for disconnected ones.
+    pub fn try_from_weights_and_mask(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = Float>,
+        mask: impl IntoIterator<Item = bool>,
+    ) -> Result<Self, WeightsError> {
+        let mut network = Self::try_from_weights(layers, weights)?;
+        let mask: Vec<_> = mask.into_iter().collect();
+        let expected = Self::connection_count(layers);
+
+        if mask.len() != expected {
+            return Err(WeightsError::WrongMaskLength {
+                expected,
+                got: mask.len(),
+            });
+        }
+
+        let mut mask = mask.into_iter();
+
+        for neuron in network
+            .dense_layers_mut()
+            .flat_map(|layer| layer.neurons.iter_mut())
+        {
+            let neuron_mask = mask.by_ref().take(neuron.weights.len()).collect();
+            neuron.apply_mask(neuron_mask);
         }
 
-        Self { layers }
+        Ok(network)
+    }
+}
+
+/// Activations recorded by `Network::propagate_traced()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Trace {
+    /// `activations[0]` are the inputs, `activations[n + 1]` are outputs
+    /// of the n-th layer.
+    pub activations: Vec<Vec<Float>>,
+}
+
+impl Trace {
+    pub fn inputs(&self) -> &[Float] {
+        &self.activations[0]
+    }
+
+    pub fn outputs(&self) -> &[Float] {
+        self.activations.last().unwrap()
     }
 }
 
-#[derive(Debug)]
-struct Layer {
+/// Fully connected layer, created from `LayerTopology`.
+#[derive(Clone, Debug)]
+struct DenseLayer {
     neurons: Vec<Neuron>,
+    activation: Activation,
 }
 
-impl Layer {
-    fn random(rng: &mut dyn RngCore, input_size: usize, output_size: usize) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::random(rng, input_size))
-            .collect();
+impl Layer for DenseLayer {
+    fn input_size(&self) -> usize {
+        self.neurons[0].weights.len()
+    }
+
+    fn output_size(&self) -> usize {
+        self.neurons.len()
+    }
+
+    fn propagate(&self, inputs: Vec<Float>) -> Vec<Float> {
+        self.neurons
+            .iter()
+            .map(|neuron| neuron.propagate(&inputs, self.activation))
+            .collect()
+    }
+
+    fn propagate_into(&self, inputs: &[Float], outputs: &mut Vec<Float>) {
+        outputs.extend(
+            self.neurons
+                .iter()
+                .map(|neuron| neuron.propagate(inputs, self.activation)),
+        );
+    }
+
+    fn weights(&self) -> Vec<Float> {
+        self.genes().collect()
+    }
+
+    fn weight_count(&self) -> usize {
+        self.neurons
+            .iter()
+            .map(|neuron| {
+                neuron.bias.is_some() as usize
+                    + neuron.weights.len()
+                    + neuron.learning_rate.is_some() as usize
+            })
+            .sum()
+    }
 
-        Self { neurons }
+    fn load_weights(&mut self, weights: &mut dyn Iterator<Item = Float>) {
+        let mut next = || weights.next().expect("got not enough weights");
+
+        for neuron in &mut self.neurons {
+            if let Some(bias) = &mut neuron.bias {
+                *bias = next();
+            }
+
+            for (input_idx, weight) in neuron.weights.iter_mut().enumerate() {
+                let value = next();
+                let connected = neuron.mask.as_ref().is_none_or(|mask| mask[input_idx]);
+
+                *weight = if connected { value } else { 0.0 };
+            }
+
+            if let Some(learning_rate) = &mut neuron.learning_rate {
+                *learning_rate = next();
+            }
+        }
+    }
+
+    fn name(&self) -> String {
+        "dense".into()
+    }
+
+    fn clone_box(&self) -> Box<dyn Layer> {
+        Box::new(self.clone())
     }
+}
 
-    fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
+impl DenseLayer {
+    fn genes(&self) -> impl Iterator<Item = Float> + '_ {
         self.neurons
             .iter()
-            .map(|neuron| neuron.propagate(&inputs))
+            .flat_map(|neuron| {
+                neuron
+                    .bias
+                    .iter()
+                    .chain(&neuron.weights)
+                    .chain(&neuron.learning_rate)
+            })
+            .copied()
+    }
+
+    fn propagate_plastic(&mut self, inputs: Vec<Float>) -> Vec<Float> {
+        self.neurons
+            .iter_mut()
+            .map(|neuron| {
+                let output = neuron.propagate(&inputs, self.activation);
+                neuron.learn(&inputs, output);
+                output
+            })
             .collect()
     }
 
+    fn propagate_flat(&self, inputs: &[Float], batch_size: usize) -> Vec<Float> {
+        let input_size = inputs.len() / batch_size;
+        let output_size = self.neurons.len();
+        let mut outputs = vec![0.0; batch_size * output_size];
+
+        for (neuron_idx, neuron) in self.neurons.iter().enumerate() {
+            for (row_idx, row) in inputs.chunks_exact(input_size).enumerate() {
+                outputs[row_idx * output_size + neuron_idx] =
+                    neuron.propagate(row, self.activation);
+            }
+        }
+
+        outputs
+    }
+
     fn from_weights(
         input_size: usize,
-        output_size: usize,
-        weights: &mut dyn Iterator<Item = f32>,
+        topology: &LayerTopology,
+        weights: &mut dyn Iterator<Item = Float>,
     ) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::from_weights(input_size, weights))
+        let neurons = (0..topology.neurons)
+            .map(|_| Neuron::from_weights(input_size, topology, weights))
             .collect();
 
-        Self { neurons }
+        Self {
+            neurons,
+            activation: topology.activation,
+        }
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct Neuron {
-    bias: f32,
-    weights: Vec<f32>,
+    bias: Option<Float>,
+    weights: Vec<Float>,
+
+    /// Which inputs this neuron is connected to; `None` stands for fully
+    /// connected. Weights of disconnected inputs are always zero.
+    mask: Option<Vec<bool>>,
+
+    /// Present only for neurons of plastic layers.
+    learning_rate: Option<Float>,
 }
 
 impl Neuron {
-    fn random(rng: &mut dyn RngCore, input_size: usize) -> Self {
-        let bias = rng.gen_range(-1.0..=1.0);
+    fn propagate(&self, inputs: &[Float], activation: Activation) -> Float {
+        assert_eq!(inputs.len(), self.weights.len());
 
-        let weights = (0..input_size).map(|_| rng.gen_range(-1.0..=1.0)).collect();
+        let output = dot(inputs, &self.weights);
 
-        Self { bias, weights }
+        activation.apply(self.bias.unwrap_or(0.0) + output)
     }
 
-    fn propagate(&self, inputs: &[f32]) -> f32 {
-        assert_eq!(inputs.len(), self.weights.len());
+    fn from_weights(
+        input_size: usize,
+        topology: &LayerTopology,
+        weights: &mut dyn Iterator<Item = Float>,
+    ) -> Self {
+        let mut next = || weights.next().expect("got not enough weights");
 
-        let output = inputs
-            .iter()
-            .zip(&self.weights)
-            .map(|(input, weight)| input * weight)
-            .sum::<f32>();
+        let bias = (topology.bias != Bias::Disabled).then(&mut next);
+        let neuron_weights = (0..input_size).map(|_| next()).collect();
+        let learning_rate = topology.plasticity.then(next);
 
-        (self.bias + output).max(0.0)
+        Self {
+            bias,
+            weights: neuron_weights,
+            mask: None,
+            learning_rate,
+        }
     }
 
-    fn from_weights(input_size: usize, weights: &mut dyn Iterator<Item = f32>) -> Self {
-        let bias = weights.next().expect("got not enough weights");
+    fn learn(&mut self, inputs: &[Float], output: Float) {
+        let Some(learning_rate) = self.learning_rate else {
+            return;
+        };
 
-        let weights = (0..input_size)
-            .map(|_| weights.next().expect("got not enough weights"))
-            .collect();
+        for (input_idx, (weight, input)) in self.weights.iter_mut().zip(inputs).enumerate() {
+            if self.mask.as_ref().is_none_or(|mask| mask[input_idx]) {
+                *weight += learning_rate * output * (input - output * *weight);
+            }
+        }
+    }
+
+    fn is_connected(&self, input: usize) -> bool {
+        self.mask.as_ref().is_none_or(|mask| mask[input])
+    }
+
+    fn prune(&mut self, threshold: Float) -> usize {
+        let mut pruned = 0;
+        let mask = self
+            .mask
+            .get_or_insert_with(|| vec![true; self.weights.len()]);
+
+        for (weight, connected) in self.weights.iter_mut().zip(mask) {
+            if *connected && weight.abs() < threshold {
+                *weight = 0.0;
+                *connected = false;
+                pruned += 1;
+            }
+        }
+
+        pruned
+    }
+
+    fn apply_mask(&mut self, mask: Vec<bool>) {
+        for (weight, &connected) in self.weights.iter_mut().zip(&mask) {
+            if !connected {
+                *weight = 0.0;
+            }
+        }
+
+        self.mask = if mask.iter().all(|&connected| connected) {
+            None
+        } else {
+            Some(mask)
+        };
+    }
+}
+
+/// Sequential dot product.
+///
+/// Note that Rust never contracts `a * b + c` into a fused multiply-add
+/// on its own (that's what `mul_add()` is for), so together with the
+/// fixed summation order this gives the same results on all IEEE 754
+/// platforms.
+#[cfg(any(not(feature = "simd"), feature = "deterministic"))]
+fn dot(inputs: &[Float], weights: &[Float]) -> Float {
+    inputs
+        .iter()
+        .zip(weights)
+        .fold(0.0, |sum, (input, weight)| sum + input * weight)
+}
+
+/// Dot product split into `LANES` independent accumulators.
+///
+/// Since the accumulators don't depend on each other, the compiler is
+/// free to keep them in a single vector register and auto-vectorize the
+/// loop - which it can't do for the sequential version above, because
+/// float addition is not associative.
+///
+/// Note that the different summation order means the results may
+/// differ from the scalar version in the last few bits - and, depending on
+/// how the loop gets vectorized, between platforms; that's why the
+/// `deterministic` feature disables this path.
+#[cfg(all(feature = "simd", not(feature = "deterministic")))]
+fn dot(inputs: &[Float], weights: &[Float]) -> Float {
+    const LANES: usize = 8;
+
+    let mut acc = [0.0; LANES];
 
-        Self { bias, weights }
+    let inputs_chunks = inputs.chunks_exact(LANES);
+    let weights_chunks = weights.chunks_exact(LANES);
+
+    let rest = inputs_chunks
+        .remainder()
+        .iter()
+        .zip(weights_chunks.remainder())
+        .map(|(input, weight)| input * weight)
+        .sum::<Float>();
+
+    for (inputs, weights) in inputs_chunks.zip(weights_chunks) {
+        for lane in 0..LANES {
+            acc[lane] += inputs[lane] * weights[lane];
+        }
     }
+
+    acc.iter().sum::<Float>() + rest
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use approx::assert_relative_eq;
+    #[cfg(feature = "rand")]
     use rand::SeedableRng;
+    #[cfg(feature = "rand")]
     use rand_chacha::ChaCha8Rng;
 
+    #[cfg(feature = "rand")]
     #[test]
     fn random() {
         // Because we always use the same seed, our rng in here will
         // always return the same set of values
         let mut rng = ChaCha8Rng::from_seed(Default::default());
-        let neuron = Neuron::random(&mut rng, 4);
+        let neuron = Neuron::random(&mut rng, 4, &LayerTopology::new(1));
 
-        assert_relative_eq!(neuron.bias, -0.6255188);
-        assert_relative_eq!(
-            neuron.weights.as_slice(),
-            [0.67383957, 0.8181262, 0.26284897, 0.5238807].as_ref()
-        );
+        #[cfg(not(feature = "f64"))]
+        {
+            assert_relative_eq!(neuron.bias.unwrap(), -0.6255188);
+            assert_relative_eq!(
+                neuron.weights.as_slice(),
+                [0.67383957, 0.8181262, 0.26284897, 0.5238807].as_ref()
+            );
+        }
+
+        // Doubles are drawn from more random bits, so the values differ
+        #[cfg(feature = "f64")]
+        {
+            assert_relative_eq!(neuron.bias.unwrap(), 0.6738395137652948);
+            assert_relative_eq!(
+                neuron.weights.as_slice(),
+                [
+                    0.26284898813304625,
+                    -0.5351683130665029,
+                    -0.7648179607770014,
+                    -0.48879602856526627
+                ]
+                .as_ref()
+            );
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_with_xavier_uniform_weights() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let topology = LayerTopology::new(10).with_init(WeightInit::XavierUniform);
+        let neuron = Neuron::random(&mut rng, 500, &topology);
+        let limit = Float::sqrt(6.0 / 510.0);
+
+        assert!(neuron.weights.iter().all(|weight| weight.abs() <= limit));
+        assert!(neuron
+            .weights
+            .iter()
+            .any(|weight| weight.abs() > limit / 2.0));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_with_he_normal_weights() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let topology = LayerTopology::new(1).with_init(WeightInit::HeNormal);
+        let neuron = Neuron::random(&mut rng, 5000, &topology);
+
+        let variance = neuron
+            .weights
+            .iter()
+            .map(|weight| weight * weight)
+            .sum::<Float>()
+            / 5000.0;
+
+        assert_relative_eq!(variance, 2.0 / 5000.0, max_relative = 0.1);
     }
 
     #[test]
     fn propagate() {
         let neuron = Neuron {
-            bias: 0.5,
+            bias: Some(0.5),
             weights: vec![-0.3, 0.8],
+            mask: None,
+            learning_rate: None,
         };
 
-        assert_relative_eq!(neuron.propagate(&[-10.0, -10.0]), 0.0,);
+        assert_relative_eq!(neuron.propagate(&[-10.0, -10.0], Activation::Relu), 0.0,);
 
         assert_relative_eq!(
-            neuron.propagate(&[0.5, 1.0]),
+            neuron.propagate(&[0.5, 1.0], Activation::Relu),
             (-0.3 * 0.5) + (0.8 * 1.0) + 0.5
         )
     }
 
+    #[test]
+    fn dot_matches_naive_sum() {
+        let inputs: Vec<_> = (0..21).map(|n| n as Float * 0.25).collect();
+        let weights: Vec<_> = (0..21).map(|n| 1.0 - n as Float * 0.125).collect();
+
+        let expected = inputs
+            .iter()
+            .zip(&weights)
+            .map(|(input, weight)| input * weight)
+            .sum::<Float>();
+
+        assert_relative_eq!(dot(&inputs, &weights), expected);
+    }
+
+    #[test]
+    fn propagate_traced() {
+        let network = Network::from_weights(
+            &[
+                LayerTopology::new(2),
+                LayerTopology::new(2),
+                LayerTopology::new(1),
+            ],
+            vec![0.0, 1.0, 0.0, 0.5, -1.0, -1.0, 0.1, 1.0, 2.0],
+        );
+
+        let trace = network.propagate_traced(vec![0.5, 0.2]);
+
+        assert_eq!(trace.activations.len(), 3);
+        assert_relative_eq!(trace.inputs(), [0.5, 0.2].as_ref());
+        assert_relative_eq!(trace.activations[1].as_slice(), [0.5, 0.0].as_ref());
+        assert_relative_eq!(trace.outputs(), [0.6].as_ref());
+        assert_relative_eq!(
+            trace.outputs(),
+            network.propagate(vec![0.5, 0.2]).as_slice()
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn propagate_batch() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let network = Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(3),
+                LayerTopology::new(4),
+                LayerTopology::new(2),
+            ],
+        );
+
+        let inputs = vec![
+            vec![0.1, 0.2, 0.3],
+            vec![-0.5, 0.0, 0.5],
+            vec![1.0, 1.0, 1.0],
+        ];
+
+        let actual = network.propagate_batch(&inputs);
+
+        let expected: Vec<_> = inputs
+            .iter()
+            .map(|input| network.propagate(input.clone()))
+            .collect();
+
+        assert_eq!(actual, expected);
+        assert!(network.propagate_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn to_dot() {
+        let network = Network {
+            layers: vec![NetworkLayer::Dense(DenseLayer {
+                neurons: vec![
+                    Neuron {
+                        bias: Some(0.5),
+                        weights: vec![-0.25, 1.0],
+                        mask: None,
+                        learning_rate: None,
+                    },
+                    Neuron {
+                        bias: None,
+                        weights: vec![0.0, 2.0],
+                        mask: None,
+                        learning_rate: None,
+                    },
+                ],
+                activation: Activation::Relu,
+            })],
+            names: Names::default(),
+        };
+
+        let expected = "\
+digraph network {
+    rankdir=LR;
+    node [shape=circle];
+    l0_0 [label=\"in0\", shape=box];
+    l0_1 [label=\"in1\", shape=box];
+    l1_0 [label=\"0.50\"];
+    l0_0 -> l1_0 [label=\"-0.25\", color=red, penwidth=0.75];
+    l0_1 -> l1_0 [label=\"1.00\", color=blue, penwidth=1.50];
+    l1_1 [label=\"\"];
+    l0_0 -> l1_1 [label=\"0.00\", color=blue, penwidth=0.50];
+    l0_1 -> l1_1 [label=\"2.00\", color=blue, penwidth=2.50];
+}
+";
+
+        assert_eq!(network.to_dot(), expected);
+    }
+
+    #[cfg(any(not(feature = "simd"), feature = "deterministic"))]
+    #[test]
+    fn dot_sums_sequentially() {
+        // Smallest power of ten whose neighbours are further than 1 apart
+        #[cfg(not(feature = "f64"))]
+        let big = 1e8;
+        #[cfg(feature = "f64")]
+        let big = 1e16;
+
+        let inputs = [big, 1.0, -big, 1.0];
+        let weights = [1.0, 1.0, 1.0, 1.0];
+
+        // ((big + 1) - big) + 1 loses the first `1` to rounding, so this
+        // assertion holds only for the left-to-right order
+        assert_eq!(dot(&inputs, &weights).to_bits(), (1.0 as Float).to_bits());
+    }
+
     #[test]
     fn weights() {
         let network = Network {
             layers: vec![
-                Layer {
+                NetworkLayer::Dense(DenseLayer {
                     neurons: vec![Neuron {
-                        bias: 0.1,
+                        bias: Some(0.1),
                         weights: vec![0.2, 0.3, 0.4],
+                        mask: None,
+                        learning_rate: None,
                     }],
-                },
-                Layer {
+                    activation: Activation::Relu,
+                }),
+                NetworkLayer::Dense(DenseLayer {
                     neurons: vec![Neuron {
-                        bias: 0.5,
+                        bias: Some(0.5),
                         weights: vec![0.6, 0.7, 0.8],
+                        mask: None,
+                        learning_rate: None,
                     }],
-                },
+                    activation: Activation::Relu,
+                }),
             ],
+            names: Names::default(),
         };
 
         let actual: Vec<_> = network.weights().collect();
@@ -192,7 +1107,7 @@ mod tests {
 
     #[test]
     fn from_weights() {
-        let layers = &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 2 }];
+        let layers = &[LayerTopology::new(3), LayerTopology::new(2)];
 
         let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
         let network = Network::from_weights(layers, weights.clone());
@@ -200,4 +1115,313 @@ mod tests {
 
         assert_relative_eq!(actual.as_slice(), weights.as_slice());
     }
+
+    mod try_from_weights {
+        use super::*;
+
+        fn layers() -> [LayerTopology; 2] {
+            [LayerTopology::new(3), LayerTopology::new(2)]
+        }
+
+        #[test]
+        fn given_not_enough_layers() {
+            let actual = Network::try_from_weights(&layers()[..1], vec![]).unwrap_err();
+
+            assert_eq!(
+                actual,
+                WeightsError::Topology(TopologyError::NotEnoughLayers { got: 1 })
+            );
+        }
+
+        #[test]
+        fn given_empty_layer() {
+            let layers = [LayerTopology::new(3), LayerTopology::new(0)];
+            let actual = Network::try_from_weights(&layers, vec![]).unwrap_err();
+
+            assert_eq!(
+                actual,
+                WeightsError::Topology(TopologyError::EmptyLayer { layer: 1 })
+            );
+        }
+
+        #[test]
+        fn given_not_enough_weights() {
+            let actual = Network::try_from_weights(&layers(), vec![0.0; 7]).unwrap_err();
+
+            assert_eq!(
+                actual,
+                WeightsError::NotEnoughWeights {
+                    expected: 8,
+                    got: 7
+                }
+            );
+        }
+
+        #[test]
+        fn given_too_many_weights() {
+            let actual = Network::try_from_weights(&layers(), vec![0.0; 9]).unwrap_err();
+
+            assert_eq!(
+                actual,
+                WeightsError::TooManyWeights {
+                    expected: 8,
+                    got: 9
+                }
+            );
+        }
+    }
+
+    mod sparse {
+        use super::*;
+
+        fn layers() -> [LayerTopology; 2] {
+            [LayerTopology::new(2), LayerTopology::new(2)]
+        }
+
+        #[test]
+        fn prune() {
+            let mut network =
+                Network::from_weights(&layers(), vec![0.5, 0.01, -0.8, 0.2, -0.05, 0.3]);
+
+            assert_eq!(network.prune(0.1), 2);
+            assert_eq!(network.active_connections(), 2);
+
+            let weights: Vec<_> = network.weights().collect();
+            let mask: Vec<_> = network.mask().collect();
+
+            assert_relative_eq!(weights.as_slice(), [0.5, 0.0, -0.8, 0.2, 0.0, 0.3].as_ref());
+            assert_eq!(mask, vec![false, true, false, true]);
+        }
+
+        #[test]
+        fn pruned_connections_stay_disconnected() {
+            let mask = vec![true, false, false, true];
+            let network = Network::from_weights_and_mask(&layers(), vec![0.5; 6], mask.clone());
+
+            let weights: Vec<_> = network.weights().collect();
+
+            assert_relative_eq!(weights.as_slice(), [0.5, 0.5, 0.0, 0.5, 0.0, 0.5].as_ref());
+            assert_eq!(network.mask().collect::<Vec<_>>(), mask);
+            assert_relative_eq!(
+                network.propagate(vec![1.0, 2.0]).as_slice(),
+                [1.0, 1.5].as_ref()
+            );
+        }
+
+        #[test]
+        fn given_wrong_mask_length() {
+            let actual = Network::try_from_weights_and_mask(&layers(), vec![0.5; 6], vec![true; 3])
+                .unwrap_err();
+
+            assert_eq!(
+                actual,
+                WeightsError::WrongMaskLength {
+                    expected: 4,
+                    got: 3
+                }
+            );
+        }
+    }
+
+    mod custom_layers {
+        use super::*;
+
+        /// Multiplies all inputs by an evolvable factor.
+        #[derive(Clone, Debug)]
+        struct Scale {
+            size: usize,
+            factor: Float,
+        }
+
+        impl Layer for Scale {
+            fn input_size(&self) -> usize {
+                self.size
+            }
+
+            fn output_size(&self) -> usize {
+                self.size
+            }
+
+            fn propagate(&self, inputs: Vec<Float>) -> Vec<Float> {
+                inputs
+                    .into_iter()
+                    .map(|input| input * self.factor)
+                    .collect()
+            }
+
+            fn weights(&self) -> Vec<Float> {
+                vec![self.factor]
+            }
+
+            fn load_weights(&mut self, weights: &mut dyn Iterator<Item = Float>) {
+                self.factor = weights.next().unwrap();
+            }
+
+            fn clone_box(&self) -> Box<dyn Layer> {
+                Box::new(self.clone())
+            }
+        }
+
+        fn network() -> Network {
+            let layers = [LayerTopology::new(2), LayerTopology::new(1)];
+            let mut network = Network::from_weights(&layers, vec![0.0, 1.0, 2.0]);
+
+            network
+                .insert_layer(
+                    0,
+                    Scale {
+                        size: 2,
+                        factor: 10.0,
+                    },
+                )
+                .unwrap();
+
+            network
+        }
+
+        #[test]
+        fn propagate() {
+            assert_relative_eq!(
+                network().propagate(vec![0.1, 0.2]).as_slice(),
+                [5.0].as_ref()
+            );
+        }
+
+        #[test]
+        fn weights() {
+            let actual: Vec<_> = network().weights().collect();
+
+            assert_relative_eq!(actual.as_slice(), [10.0, 0.0, 1.0, 2.0].as_ref());
+        }
+
+        #[test]
+        fn load_weights() {
+            let mut network = network();
+            network.load_weights(vec![1.0, 0.5, 1.0, 1.0]).unwrap();
+
+            assert_relative_eq!(network.propagate(vec![0.1, 0.2]).as_slice(), [0.8].as_ref());
+
+            assert_eq!(
+                network.load_weights(vec![1.0]),
+                Err(WeightsError::NotEnoughWeights {
+                    expected: 4,
+                    got: 1
+                })
+            );
+        }
+
+        #[test]
+        fn insert_layer_with_wrong_size() {
+            let actual = network().insert_layer(
+                2,
+                Scale {
+                    size: 2,
+                    factor: 1.0,
+                },
+            );
+
+            assert_eq!(
+                actual,
+                Err(TopologyError::LayerSizeMismatch {
+                    layer: 2,
+                    expected: 1,
+                    got: 2
+                })
+            );
+        }
+
+        #[test]
+        fn quantize() {
+            let actual = network().quantize(Precision::F16).propagate(vec![0.1, 0.2]);
+
+            assert_relative_eq!(actual.as_slice(), [5.0].as_ref(), epsilon = 1e-2);
+        }
+    }
+
+    mod plasticity {
+        use super::*;
+
+        fn network() -> Network {
+            let layers = [
+                LayerTopology::new(2),
+                LayerTopology::new(1).with_plasticity(true),
+            ];
+
+            // bias, two weights, learning rate
+            Network::from_weights(&layers, vec![0.0, 0.5, 0.5, 0.1])
+        }
+
+        #[test]
+        fn learning_rates_are_genes() {
+            let weights: Vec<_> = network().weights().collect();
+
+            assert_relative_eq!(weights.as_slice(), [0.0, 0.5, 0.5, 0.1].as_ref());
+        }
+
+        #[test]
+        fn propagate_plastic() {
+            let mut network = network();
+
+            // output = 0.5 * 1.0 + 0.5 * 0.0 = 0.5
+            let output = network.propagate_plastic(vec![1.0, 0.0]);
+            assert_relative_eq!(output.as_slice(), [0.5].as_ref());
+
+            // Δw1 = 0.1 * 0.5 * (1.0 - 0.5 * 0.5) = 0.0375
+            // Δw2 = 0.1 * 0.5 * (0.0 - 0.5 * 0.5) = -0.0125
+            let weights: Vec<_> = network.weights().collect();
+            assert_relative_eq!(weights.as_slice(), [0.0, 0.5375, 0.4875, 0.1].as_ref());
+        }
+
+        #[test]
+        fn propagate_does_not_learn() {
+            let network = network();
+            network.propagate(vec![1.0, 0.0]);
+
+            let weights: Vec<_> = network.weights().collect();
+            assert_relative_eq!(weights.as_slice(), [0.0, 0.5, 0.5, 0.1].as_ref());
+        }
+    }
+
+    #[test]
+    fn layer_weight_counts() {
+        let layers = [
+            LayerTopology::new(3),
+            LayerTopology::new(4),
+            LayerTopology::new(2).with_bias(Bias::Disabled),
+        ];
+
+        let actual: Vec<_> = Network::layer_weight_counts(&layers).collect();
+
+        assert_eq!(actual, vec![16, 8]);
+        assert_eq!(Network::weight_count(&layers), 24);
+    }
+
+    #[test]
+    fn from_weights_without_biases() {
+        let layers = &[
+            LayerTopology::new(3),
+            LayerTopology::new(2).with_bias(Bias::Disabled),
+        ];
+
+        let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6];
+        let network = Network::from_weights(layers, weights.clone());
+        let actual: Vec<_> = network.weights().collect();
+
+        assert_relative_eq!(actual.as_slice(), weights.as_slice());
+        assert_relative_eq!(
+            network.propagate(vec![1.0, 1.0, 1.0]).as_slice(),
+            [0.6, 1.5].as_ref()
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_with_zero_biases() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let topology = LayerTopology::new(1).with_bias(Bias::Zero);
+        let neuron = Neuron::random(&mut rng, 4, &topology);
+
+        assert_eq!(neuron.bias, Some(0.0));
+        assert_eq!(neuron.weights.len(), 4);
+    }
 }
@@ -0,0 +1,254 @@
+use crate::*;
+
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+/// Optional names of a network's inputs and outputs (e.g. `"eye_3"` or
+/// `"rotation"`), so that callers don't have to rely on index conventions.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Names {
+    inputs: Option<Vec<String>>,
+    outputs: Option<Vec<String>>,
+}
+
+impl Network {
+    /// Names this network's inputs, one name per input.
+    pub fn with_input_names<S: Into<String>>(
+        mut self,
+        names: impl IntoIterator<Item = S>,
+    ) -> Result<Self, NamesError> {
+        self.names.inputs = Some(checked_names(names, self.input_size())?);
+        Ok(self)
+    }
+
+    /// Names this network's outputs, one name per output.
+    pub fn with_output_names<S: Into<String>>(
+        mut self,
+        names: impl IntoIterator<Item = S>,
+    ) -> Result<Self, NamesError> {
+        self.names.outputs = Some(checked_names(names, self.output_size())?);
+        Ok(self)
+    }
+
+    pub fn input_names(&self) -> Option<&[String]> {
+        self.names.inputs.as_deref()
+    }
+
+    pub fn output_names(&self) -> Option<&[String]> {
+        self.names.outputs.as_deref()
+    }
+
+    pub fn input_index(&self, name: &str) -> Option<usize> {
+        position(self.input_names()?, name)
+    }
+
+    pub fn output_index(&self, name: &str) -> Option<usize> {
+        position(self.output_names()?, name)
+    }
+
+    /// Forgets names that don't fit anymore, e.g. after `insert_layer()`
+    /// has changed the number of inputs.
+    pub(crate) fn refresh_names(&mut self) {
+        let input_size = self.input_size();
+        let output_size = self.output_size();
+
+        if let Some(inputs) = &self.names.inputs {
+            if inputs.len() != input_size {
+                self.names.inputs = None;
+            }
+        }
+
+        if let Some(outputs) = &self.names.outputs {
+            if outputs.len() != output_size {
+                self.names.outputs = None;
+            }
+        }
+    }
+
+    /// Propagates inputs given by their names, returning outputs keyed by
+    /// their names; all of the network's inputs have to be provided.
+    #[cfg(feature = "std")]
+    pub fn propagate_named(
+        &self,
+        inputs: &HashMap<&str, Float>,
+    ) -> Result<HashMap<&str, Float>, NamesError> {
+        let input_names = self.input_names().ok_or(NamesError::Unnamed)?;
+        let output_names = self.output_names().ok_or(NamesError::Unnamed)?;
+
+        if let Some(name) = inputs
+            .keys()
+            .find(|name| position(input_names, name).is_none())
+        {
+            return Err(NamesError::Unknown {
+                name: name.to_string(),
+            });
+        }
+
+        let inputs = input_names
+            .iter()
+            .map(|name| {
+                inputs
+                    .get(name.as_str())
+                    .copied()
+                    .ok_or_else(|| NamesError::Missing { name: name.clone() })
+            })
+            .collect::<Result<_, _>>()?;
+
+        let outputs = self.propagate(inputs);
+
+        Ok(output_names
+            .iter()
+            .map(String::as_str)
+            .zip(outputs)
+            .collect())
+    }
+}
+
+impl NetworkBuilder {
+    /// Same as `input()`, but also names the inputs.
+    pub fn input_named<S: Into<String>>(self, names: impl IntoIterator<Item = S>) -> Self {
+        let names: Vec<_> = names.into_iter().map(Into::into).collect();
+        let mut this = self.input(names.len());
+
+        this.input_names = Some(names);
+        this
+    }
+
+    /// Same as `output()`, but also names the outputs.
+    pub fn output_named<S: Into<String>>(
+        self,
+        names: impl IntoIterator<Item = S>,
+        activation: Activation,
+    ) -> Self {
+        let names: Vec<_> = names.into_iter().map(Into::into).collect();
+        let mut this = self.output(names.len(), activation);
+
+        this.output_names = Some(names);
+        this
+    }
+
+    /// Applies names given through `input_named()` and `output_named()`.
+    pub fn name(&self, network: Network) -> Result<Network, NamesError> {
+        let mut network = network;
+
+        if let Some(names) = &self.input_names {
+            network = network.with_input_names(names.iter().cloned())?;
+        }
+
+        if let Some(names) = &self.output_names {
+            network = network.with_output_names(names.iter().cloned())?;
+        }
+
+        Ok(network)
+    }
+}
+
+fn checked_names<S: Into<String>>(
+    names: impl IntoIterator<Item = S>,
+    expected: usize,
+) -> Result<Vec<String>, NamesError> {
+    let names: Vec<String> = names.into_iter().map(Into::into).collect();
+
+    if names.len() != expected {
+        return Err(NamesError::WrongCount {
+            expected,
+            got: names.len(),
+        });
+    }
+
+    for (idx, name) in names.iter().enumerate() {
+        if names[..idx].contains(name) {
+            return Err(NamesError::Duplicate { name: name.clone() });
+        }
+    }
+
+    Ok(names)
+}
+
+fn position(names: &[String], name: &str) -> Option<usize> {
+    names.iter().position(|candidate| candidate == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use approx::assert_relative_eq;
+
+    fn network() -> Network {
+        let builder = Network::builder()
+            .input_named(["left", "right"])
+            .output_named(["sum", "diff"], Activation::Identity)
+            .bias(Bias::Disabled);
+
+        let network = Network::from_weights(&builder.topology(), vec![1.0, 1.0, 1.0, -1.0]);
+
+        builder.name(network).unwrap()
+    }
+
+    #[test]
+    fn indices() {
+        let network = network();
+
+        assert_eq!(network.input_index("right"), Some(1));
+        assert_eq!(network.output_index("diff"), Some(1));
+        assert_eq!(network.output_index("left"), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn propagate_named() {
+        let network = network();
+        let inputs = HashMap::from([("left", 2.0), ("right", 0.5)]);
+        let outputs = network.propagate_named(&inputs).unwrap();
+
+        assert_relative_eq!(outputs["sum"], 2.5);
+        assert_relative_eq!(outputs["diff"], 1.5);
+
+        assert_eq!(
+            network.propagate_named(&HashMap::from([("left", 2.0)])),
+            Err(NamesError::Missing {
+                name: "right".into()
+            })
+        );
+
+        assert_eq!(
+            network.propagate_named(&HashMap::from([("up", 2.0)])),
+            Err(NamesError::Unknown { name: "up".into() })
+        );
+    }
+
+    #[test]
+    fn invalid_names() {
+        assert_eq!(
+            network().with_input_names(["a"]).unwrap_err(),
+            NamesError::WrongCount {
+                expected: 2,
+                got: 1
+            }
+        );
+
+        assert_eq!(
+            network().with_output_names(["a", "a"]).unwrap_err(),
+            NamesError::Duplicate { name: "a".into() }
+        );
+    }
+
+    #[test]
+    fn insert_layer_forgets_stale_names() {
+        let mut network = network();
+
+        network
+            .insert_layer(0, Conv1d::new(2, 1, 1, Padding::Same))
+            .unwrap();
+
+        assert!(network.input_names().is_some());
+
+        network
+            .insert_layer(0, Conv1d::new(4, 3, 1, Padding::Valid))
+            .unwrap();
+
+        assert_eq!(network.input_names(), None);
+        assert!(network.output_names().is_some());
+    }
+}
@@ -0,0 +1,429 @@
+use crate::*;
+
+/// Storage format of `QuantizedNetwork`'s weights.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Precision {
+    /// IEEE 754 half-precision floats (2 bytes per weight).
+    F16,
+
+    /// Signed bytes scaled by a per-neuron factor (1 byte per weight).
+    I8,
+}
+
+/// Read-only copy of a `Network` with weights stored in reduced
+/// precision; weights get converted back to `Float` on the fly inside
+/// `propagate()`.
+///
+/// Biases (and learning rates of plastic neurons) are always kept as
+/// `Float`, since there's just one per neuron; custom layers are kept as
+/// they are.
+#[derive(Debug)]
+pub struct QuantizedNetwork {
+    layers: Vec<QuantizedNetworkLayer>,
+}
+
+#[derive(Debug)]
+enum QuantizedNetworkLayer {
+    Dense(QuantizedLayer),
+    Custom(Box<dyn Layer>),
+}
+
+impl Clone for QuantizedNetwork {
+    fn clone(&self) -> Self {
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| match layer {
+                QuantizedNetworkLayer::Dense(layer) => QuantizedNetworkLayer::Dense(layer.clone()),
+                QuantizedNetworkLayer::Custom(layer) => {
+                    QuantizedNetworkLayer::Custom(layer.clone_box())
+                }
+            })
+            .collect();
+
+        Self { layers }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct QuantizedLayer {
+    input_size: usize,
+    activation: Activation,
+    biases: Vec<Option<Float>>,
+    learning_rates: Vec<Option<Float>>,
+    weights: QuantizedWeights,
+}
+
+#[derive(Clone, Debug)]
+enum QuantizedWeights {
+    F16(Vec<u16>),
+    I8 { values: Vec<i8>, scales: Vec<Float> },
+}
+
+impl Network {
+    pub fn quantize(&self, precision: Precision) -> QuantizedNetwork {
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| match layer {
+                NetworkLayer::Dense(layer) => {
+                    QuantizedNetworkLayer::Dense(QuantizedLayer::new(layer, precision))
+                }
+                NetworkLayer::Custom(layer) => QuantizedNetworkLayer::Custom(layer.clone_box()),
+            })
+            .collect();
+
+        QuantizedNetwork { layers }
+    }
+}
+
+impl QuantizedNetwork {
+    pub fn propagate(&self, inputs: Vec<Float>) -> Vec<Float> {
+        self.layers
+            .iter()
+            .fold(inputs, |inputs, layer| match layer {
+                QuantizedNetworkLayer::Dense(layer) => layer.propagate(&inputs),
+                QuantizedNetworkLayer::Custom(layer) => layer.propagate(inputs),
+            })
+    }
+
+    /// Returns how many bytes the weights (without biases) of the dense
+    /// layers occupy.
+    pub fn weights_size(&self) -> usize {
+        self.layers
+            .iter()
+            .filter_map(|layer| match layer {
+                QuantizedNetworkLayer::Dense(layer) => Some(layer),
+                QuantizedNetworkLayer::Custom(_) => None,
+            })
+            .map(|layer| match &layer.weights {
+                QuantizedWeights::F16(values) => 2 * values.len(),
+                QuantizedWeights::I8 { values, scales } => values.len() + 4 * scales.len(),
+            })
+            .sum()
+    }
+
+    /// Converts this network back to full precision (minus whatever got
+    /// lost during the quantization).
+    pub fn dequantize(&self) -> Network {
+        let layers = self
+            .layers
+            .iter()
+            .map(|layer| {
+                let layer = match layer {
+                    QuantizedNetworkLayer::Dense(layer) => layer,
+                    QuantizedNetworkLayer::Custom(layer) => {
+                        return NetworkLayer::Custom(layer.clone_box());
+                    }
+                };
+
+                let neurons = layer
+                    .biases
+                    .iter()
+                    .enumerate()
+                    .map(|(neuron, &bias)| Neuron {
+                        bias,
+                        weights: layer.neuron_weights(neuron).collect(),
+                        mask: None,
+                        learning_rate: layer.learning_rates[neuron],
+                    })
+                    .collect();
+
+                NetworkLayer::Dense(DenseLayer {
+                    neurons,
+                    activation: layer.activation,
+                })
+            })
+            .collect();
+
+        Network {
+            layers,
+            names: Names::default(),
+        }
+    }
+}
+
+impl QuantizedLayer {
+    fn new(layer: &DenseLayer, precision: Precision) -> Self {
+        let input_size = layer.neurons[0].weights.len();
+        let biases = layer.neurons.iter().map(|neuron| neuron.bias).collect();
+
+        let learning_rates = layer
+            .neurons
+            .iter()
+            .map(|neuron| neuron.learning_rate)
+            .collect();
+
+        let weights = match precision {
+            Precision::F16 => QuantizedWeights::F16(
+                layer
+                    .neurons
+                    .iter()
+                    .flat_map(|neuron| &neuron.weights)
+                    .map(|&weight| f32_to_f16(to_f32(weight)))
+                    .collect(),
+            ),
+
+            Precision::I8 => {
+                let scales: Vec<_> = layer
+                    .neurons
+                    .iter()
+                    .map(|neuron| {
+                        let max = neuron
+                            .weights
+                            .iter()
+                            .fold(0.0, |max: Float, weight| max.max(weight.abs()));
+
+                        max / (i8::MAX as Float)
+                    })
+                    .collect();
+
+                let values = layer
+                    .neurons
+                    .iter()
+                    .zip(&scales)
+                    .flat_map(|(neuron, &scale)| {
+                        neuron.weights.iter().map(move |&weight| {
+                            if scale > 0.0 {
+                                round_to_i8((weight / scale).clamp(-127.0, 127.0))
+                            } else {
+                                0
+                            }
+                        })
+                    })
+                    .collect();
+
+                QuantizedWeights::I8 { values, scales }
+            }
+        };
+
+        Self {
+            input_size,
+            activation: layer.activation,
+            biases,
+            learning_rates,
+            weights,
+        }
+    }
+
+    fn neuron_weights(&self, neuron: usize) -> Box<dyn Iterator<Item = Float> + '_> {
+        let range = (neuron * self.input_size)..((neuron + 1) * self.input_size);
+
+        match &self.weights {
+            QuantizedWeights::F16(values) => Box::new(
+                values[range]
+                    .iter()
+                    .map(|&value| f16_to_f32(value) as Float),
+            ),
+
+            QuantizedWeights::I8 { values, scales } => {
+                let scale = scales[neuron];
+                Box::new(
+                    values[range]
+                        .iter()
+                        .map(move |&value| value as Float * scale),
+                )
+            }
+        }
+    }
+
+    fn propagate(&self, inputs: &[Float]) -> Vec<Float> {
+        assert_eq!(inputs.len(), self.input_size);
+
+        (0..self.biases.len())
+            .map(|neuron| {
+                let output = inputs
+                    .iter()
+                    .zip(self.neuron_weights(neuron))
+                    .map(|(input, weight)| input * weight)
+                    .sum::<Float>();
+
+                self.activation
+                    .apply(self.biases[neuron].unwrap_or(0.0) + output)
+            })
+            .collect()
+    }
+}
+
+/// Rounds half away from zero, just like `f32::round()` (which requires
+/// `std`).
+fn round_to_i8(value: Float) -> i8 {
+    if value >= 0.0 {
+        (value + 0.5) as i8
+    } else {
+        (value - 0.5) as i8
+    }
+}
+
+fn to_f32(value: Float) -> f32 {
+    // No-op for the default precision
+    #[allow(clippy::unnecessary_cast)]
+    let value = value as f32;
+
+    value
+}
+
+/// Converts `f32` into bits of an IEEE 754 half-precision float, rounding
+/// to nearest-even.
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let mant = bits & 0x007f_ffff;
+
+    // Infinity or NaN
+    if exp == 0xff {
+        return sign | 0x7c00 | if mant != 0 { 0x0200 } else { 0 };
+    }
+
+    let exp = exp - 127 + 15;
+
+    // Too large - saturates to infinity
+    if exp >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    // Too small for a normal half - becomes subnormal or zero
+    if exp <= 0 {
+        if exp < -10 {
+            return sign;
+        }
+
+        let mant = mant | 0x0080_0000;
+        let shift = (14 - exp) as u32;
+
+        return sign | round_shifted(mant, shift) as u16;
+    }
+
+    // Note that when rounding overflows the mantissa, it nicely carries
+    // into the exponent (up to infinity)
+    sign | round_shifted(((exp as u32) << 23) | mant, 13) as u16
+}
+
+/// Shifts `value` right by `shift` bits, rounding to nearest-even.
+fn round_shifted(value: u32, shift: u32) -> u32 {
+    let round_bit = 1 << (shift - 1);
+    let shifted = value >> shift;
+
+    // Round up when above the halfway point or, when exactly halfway,
+    // when that makes the result even
+    if (value & round_bit) != 0 && (value & (3 * round_bit - 1)) != 0 {
+        shifted + 1
+    } else {
+        shifted
+    }
+}
+
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = ((half & 0x8000) as u32) << 16;
+    let exp = ((half >> 10) & 0x1f) as u32;
+    let mant = (half & 0x03ff) as u32;
+
+    let bits = match (exp, mant) {
+        (0, 0) => sign,
+
+        // Subnormal - normalize it, since in `f32` it's a normal number
+        (0, _) => {
+            let shift = mant.leading_zeros() - 21;
+            let mant = (mant << shift) & 0x03ff;
+
+            sign | ((127 - 15 + 1 - shift) << 23) | (mant << 13)
+        }
+
+        (0x1f, _) => sign | 0x7f80_0000 | (mant << 13),
+
+        _ => sign | ((exp + 127 - 15) << 23) | (mant << 13),
+    };
+
+    f32::from_bits(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    #[cfg(feature = "rand")]
+    use rand::SeedableRng;
+    #[cfg(feature = "rand")]
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn f16_conversion() {
+        assert_eq!(f32_to_f16(0.0), 0x0000);
+        assert_eq!(f32_to_f16(-0.0), 0x8000);
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f32_to_f16(-2.0), 0xc000);
+        assert_eq!(f32_to_f16(65504.0), 0x7bff);
+        assert_eq!(f32_to_f16(1e6), 0x7c00);
+        assert_eq!(f32_to_f16(2.0f32.powi(-24)), 0x0001);
+        assert_eq!(f32_to_f16(1e-10), 0x0000);
+
+        for value in [0.0, 1.0, -2.0, 0.5, 65504.0, 2.0f32.powi(-24), 0.333] {
+            let actual = f16_to_f32(f32_to_f16(value));
+            assert_relative_eq!(actual, value, max_relative = 1e-3);
+        }
+
+        assert!(f16_to_f32(f32_to_f16(f32::NAN)).is_nan());
+    }
+
+    #[test]
+    fn f16_rounds_to_nearest_even() {
+        // 1 + 2^-11 is exactly between 1.0 and the next half
+        assert_eq!(f32_to_f16(1.0 + 2.0f32.powi(-11)), 0x3c00);
+
+        // 1 + 3 * 2^-11 is exactly between the first and second halves
+        // after 1.0, so it rounds to the even (second) one
+        assert_eq!(f32_to_f16(1.0 + 3.0 * 2.0f32.powi(-11)), 0x3c02);
+    }
+
+    #[cfg(feature = "rand")]
+    fn network() -> Network {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        Network::random(
+            &mut rng,
+            &[
+                LayerTopology::new(4),
+                LayerTopology::new(8),
+                LayerTopology::new(2),
+            ],
+        )
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn propagate() {
+        let network = network();
+        let inputs = vec![0.1, 0.7, -0.3, 0.9];
+        let expected = network.propagate(inputs.clone());
+
+        for (precision, epsilon) in [(Precision::F16, 1e-3), (Precision::I8, 5e-2)] {
+            let actual = network.quantize(precision).propagate(inputs.clone());
+
+            assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = epsilon);
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn weights_size() {
+        let network = network();
+
+        assert_eq!(network.quantize(Precision::F16).weights_size(), 2 * 48);
+        assert_eq!(network.quantize(Precision::I8).weights_size(), 48 + 4 * 10);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn dequantize() {
+        let network = network();
+        let expected: Vec<_> = network.weights().collect();
+        let actual: Vec<_> = network
+            .quantize(Precision::F16)
+            .dequantize()
+            .weights()
+            .collect();
+
+        assert_relative_eq!(actual.as_slice(), expected.as_slice(), epsilon = 1e-3);
+    }
+}
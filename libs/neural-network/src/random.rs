@@ -0,0 +1,121 @@
+use crate::*;
+use rand::{Rng, RngCore};
+
+#[cfg(not(feature = "f64"))]
+use core::f32::consts::PI;
+
+#[cfg(feature = "f64")]
+use core::f64::consts::PI;
+
+/// Learning rates of plastic neurons start in U(-x, x); they are small
+/// so that networks don't change too abruptly before evolution has had a
+/// chance to tune them.
+const LEARNING_RATE_INIT: Float = 0.1;
+
+impl Network {
+    pub fn random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Self {
+        Self::try_random(rng, layers).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_random(
+        rng: &mut dyn RngCore,
+        layers: &[LayerTopology],
+    ) -> Result<Self, TopologyError> {
+        Self::validate(layers)?;
+
+        let layers = layers
+            .windows(2)
+            .map(|layers| {
+                NetworkLayer::Dense(DenseLayer::random(rng, layers[0].neurons, &layers[1]))
+            })
+            .collect();
+
+        Ok(Self {
+            layers,
+            names: Names::default(),
+        })
+    }
+}
+
+impl Bias {
+    fn sample(self, rng: &mut dyn RngCore) -> Option<Float> {
+        match self {
+            Bias::Disabled => None,
+            Bias::Zero => Some(0.0),
+            Bias::Uniform => Some(rng.gen_range(-1.0..=1.0)),
+            Bias::Normal => Some(standard_normal(rng)),
+        }
+    }
+}
+
+impl WeightInit {
+    fn sample(self, rng: &mut dyn RngCore, fan_in: usize, fan_out: usize) -> Float {
+        let fan_in = fan_in.max(1) as Float;
+        let fan_out = fan_out.max(1) as Float;
+
+        match self {
+            WeightInit::Uniform => rng.gen_range(-1.0..=1.0),
+            WeightInit::XavierUniform => {
+                let limit = (6.0 / (fan_in + fan_out)).sqrt();
+                rng.gen_range(-limit..=limit)
+            }
+            WeightInit::XavierNormal => (2.0 / (fan_in + fan_out)).sqrt() * standard_normal(rng),
+            WeightInit::HeUniform => {
+                let limit = (6.0 / fan_in).sqrt();
+                rng.gen_range(-limit..=limit)
+            }
+            WeightInit::HeNormal => (2.0 / fan_in).sqrt() * standard_normal(rng),
+        }
+    }
+}
+
+/// Samples N(0, 1) using the Box-Muller transform.
+fn standard_normal(rng: &mut dyn RngCore) -> Float {
+    // `gen()` returns [0, 1), so we flip it to (0, 1] to keep `ln()` finite
+    let u1 = 1.0 - rng.gen::<Float>();
+    let u2 = rng.gen::<Float>();
+
+    (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos()
+}
+
+impl DenseLayer {
+    pub(crate) fn random(
+        rng: &mut dyn RngCore,
+        input_size: usize,
+        topology: &LayerTopology,
+    ) -> Self {
+        let neurons = (0..topology.neurons)
+            .map(|_| Neuron::random(rng, input_size, topology))
+            .collect();
+
+        Self {
+            neurons,
+            activation: topology.activation,
+        }
+    }
+}
+
+impl Neuron {
+    pub(crate) fn random(
+        rng: &mut dyn RngCore,
+        input_size: usize,
+        topology: &LayerTopology,
+    ) -> Self {
+        let bias = topology.bias.sample(rng);
+
+        let weights = (0..input_size)
+            .map(|_| topology.init.sample(rng, input_size, topology.neurons))
+            .collect();
+
+        let learning_rate = topology
+            .plasticity
+            .then(|| rng.gen_range(-LEARNING_RATE_INIT..=LEARNING_RATE_INIT));
+
+        Self {
+            bias,
+            weights,
+            mask: None,
+            learning_rate,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::*;
+
+/// Weight-magnitude penalty, for fitness functions that want to
+/// discourage exploding weights.
+///
+/// Penalty is typically subtracted from the fitness score; see
+/// `examples/regularized_fitness.rs`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Regularization {
+    /// `coeff * Σ|w|` - pushes small weights towards zero.
+    L1(Float),
+
+    /// `coeff * Σw²` - punishes mostly the largest weights.
+    L2(Float),
+}
+
+impl Regularization {
+    /// Computes the penalty over arbitrary weights - e.g. over genes of
+    /// a chromosome.
+    pub fn penalty(self, weights: impl IntoIterator<Item = Float>) -> Float {
+        let weights = weights.into_iter();
+
+        match self {
+            Self::L1(coeff) => coeff * weights.map(|weight| weight.abs()).sum::<Float>(),
+            Self::L2(coeff) => coeff * weights.map(|weight| weight * weight).sum::<Float>(),
+        }
+    }
+}
+
+impl Network {
+    /// Computes the penalty over all of this network's weights (biases
+    /// and learning rates included).
+    pub fn penalty(&self, regularization: Regularization) -> Float {
+        regularization.penalty(self.weights())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn penalty() {
+        let weights = [0.5, -2.0, 1.0];
+
+        assert_relative_eq!(Regularization::L1(0.1).penalty(weights), 0.35);
+        assert_relative_eq!(Regularization::L2(0.1).penalty(weights), 0.525);
+    }
+
+    #[test]
+    fn network_penalty() {
+        let layers = [LayerTopology::new(2), LayerTopology::new(1)];
+        let network = Network::from_weights(&layers, vec![1.0, -1.0, 2.0]);
+
+        assert_relative_eq!(network.penalty(Regularization::L1(1.0)), 4.0);
+        assert_relative_eq!(network.penalty(Regularization::L2(1.0)), 6.0);
+    }
+}
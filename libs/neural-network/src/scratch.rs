@@ -0,0 +1,78 @@
+use crate::*;
+
+/// Buffers reused by `Network::propagate_with()`, so that - once they've
+/// grown to fit the widest layer - propagating doesn't allocate at all.
+///
+/// One scratch can be shared by any number of networks (e.g. all the
+/// birds of a simulation), as long as they're propagated one at a time.
+#[derive(Clone, Debug, Default)]
+pub struct PropagationScratch {
+    inputs: Vec<Float>,
+    outputs: Vec<Float>,
+}
+
+impl PropagationScratch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Network {
+    /// Same as `propagate()`, but uses `scratch` instead of allocating
+    /// a new vector for each layer.
+    ///
+    /// Custom layers that don't override `Layer::propagate_into()` still
+    /// allocate.
+    pub fn propagate_with<'s>(
+        &self,
+        inputs: impl IntoIterator<Item = Float>,
+        scratch: &'s mut PropagationScratch,
+    ) -> &'s [Float] {
+        let PropagationScratch {
+            inputs: layer_inputs,
+            outputs: layer_outputs,
+        } = scratch;
+
+        layer_inputs.clear();
+        layer_inputs.extend(inputs);
+
+        for layer in &self.layers {
+            layer_outputs.clear();
+            layer.as_layer().propagate_into(layer_inputs, layer_outputs);
+
+            core::mem::swap(layer_inputs, layer_outputs);
+        }
+
+        layer_inputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn propagate_with() {
+        let layers = [
+            LayerTopology::new(3),
+            LayerTopology::new(4),
+            LayerTopology::new(2).with_activation(Activation::Tanh),
+        ];
+
+        let weights = (0..Network::weight_count(&layers)).map(|n| (n as Float).sin());
+        let mut network = Network::from_weights(&layers, weights);
+
+        network
+            .insert_layer(0, Conv1d::new(3, 2, 1, Padding::Same))
+            .unwrap();
+
+        let mut scratch = PropagationScratch::new();
+
+        for inputs in [[0.1, 0.2, 0.3], [-1.0, 0.5, 2.0]] {
+            let expected = network.propagate(inputs.to_vec());
+            let actual = network.propagate_with(inputs, &mut scratch);
+
+            assert_eq!(actual, expected.as_slice());
+        }
+    }
+}
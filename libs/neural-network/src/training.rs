@@ -0,0 +1,205 @@
+use crate::*;
+
+/// Recorded pair of what the network saw and what it should've answered.
+#[derive(Clone, Debug)]
+pub struct Sample {
+    pub inputs: Vec<Float>,
+    pub targets: Vec<Float>,
+}
+
+/// Plain stochastic gradient descent (backpropagation over the mean
+/// squared error), for fine-tuning evolved networks during their
+/// lifetime.
+///
+/// After training, the improved weights can be written back into the
+/// genome (Lamarckian evolution) by simply collecting
+/// `Network::weights()` into a new chromosome.
+#[derive(Clone, Debug)]
+pub struct Trainer {
+    learning_rate: Float,
+    epochs: usize,
+}
+
+impl Trainer {
+    pub fn new(learning_rate: Float, epochs: usize) -> Self {
+        assert!(learning_rate > 0.0);
+
+        Self {
+            learning_rate,
+            epochs,
+        }
+    }
+
+    /// Trains `network` on `samples`, returning the mean squared error
+    /// from before the last epoch's updates.
+    ///
+    /// Connections disconnected by a mask stay disconnected; learning
+    /// rates of plastic neurons are left as they are.
+    ///
+    /// Gradients can't flow through custom layers, so only the dense
+    /// layers after the last custom one get trained.
+    pub fn train(&self, network: &mut Network, samples: &[Sample]) -> Float {
+        let mut error = 0.0;
+
+        for _ in 0..self.epochs {
+            error = samples
+                .iter()
+                .map(|sample| self.train_sample(network, sample))
+                .sum::<Float>()
+                / samples.len().max(1) as Float;
+        }
+
+        error
+    }
+
+    /// Returns the mean squared error of `network` over `samples`.
+    pub fn error(network: &Network, samples: &[Sample]) -> Float {
+        samples
+            .iter()
+            .map(|sample| squared_error(&network.propagate(sample.inputs.clone()), &sample.targets))
+            .sum::<Float>()
+            / samples.len().max(1) as Float
+    }
+
+    fn train_sample(&self, network: &mut Network, sample: &Sample) -> Float {
+        let trace = network.propagate_traced(sample.inputs.clone());
+        let activations = trace.activations;
+        let outputs = activations.last().unwrap();
+        let error = squared_error(outputs, &sample.targets);
+
+        // Activation of each layer, `None` standing for custom layers
+        let layer_activations: Vec<_> = network
+            .layers
+            .iter()
+            .map(|layer| layer.as_dense().map(|layer| layer.activation))
+            .collect();
+
+        let Some(Some(output_activation)) = layer_activations.last().copied() else {
+            return error;
+        };
+
+        let mut deltas: Vec<Float> = outputs
+            .iter()
+            .zip(&sample.targets)
+            .map(|(output, target)| (output - target) * output_activation.derivative(*output))
+            .collect();
+
+        for (layer_idx, layer) in network.layers.iter_mut().enumerate().rev() {
+            let Some(layer) = layer.as_dense_mut() else {
+                break;
+            };
+
+            let inputs = &activations[layer_idx];
+
+            // Deltas of the previous layer have to be computed using the
+            // weights from before this step's update
+            let prev_activation = layer_idx
+                .checked_sub(1)
+                .and_then(|prev_idx| layer_activations[prev_idx]);
+
+            let prev_deltas = if let Some(prev_activation) = prev_activation {
+                (0..inputs.len())
+                    .map(|input_idx| {
+                        let sum = layer
+                            .neurons
+                            .iter()
+                            .zip(&deltas)
+                            .map(|(neuron, delta)| neuron.weights[input_idx] * delta)
+                            .sum::<Float>();
+
+                        sum * prev_activation.derivative(inputs[input_idx])
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            for (neuron, delta) in layer.neurons.iter_mut().zip(&deltas) {
+                if let Some(bias) = &mut neuron.bias {
+                    *bias -= self.learning_rate * delta;
+                }
+
+                for (input_idx, (weight, input)) in
+                    neuron.weights.iter_mut().zip(inputs).enumerate()
+                {
+                    if neuron.mask.as_ref().is_none_or(|mask| mask[input_idx]) {
+                        *weight -= self.learning_rate * delta * input;
+                    }
+                }
+            }
+
+            deltas = prev_deltas;
+        }
+
+        error
+    }
+}
+
+fn squared_error(outputs: &[Float], targets: &[Float]) -> Float {
+    assert_eq!(outputs.len(), targets.len());
+
+    outputs
+        .iter()
+        .zip(targets)
+        .map(|(output, target)| (output - target) * (output - target))
+        .sum::<Float>()
+        / outputs.len() as Float
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "rand")]
+    use rand::SeedableRng;
+    #[cfg(feature = "rand")]
+    use rand_chacha::ChaCha8Rng;
+
+    fn samples() -> Vec<Sample> {
+        // y = x1 + 0.5 * x2
+        (0..20)
+            .map(|n| {
+                let x1 = (n % 5) as Float / 5.0;
+                let x2 = (n / 5) as Float / 4.0;
+
+                Sample {
+                    inputs: vec![x1, x2],
+                    targets: vec![x1 + 0.5 * x2],
+                }
+            })
+            .collect()
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn train() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let layers = [
+            LayerTopology::new(2),
+            LayerTopology::new(4),
+            LayerTopology::new(1).with_bias(Bias::Zero),
+        ];
+
+        let mut network = Network::random(&mut rng, &layers);
+        let samples = samples();
+        let error_before = Trainer::error(&network, &samples);
+
+        Trainer::new(0.05, 200).train(&mut network, &samples);
+
+        let error_after = Trainer::error(&network, &samples);
+
+        assert!(error_after < error_before / 10.0);
+        assert!(error_after < 0.01);
+    }
+
+    #[test]
+    fn train_keeps_masked_connections_disconnected() {
+        let layers = [LayerTopology::new(2), LayerTopology::new(1)];
+        let mut network =
+            Network::from_weights_and_mask(&layers, vec![0.1, 0.5, 0.5], [true, false]);
+
+        Trainer::new(0.05, 50).train(&mut network, &samples());
+
+        assert_eq!(network.weights().nth(2), Some(0.0));
+    }
+}
@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+/// Command-line arguments - positional ones, `--option value` pairs and
+/// `--switch`es.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Args {
+    pub positional: Vec<String>,
+    options: Vec<(String, String)>,
+    switches: Vec<String>,
+}
+
+impl Args {
+    /// Parses arguments, accepting only given options and switches (named
+    /// without the leading `--`).
+    pub fn parse(
+        args: impl IntoIterator<Item = String>,
+        options: &[&str],
+        switches: &[&str],
+    ) -> Result<Self, String> {
+        let mut this = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                this.positional.push(arg);
+                continue;
+            };
+
+            if switches.contains(&name) {
+                this.switches.push(name.to_string());
+                continue;
+            }
+
+            if !options.contains(&name) {
+                return Err(format!("unknown option `--{name}`"));
+            }
+
+            let value = args
+                .next()
+                .ok_or_else(|| format!("`--{name}` requires a value"))?;
+
+            this.options.push((name.to_string(), value));
+        }
+
+        Ok(this)
+    }
+
+    /// Returns value of given option (the last one, if it's been given
+    /// multiple times).
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn parsed_option<T: FromStr>(&self, name: &str) -> Result<Option<T>, String> {
+        self.option(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| format!("invalid value of `--{name}`: `{value}`"))
+            })
+            .transpose()
+    }
+
+    pub fn switch(&self, name: &str) -> bool {
+        self.switches.iter().any(|switch| switch == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()), &["out"], &["quiet"])
+    }
+
+    #[test]
+    fn parse() {
+        let args = args(&[
+            "train",
+            "--out",
+            "a",
+            "--quiet",
+            "experiment.json",
+            "--out",
+            "b",
+        ])
+        .unwrap();
+
+        assert_eq!(args.positional, ["train", "experiment.json"]);
+        assert_eq!(args.option("out"), Some("b"));
+        assert_eq!(args.option("seed"), None);
+        assert!(args.switch("quiet"));
+        assert!(!args.switch("out"));
+
+        assert_eq!(
+            args.parsed_option::<usize>("out"),
+            Err("invalid value of `--out`: `b`".into())
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(args(&["--out"]), Err("`--out` requires a value".into()));
+        assert_eq!(
+            args(&["--seed", "1"]),
+            Err("unknown option `--seed`".into())
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use crate::*;
+
+/// Trains two experiments with the same `seeds` and compares their final
+/// fitness - mean and standard deviation of each, along with the p-value of
+/// Mann-Whitney U test on whether either of them tends to do better.
+pub fn compare(a: &Path, b: &Path, seeds: u64) -> Result<(), String> {
+    if seeds < 2 {
+        return Err("comparison needs at least two seeds".into());
+    }
+
+    let a = (a.display().to_string(), ExperimentFile::parse(&read(a)?)?);
+    let b = (b.display().to_string(), ExperimentFile::parse(&read(b)?)?);
+
+    let results = [&a, &b].map(|(name, experiment)| {
+        let results = experiment.batch(0..seeds).run();
+
+        for run in &results.runs {
+            if let Some(stats) = run.history.last() {
+                eprintln!(
+                    "{name}, seed={}: max={:.2}, avg={:.2}",
+                    run.seed, stats.max_fitness, stats.avg_fitness
+                );
+            }
+        }
+
+        [
+            results.finals(|stats| stats.max_fitness),
+            results.finals(|stats| stats.avg_fitness),
+        ]
+    });
+
+    let width = a.0.len().max(b.0.len());
+
+    for (metric, idx) in [("max fitness", 0), ("avg fitness", 1)] {
+        let samples = results.each_ref().map(|results| {
+            results[idx]
+                .iter()
+                .map(|&result| result as f64)
+                .collect::<Vec<_>>()
+        });
+
+        println!("{metric}:");
+
+        for ((name, _), samples) in [&a, &b].into_iter().zip(&samples) {
+            let (mean, stddev) = mean_stddev(samples);
+            println!("    {name:width$}  mean={mean:.3}  stddev={stddev:.3}");
+        }
+
+        let (u, p) = mann_whitney_u(&samples[0], &samples[1]);
+        println!("    U={u:.1}, p={p:.4}");
+    }
+
+    Ok(())
+}
+
+/// Returns mean and (sample) standard deviation.
+fn mean_stddev(samples: &[f64]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+
+    let variance = samples
+        .iter()
+        .map(|sample| (sample - mean).powi(2))
+        .sum::<f64>()
+        / (n - 1.0).max(1.0);
+
+    (mean, variance.sqrt())
+}
+
+/// Performs two-sided Mann-Whitney U test, returning `U` of the first
+/// sample and the p-value.
+///
+/// The p-value comes from the normal approximation (corrected for ties and
+/// continuity), which gets reasonable from around eight samples per side.
+fn mann_whitney_u(a: &[f64], b: &[f64]) -> (f64, f64) {
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+
+    let mut samples: Vec<_> = a
+        .iter()
+        .map(|&value| (value, true))
+        .chain(b.iter().map(|&value| (value, false)))
+        .collect();
+
+    samples.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    // Ties get the average of the ranks they span
+    let mut rank_sum = 0.0;
+    let mut ties = 0.0;
+    let mut start = 0;
+
+    while start < samples.len() {
+        let end = start
+            + samples[start..]
+                .iter()
+                .take_while(|sample| sample.0 == samples[start].0)
+                .count();
+
+        let rank = (start + end + 1) as f64 / 2.0;
+        let count = (end - start) as f64;
+
+        rank_sum += rank * samples[start..end].iter().filter(|sample| sample.1).count() as f64;
+        ties += count.powi(3) - count;
+        start = end;
+    }
+
+    let u = rank_sum - n1 * (n1 + 1.0) / 2.0;
+    let mean = n1 * n2 / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - ties / (n * (n - 1.0)));
+
+    if variance <= 0.0 {
+        return (u, 1.0);
+    }
+
+    let z = ((u - mean).abs() - 0.5).max(0.0) / variance.sqrt();
+
+    (u, erfc(z / std::f64::consts::SQRT_2).min(1.0))
+}
+
+/// Complementary error function, accurate to about `1e-7` (Abramowitz and
+/// Stegun, 7.1.26).
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+
+    let erfc = poly * (-x * x).exp();
+
+    if x >= 0.0 {
+        erfc
+    } else {
+        2.0 - erfc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn mann_whitney_u() {
+        let (u, p) = super::mann_whitney_u(&[1.0, 2.0, 3.0, 4.0, 5.0], &[6.0, 7.0, 8.0, 9.0, 10.0]);
+
+        assert_eq!(u, 0.0);
+        assert!((p - 0.0122).abs() < 1e-3, "p = {p}");
+
+        // Identical samples are as alike as it gets
+        let (u, p) = super::mann_whitney_u(&[1.0, 2.0, 2.0], &[1.0, 2.0, 2.0]);
+
+        assert_eq!(u, 4.5);
+        assert!(p > 0.999, "p = {p}");
+    }
+
+    #[test]
+    fn mean_stddev() {
+        let (mean, stddev) = super::mean_stddev(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+
+        assert_eq!(mean, 5.0);
+        assert!((stddev - 2.138).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,150 @@
+use crate::*;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+/// Number of the most recent generations the sparkline shows
+const SPARKLINE_LEN: usize = 60;
+
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Live summary of a training, redrawn in the terminal after each
+/// generation (see `train --dashboard`).
+pub struct Dashboard {
+    generations: usize,
+    started_at: Instant,
+
+    /// Generation the training has started (or resumed) at, for the ETA
+    first_generation: usize,
+
+    /// Best fitness of each generation trained so far
+    max_fitness: Vec<f32>,
+}
+
+impl Dashboard {
+    /// Creates dashboard of a training going up to `generations`, starting
+    /// (or resuming) at `first_generation`.
+    pub fn new(generations: usize, first_generation: usize) -> Self {
+        Self {
+            generations,
+            started_at: Instant::now(),
+            first_generation,
+            max_fitness: Vec::new(),
+        }
+    }
+
+    pub fn update(&mut self, trainer: &sim::ParallelTrainer, stats: &[sim::Statistics]) {
+        self.max_fitness.push(
+            stats
+                .iter()
+                .map(|stats| stats.max_fitness)
+                .fold(f32::MIN, f32::max),
+        );
+
+        let diversity = trainer
+            .simulations()
+            .iter()
+            .map(sim::Simulation::diversity)
+            .sum::<f32>()
+            / trainer.simulations().len() as f32;
+
+        let text = self.render(
+            trainer.generations(),
+            stats,
+            diversity,
+            self.started_at.elapsed(),
+        );
+
+        // Clears the screen and moves the cursor to its top-left corner
+        let mut stderr = std::io::stderr();
+        _ = write!(stderr, "\x1b[2J\x1b[H{text}");
+        _ = stderr.flush();
+    }
+
+    fn render(
+        &self,
+        generation: usize,
+        stats: &[sim::Statistics],
+        diversity: f32,
+        elapsed: Duration,
+    ) -> String {
+        let done = generation - self.first_generation;
+        let remaining = self.generations.saturating_sub(generation);
+
+        let eta = if done > 0 {
+            elapsed.mul_f64(remaining as f64 / done as f64)
+        } else {
+            Duration::ZERO
+        };
+
+        let avg_fitness =
+            stats.iter().map(|stats| stats.avg_fitness).sum::<f32>() / stats.len().max(1) as f32;
+
+        format!(
+            "generation  {generation}/{}\n\
+             max fitness {:.2}\n\
+             avg fitness {avg_fitness:.2}\n\
+             diversity   {diversity:.4}\n\
+             eta         {}\n\
+             \n\
+             {}\n",
+            self.generations,
+            self.max_fitness.last().copied().unwrap_or_default(),
+            format_duration(eta),
+            sparkline(&self.max_fitness),
+        )
+    }
+}
+
+/// Draws the most recent values as a sparkline, scaled between the lowest
+/// and the highest of them.
+fn sparkline(values: &[f32]) -> String {
+    let values = &values[values.len().saturating_sub(SPARKLINE_LEN)..];
+
+    let min = values.iter().copied().fold(f32::MAX, f32::min);
+    let max = values.iter().copied().fold(f32::MIN, f32::max);
+
+    values
+        .iter()
+        .map(|value| {
+            let level = if max > min {
+                (value - min) / (max - min)
+            } else {
+                0.0
+            };
+
+            SPARKS[((level * (SPARKS.len() - 1) as f32).round() as usize).min(SPARKS.len() - 1)]
+        })
+        .collect()
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+
+    format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparkline() {
+        assert_eq!(super::sparkline(&[1.0, 2.0, 3.0, 8.0]), "▁▂▃█");
+        assert_eq!(super::sparkline(&[5.0, 5.0]), "▁▁");
+        assert_eq!(super::sparkline(&[]), "");
+    }
+
+    #[test]
+    fn render() {
+        let mut dashboard = Dashboard::new(10, 2);
+        dashboard.max_fitness = vec![1.0, 3.0];
+
+        let text = dashboard.render(4, &[], 0.25, Duration::from_secs(100));
+
+        assert!(text.starts_with("generation  4/10\nmax fitness 3.00\n"));
+
+        // Two generations have taken 100 s, so the remaining six need 300 s
+        assert!(text.contains("eta         0:05:00\n"));
+        assert!(text.ends_with("▁█\n"));
+    }
+}
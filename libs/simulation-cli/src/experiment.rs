@@ -0,0 +1,291 @@
+use lib_simulation as sim;
+use sim::Json;
+
+/// What to train and for how long, read from a JSON file:
+///
+/// ```json
+/// {
+///     "seed": 42,
+///     "generations": 500,
+///     "worlds": 4,
+///     "merge_every": 10,
+///     "config": { "population": 80, "mutation_chance": 0.05 }
+/// }
+/// ```
+///
+/// Everything's optional; knobs missing from `config` stay as in
+/// `sim::Config::default()` (see `set_knob()` for the supported ones).
+///
+/// `train` trains it as is, while `sweep` and `compare` turn it into a
+/// `sim::Experiment` (see `batch()`) - e.g. to compare tournament selection
+/// with the roulette wheel, through `"selection": "tournament"`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExperimentFile {
+    pub config: sim::Config,
+    pub seed: u64,
+    pub generations: usize,
+
+    /// Number of worlds trained side by side, each on its own thread (see
+    /// `sim::ParallelTrainer`)
+    pub worlds: usize,
+
+    /// Number of generations after which birds of all the worlds get mixed
+    pub merge_every: usize,
+}
+
+impl Default for ExperimentFile {
+    fn default() -> Self {
+        Self {
+            config: sim::Config::default(),
+            seed: 0,
+            generations: 100,
+            worlds: 1,
+            merge_every: 10,
+        }
+    }
+}
+
+impl ExperimentFile {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        Self::from_json(&parse_json(text)?)
+    }
+
+    pub fn from_json(json: &Json) -> Result<Self, String> {
+        let Json::Object(fields) = json else {
+            return Err("experiment has to be an object".into());
+        };
+
+        let mut this = Self::default();
+
+        for (name, value) in fields {
+            match name.as_str() {
+                "seed" => this.seed = number(name, value)?,
+                "generations" => this.generations = number(name, value)?,
+                "worlds" => this.worlds = number(name, value)?,
+                "merge_every" => this.merge_every = number(name, value)?,
+
+                "config" => {
+                    let Json::Object(knobs) = value else {
+                        return Err("`config` has to be an object".into());
+                    };
+
+                    for (knob, value) in knobs {
+                        set_knob(&mut this.config, knob, value)?;
+                    }
+                }
+
+                _ => return Err(format!("unknown field `{name}`")),
+            }
+        }
+
+        if this.worlds == 0 || this.merge_every == 0 {
+            return Err("`worlds` and `merge_every` have to be positive".into());
+        }
+
+        // Catches everything `sim::Simulation::new()` would panic on, before
+        // any training starts
+        this.config
+            .validate()
+            .map_err(|err| format!("invalid config: {err}"))?;
+
+        Ok(this)
+    }
+
+    /// Returns a batch of trainings of this experiment, one per seed (with
+    /// `seed` itself ignored); `worlds` and `merge_every` don't carry over,
+    /// since each seed gets trained in a single world.
+    pub fn batch(&self, seeds: impl IntoIterator<Item = u64>) -> sim::Experiment {
+        sim::Experiment {
+            config: self.config.clone(),
+            seeds: seeds.into_iter().collect(),
+            termination: sim::Termination::Generations(self.generations),
+        }
+    }
+}
+
+/// Returns the numeric knobs `set_knob()` supports (which are the same ones
+/// the wasm bindings' config has), as a JSON object.
+pub fn knobs(config: &sim::Config) -> Json {
+    Json::object([
+        ("population", Json::number(config.population)),
+        ("species", Json::number(config.species)),
+        ("foods", Json::number(config.foods)),
+        ("generation_length", Json::number(config.generation_length)),
+        ("mutation_chance", Json::number(config.mutation_chance)),
+        ("mutation_coeff", Json::number(config.mutation_coeff)),
+        ("eye_fov_range", Json::number(config.eye_fov_range)),
+        ("eye_fov_angle", Json::number(config.eye_fov_angle)),
+        ("eye_cells", Json::number(config.eye_cells)),
+        ("speed_min", Json::number(config.speed_min)),
+        ("speed_max", Json::number(config.speed_max)),
+        ("speed_accel", Json::number(config.speed_accel)),
+        ("rotation_accel", Json::number(config.rotation_accel)),
+        ("substeps", Json::number(config.substeps)),
+    ])
+}
+
+/// Sets config's knob of given name - one of `knobs()`, or one of the
+/// genetic operators:
+///
+/// - `selection` - `"roulette_wheel"` or `"tournament"` (of two birds),
+/// - `tournament_size` - number, implies `"selection": "tournament"`,
+/// - `crossover` - `"uniform"` or `"blend"`.
+pub fn set_knob(config: &mut sim::Config, knob: &str, value: &Json) -> Result<(), String> {
+    let invalid = || format!("invalid value of `{knob}`");
+
+    match knob {
+        "population" => config.population = number(knob, value)?,
+        "species" => config.species = number(knob, value)?,
+        "foods" => config.foods = number(knob, value)?,
+        "generation_length" => config.generation_length = number(knob, value)?,
+        "mutation_chance" => config.mutation_chance = number(knob, value)?,
+        "mutation_coeff" => config.mutation_coeff = number(knob, value)?,
+        "eye_fov_range" => config.eye_fov_range = number(knob, value)?,
+        "eye_fov_angle" => config.eye_fov_angle = number(knob, value)?,
+        "eye_cells" => config.eye_cells = number(knob, value)?,
+        "speed_min" => config.speed_min = number(knob, value)?,
+        "speed_max" => config.speed_max = number(knob, value)?,
+        "speed_accel" => config.speed_accel = number(knob, value)?,
+        "rotation_accel" => config.rotation_accel = number(knob, value)?,
+        "substeps" => config.substeps = number(knob, value)?,
+
+        "selection" => {
+            config.selection = match value.as_str().ok_or_else(invalid)? {
+                "roulette_wheel" => sim::Selection::RouletteWheel,
+                "tournament" => sim::Selection::Tournament { size: 2 },
+                _ => return Err(invalid()),
+            }
+        }
+
+        "tournament_size" => {
+            config.selection = sim::Selection::Tournament {
+                size: number(knob, value)?,
+            }
+        }
+
+        "crossover" => {
+            config.crossover = match value.as_str().ok_or_else(invalid)? {
+                "uniform" => sim::Crossover::Uniform,
+                "blend" => sim::Crossover::Blend,
+                _ => return Err(invalid()),
+            }
+        }
+
+        _ => return Err(format!("unknown knob `{knob}`")),
+    }
+
+    Ok(())
+}
+
+pub fn parse_json(text: &str) -> Result<Json, String> {
+    Json::parse(text).map_err(|offset| format!("not a valid JSON (at byte {offset})"))
+}
+
+pub fn number<T: std::str::FromStr>(name: &str, value: &Json) -> Result<T, String> {
+    value
+        .as_number()
+        .ok_or_else(|| format!("invalid value of `{name}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let experiment = ExperimentFile::parse(
+            r#"{"seed": 42, "worlds": 2, "config": {"population": 10, "mutation_chance": 0.5}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            experiment,
+            ExperimentFile {
+                config: sim::Config {
+                    population: 10,
+                    mutation_chance: 0.5,
+                    ..sim::Config::default()
+                },
+                seed: 42,
+                worlds: 2,
+                ..ExperimentFile::default()
+            }
+        );
+    }
+
+    #[test]
+    fn batch() {
+        let experiment = ExperimentFile {
+            generations: 2,
+            worlds: 4,
+            ..ExperimentFile::default()
+        };
+
+        assert_eq!(
+            experiment.batch(1..3),
+            sim::Experiment {
+                config: experiment.config.clone(),
+                seeds: vec![1, 2],
+                termination: sim::Termination::Generations(2),
+            }
+        );
+    }
+
+    #[test]
+    fn knobs() {
+        let mut config = sim::Config::default();
+        let Json::Object(knobs) = super::knobs(&sim::Config {
+            species: 2,
+            eye_fov_range: 0.5,
+            ..sim::Config::default()
+        }) else {
+            unreachable!();
+        };
+
+        for (knob, value) in &knobs {
+            set_knob(&mut config, knob, value).unwrap();
+        }
+
+        assert_eq!(config.species, 2);
+        assert_eq!(config.eye_fov_range, 0.5);
+
+        set_knob(&mut config, "tournament_size", &Json::number(3)).unwrap();
+        set_knob(&mut config, "crossover", &Json::String("blend".into())).unwrap();
+
+        assert_eq!(config.selection, sim::Selection::Tournament { size: 3 });
+        assert_eq!(config.crossover, sim::Crossover::Blend);
+
+        assert_eq!(
+            set_knob(&mut config, "selection", &Json::String("elitism".into())),
+            Err("invalid value of `selection`".into())
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            ExperimentFile::parse(r#"{"config": {"wings": 2}}"#),
+            Err("unknown knob `wings`".into())
+        );
+
+        assert_eq!(
+            ExperimentFile::parse(r#"{"generations": -1}"#),
+            Err("invalid value of `generations`".into())
+        );
+
+        assert_eq!(
+            ExperimentFile::parse(r#"{"worlds": 0}"#),
+            Err("`worlds` and `merge_every` have to be positive".into())
+        );
+
+        assert_eq!(
+            ExperimentFile::parse(r#"{"config": {"mutation_chance": 1.5}}"#),
+            Err("invalid config: `mutation_chance` has to be between zero and one".into())
+        );
+
+        assert_eq!(
+            ExperimentFile::parse(r#"{"config": {"eye_cells": 0}}"#),
+            Err("invalid config: `eye_cells` has to be positive".into())
+        );
+    }
+}
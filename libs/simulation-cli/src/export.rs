@@ -0,0 +1,62 @@
+use crate::*;
+use sim::Json;
+
+/// Bundles genes of a bird (e.g. `champion.json` written by `train`) with
+/// the experiment's config into a single JSON the web frontend accepts -
+/// `Config.load()` reads the config out of it, and
+/// `Simulation.import_brain()` the bird.
+pub fn export(experiment: &Path, genes: &Path, out: &Path) -> Result<(), String> {
+    let experiment = ExperimentFile::parse(&read(experiment)?)?;
+
+    let chromosome = sim::load_genes(&read(genes)?)
+        .map_err(|err| format!("couldn't load {}: {err}", genes.display()))?;
+
+    // Catches genes that have come from a different experiment
+    let mut sim = sim::Simulation::new(
+        sim::Config {
+            population: 1,
+            ..experiment.config.clone()
+        },
+        0,
+    );
+
+    sim.seed_with(std::slice::from_ref(&chromosome))
+        .map_err(|_| "genes don't match the experiment's config".to_string())?;
+
+    write(out, &artifact(&experiment.config, &chromosome)?)
+}
+
+fn artifact(config: &sim::Config, chromosome: &ga::Chromosome) -> Result<String, String> {
+    let Ok(Json::Object(mut fields)) = Json::parse(&sim::save_genes(chromosome)) else {
+        return Err("saved genes aren't a JSON object".into());
+    };
+
+    fields.push(("config".into(), knobs(config)));
+    Ok(Json::Object(fields).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn artifact() {
+        let config = sim::Config {
+            eye_cells: 3,
+            ..sim::Config::default()
+        };
+
+        let chromosome: ga::Chromosome = [0.5, -1.0].into_iter().collect();
+        let artifact = super::artifact(&config, &chromosome).unwrap();
+
+        let genes: Vec<_> = sim::load_genes(&artifact).unwrap().into_iter().collect();
+        assert_eq!(genes, [0.5, -1.0]);
+
+        let json = Json::parse(&artifact).unwrap();
+        let eye_cells = json
+            .get("config")
+            .and_then(|config| config.get("eye_cells"));
+
+        assert_eq!(eye_cells.and_then(Json::as_number), Some(3));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::*;
+
+/// Every how many steps a frame gets recorded - the ones in between get
+/// interpolated during playback, which keeps replays of long generations
+/// reasonably small.
+const KEYFRAME_INTERVAL: usize = 4;
+
+/// Picks the first, best and last generation out of those trained, keeping
+/// their replays - see `Train::replay`.
+#[derive(Debug, Default)]
+pub struct HighlightsRecorder {
+    first: Option<Recorded>,
+    best: Option<Recorded>,
+    last: Option<Recorded>,
+}
+
+#[derive(Clone, Debug)]
+struct Recorded {
+    generation: usize,
+    max_fitness: f32,
+    replay: sim::Replay,
+}
+
+impl HighlightsRecorder {
+    /// Creates recorder for the next generation, to be `push()`-ed once
+    /// it's over.
+    pub fn recorder() -> sim::Recorder {
+        sim::Recorder::keyframed(KEYFRAME_INTERVAL)
+    }
+
+    pub fn push(&mut self, generation: usize, max_fitness: f32, replay: sim::Replay) {
+        let recorded = Recorded {
+            generation,
+            max_fitness,
+            replay,
+        };
+
+        if self.first.is_none() {
+            self.first = Some(recorded.clone());
+        }
+
+        if self
+            .best
+            .as_ref()
+            .is_none_or(|best| recorded.max_fitness > best.max_fitness)
+        {
+            self.best = Some(recorded.clone());
+        }
+
+        self.last = Some(recorded);
+    }
+
+    /// Returns the picked generations, in order; a generation that's e.g.
+    /// both the best and the last one is there only once.
+    pub fn finish(self) -> sim::Highlights {
+        let mut clips: Vec<sim::Clip> = Vec::new();
+
+        for (label, recorded) in [
+            ("first", self.first),
+            ("best", self.best),
+            ("last", self.last),
+        ] {
+            let Some(recorded) = recorded else {
+                continue;
+            };
+
+            match clips
+                .iter_mut()
+                .find(|clip| clip.generation == recorded.generation)
+            {
+                Some(clip) => clip.labels.push(label.into()),
+
+                None => clips.push(sim::Clip {
+                    labels: vec![label.into()],
+                    generation: recorded.generation,
+                    replay: recorded.replay,
+                }),
+            }
+        }
+
+        clips.sort_by_key(|clip| clip.generation);
+
+        sim::Highlights { clips }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finish() {
+        let summary = |highlights: sim::Highlights| -> Vec<_> {
+            highlights
+                .clips
+                .into_iter()
+                .map(|clip| (clip.generation, clip.labels.join(",")))
+                .collect()
+        };
+
+        let mut highlights = HighlightsRecorder::default();
+
+        for (generation, max_fitness) in [(0, 1.0), (1, 3.0), (2, 2.0)] {
+            highlights.push(generation, max_fitness, sim::Recorder::new().finish());
+        }
+
+        assert_eq!(
+            summary(highlights.finish()),
+            [(0, "first".into()), (1, "best".into()), (2, "last".into())]
+        );
+
+        let mut highlights = HighlightsRecorder::default();
+        highlights.push(0, 1.0, sim::Recorder::new().finish());
+
+        assert_eq!(
+            summary(highlights.finish()),
+            [(0, "first,best,last".into())]
+        );
+    }
+}
@@ -0,0 +1,137 @@
+//! Trains birds headlessly, at native speed:
+//!
+//! ```text
+//! cargo run --release -p how-to-fly-cli -- train experiment.json --out runs/first
+//! ```
+//!
+//! See `ExperimentFile` for what goes into the experiment file and `Train` for
+//! what comes out of the training; `Sweep` trains an experiment for many
+//! combinations of knobs:
+//!
+//! ```text
+//! cargo run --release -p how-to-fly-cli -- sweep sweep.json --out runs/sweep
+//! ```
+//!
+//! `compare` tells whether one experiment does better than another (e.g.
+//! with a different `selection` or `crossover`, see `set_knob()`):
+//!
+//! ```text
+//! cargo run --release -p how-to-fly-cli -- compare a.json b.json --seeds 20
+//! ```
+//!
+//! Trained champions can be then watched in the browser:
+//!
+//! ```text
+//! cargo run --release -p how-to-fly-cli -- export experiment.json runs/first/champion.json
+//! ```
+//!
+//! ... and so can the key moments of a training, recorded with `--replay`
+//! (the browser's "import" takes `replay.json` as well).
+
+mod args;
+mod compare;
+mod dashboard;
+mod experiment;
+mod export;
+mod highlights;
+mod stats;
+mod sweep;
+mod train;
+
+use self::args::*;
+use self::compare::*;
+use self::dashboard::*;
+use self::experiment::*;
+use self::export::*;
+use self::highlights::*;
+use self::stats::*;
+use self::sweep::*;
+use self::train::*;
+use lib_genetic_algorithm as ga;
+use lib_simulation as sim;
+use std::path::{Path, PathBuf};
+use std::{env, fs, process};
+
+const USAGE: &str = "\
+usage:
+    how-to-fly-cli train <experiment.json> [--out <dir>] [--stats-format csv|jsonl]
+                         [--checkpoint-every <generations>] [--resume <checkpoint.json>]
+                         [--dashboard] [--replay]
+    how-to-fly-cli sweep <sweep.json> [--out <dir>]
+    how-to-fly-cli compare <a.json> <b.json> [--seeds <count>]
+    how-to-fly-cli export <experiment.json> <champion.json> [--out <brain.json>]";
+
+fn main() {
+    if let Err(err) = run(env::args().skip(1)) {
+        eprintln!("error: {err}");
+        process::exit(1);
+    }
+}
+
+fn run(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let args = Args::parse(
+        args,
+        &["out", "stats-format", "checkpoint-every", "resume", "seeds"],
+        &["dashboard", "replay"],
+    )?;
+
+    match args.positional.as_slice() {
+        [command, experiment] if command == "train" => Train::new(experiment, &args)?.run(),
+
+        [command, sweep] if command == "sweep" => {
+            let out = PathBuf::from(args.option("out").unwrap_or("."));
+
+            Sweep::parse(&read(Path::new(sweep))?)?.run(&out)
+        }
+
+        [command, a, b] if command == "compare" => {
+            let seeds = args.parsed_option("seeds")?.unwrap_or(10);
+
+            compare(Path::new(a), Path::new(b), seeds)
+        }
+
+        [command, experiment, genes] if command == "export" => {
+            let out = PathBuf::from(args.option("out").unwrap_or("brain.json"));
+
+            export(Path::new(experiment), Path::new(genes), &out)
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("couldn't read {}: {err}", path.display()))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("couldn't write {}: {err}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_experiment() {
+        let path =
+            env::temp_dir().join(format!("how-to-fly-cli-experiment-{}.json", process::id()));
+
+        // Passes the knobs' own checks, but would panic on the first step
+        write(&path, r#"{"config": {"speed_accel": -0.1}}"#).unwrap();
+
+        let path = path.display().to_string();
+        let out = env::temp_dir().display().to_string();
+
+        let train = run(["train", &path, "--out", &out]
+            .map(String::from)
+            .into_iter());
+        let compare = run(["compare", &path, &path].map(String::from).into_iter());
+
+        fs::remove_file(&path).unwrap();
+
+        let err = "invalid config: `speed_accel` has to be non-negative";
+
+        assert_eq!(train, Err(err.into()));
+        assert_eq!(compare, Err(err.into()));
+    }
+}
@@ -0,0 +1,141 @@
+use lib_simulation as sim;
+use sim::Json;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum StatsFormat {
+    #[default]
+    Csv,
+
+    /// One JSON object per line
+    JsonLines,
+}
+
+impl StatsFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format {
+            "csv" => Ok(Self::Csv),
+            "jsonl" => Ok(Self::JsonLines),
+            _ => Err(format!("unknown stats format `{format}`")),
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::JsonLines => "jsonl",
+        }
+    }
+}
+
+/// Streams statistics into a file as the generations go by, flushing
+/// after each of them - so that the file can be tailed (e.g. by a plotting
+/// tool) during the run and survives it crashing.
+///
+/// Each row is prefixed with the index of the world it comes from.
+pub struct StatsWriter {
+    file: File,
+    format: StatsFormat,
+}
+
+impl StatsWriter {
+    pub fn create(path: &Path, format: StatsFormat) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|err| format!("couldn't create {}: {err}", path.display()))?;
+
+        let mut this = Self { file, format };
+
+        if format == StatsFormat::Csv {
+            this.write(&format!("world,{}\n", sim::Statistics::CSV_HEADER))?;
+        }
+
+        Ok(this)
+    }
+
+    /// Writes statistics of a generation of each of the worlds.
+    pub fn append(&mut self, stats: &[sim::Statistics]) -> Result<(), String> {
+        let rows: String = stats
+            .iter()
+            .enumerate()
+            .map(|(world, stats)| Ok(self.row(world, stats)? + "\n"))
+            .collect::<Result<_, String>>()?;
+
+        self.write(&rows)
+    }
+
+    fn row(&self, world: usize, stats: &sim::Statistics) -> Result<String, String> {
+        match self.format {
+            StatsFormat::Csv => Ok(format!("{world},{}", stats.to_csv_row())),
+
+            StatsFormat::JsonLines => {
+                let Json::Object(mut fields) = stats.to_json() else {
+                    return Err("statistics aren't a JSON object".into());
+                };
+
+                fields.insert(0, ("world".into(), Json::number(world)));
+                Ok(Json::Object(fields).to_string())
+            }
+        }
+    }
+
+    fn write(&mut self, text: &str) -> Result<(), String> {
+        self.file
+            .write_all(text.as_bytes())
+            .and_then(|_| self.file.flush())
+            .map_err(|err| format!("couldn't write stats: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn append() {
+        let mut sim = sim::Simulation::new(
+            sim::Config {
+                population: 2,
+                generation_length: 5,
+                ..sim::Config::default()
+            },
+            0,
+        );
+
+        let stats = sim.train();
+
+        for format in [StatsFormat::Csv, StatsFormat::JsonLines] {
+            let path = std::env::temp_dir().join(format!(
+                "how-to-fly-cli-stats-{}.{}",
+                std::process::id(),
+                format.extension()
+            ));
+
+            let mut writer = StatsWriter::create(&path, format).unwrap();
+            writer.append(&[stats.clone(), stats.clone()]).unwrap();
+
+            let written = std::fs::read_to_string(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+
+            let lines: Vec<_> = written.lines().collect();
+
+            match format {
+                StatsFormat::Csv => {
+                    assert_eq!(lines.len(), 3);
+                    assert!(lines[0].starts_with("world,generation,"));
+                    assert_eq!(lines[2], format!("1,{}", stats.to_csv_row()));
+                }
+
+                StatsFormat::JsonLines => {
+                    assert_eq!(lines.len(), 2);
+
+                    let row = Json::parse(lines[1]).unwrap();
+
+                    assert_eq!(row.get("world").and_then(Json::as_number), Some(1));
+                    assert_eq!(row.get("max_fitness"), stats.to_json().get("max_fitness"));
+                }
+            }
+        }
+    }
+}
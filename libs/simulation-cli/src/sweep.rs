@@ -0,0 +1,281 @@
+use crate::*;
+use sim::Json;
+
+/// Experiment with some of the config's knobs given as lists of values
+/// (or ranges), trained for each combination of them - and for each of
+/// the seeds:
+///
+/// ```json
+/// {
+///     "seeds": [1, 2, 3],
+///     "generations": 50,
+///     "config": {
+///         "population": [20, 40],
+///         "mutation_chance": { "from": 0.01, "to": 0.05, "step": 0.02 }
+///     }
+/// }
+/// ```
+///
+/// The output directory (current one by default) gets `summary.csv`, with
+/// final fitness of each combination averaged over the seeds.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sweep {
+    /// Experiment with all the swept knobs left out
+    base: Json,
+
+    /// Swept knobs, along with their values
+    pub knobs: Vec<(String, Vec<Json>)>,
+
+    pub seeds: Vec<u64>,
+}
+
+impl Sweep {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let Json::Object(mut fields) = parse_json(text)? else {
+            return Err("sweep has to be an object".into());
+        };
+
+        let mut seeds = None;
+        let mut knobs = Vec::new();
+
+        if let Some(idx) = fields.iter().position(|(name, _)| name == "seeds") {
+            let (_, value) = fields.remove(idx);
+
+            seeds = Some(
+                value
+                    .as_array()
+                    .ok_or("`seeds` has to be an array")?
+                    .iter()
+                    .map(|seed| number("seeds", seed))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+        }
+
+        if let Some((_, Json::Object(config))) =
+            fields.iter_mut().find(|(name, _)| name == "config")
+        {
+            for (knob, value) in std::mem::take(config) {
+                match values(&knob, &value)? {
+                    Some(values) => knobs.push((knob, values)),
+                    None => config.push((knob, value)),
+                }
+            }
+        }
+
+        let base = Json::Object(fields);
+        let seeds = match seeds {
+            Some(seeds) => seeds,
+            None => vec![ExperimentFile::from_json(&base)?.seed],
+        };
+
+        if seeds.is_empty() {
+            return Err("`seeds` can't be empty".into());
+        }
+
+        let this = Self { base, knobs, seeds };
+
+        // Catches invalid combinations before any of them gets trained
+        this.experiments()?;
+
+        Ok(this)
+    }
+
+    /// Returns an experiment for each combination of the knobs' values,
+    /// along with the values (in the order of `knobs`).
+    pub fn experiments(&self) -> Result<Vec<(Vec<Json>, ExperimentFile)>, String> {
+        let base = ExperimentFile::from_json(&self.base)?;
+        let mut experiments = vec![(Vec::new(), base)];
+
+        for (knob, values) in &self.knobs {
+            let mut next = Vec::new();
+
+            for (combination, experiment) in &experiments {
+                for value in values {
+                    let mut experiment = experiment.clone();
+                    set_knob(&mut experiment.config, knob, value)?;
+
+                    let mut combination = combination.clone();
+                    combination.push(value.clone());
+
+                    next.push((combination, experiment));
+                }
+            }
+
+            experiments = next;
+        }
+
+        for (combination, experiment) in &experiments {
+            experiment.config.validate().map_err(|err| {
+                let combination: Vec<_> = self
+                    .knobs
+                    .iter()
+                    .zip(combination)
+                    .map(|((knob, _), value)| format!("{knob}={value}"))
+                    .collect();
+
+                format!("invalid config for {}: {err}", combination.join(", "))
+            })?;
+        }
+
+        Ok(experiments)
+    }
+
+    pub fn run(&self, out: &Path) -> Result<(), String> {
+        let experiments = self.experiments()?;
+
+        fs::create_dir_all(out)
+            .map_err(|err| format!("couldn't create {}: {err}", out.display()))?;
+
+        let mut summary: Vec<_> = self.knobs.iter().map(|(knob, _)| knob.as_str()).collect();
+        summary.extend(["runs", "max_fitness", "avg_fitness"]);
+
+        let mut summary = summary.join(",") + "\n";
+
+        for (idx, (combination, experiment)) in experiments.iter().enumerate() {
+            let values: Vec<_> = combination.iter().map(Json::to_string).collect();
+            let results = experiment.batch(self.seeds.iter().copied()).run();
+
+            for run in &results.runs {
+                let Some(stats) = run.history.last() else {
+                    continue;
+                };
+
+                eprintln!(
+                    "run {}/{}: {}, seed={}: max={:.2}, avg={:.2}",
+                    idx + 1,
+                    experiments.len(),
+                    self.knobs
+                        .iter()
+                        .zip(&values)
+                        .map(|((knob, _), value)| format!("{knob}={value}"))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    run.seed,
+                    stats.max_fitness,
+                    stats.avg_fitness,
+                );
+            }
+
+            let mean = |finals: Vec<f32>| finals.iter().sum::<f32>() / finals.len() as f32;
+
+            let mut row = values;
+            row.extend([
+                results.runs.len().to_string(),
+                mean(results.finals(|stats| stats.max_fitness)).to_string(),
+                mean(results.finals(|stats| stats.avg_fitness)).to_string(),
+            ]);
+
+            summary.push_str(&(row.join(",") + "\n"));
+        }
+
+        print!("{summary}");
+        write(&out.join("summary.csv"), &summary)
+    }
+}
+
+/// Expands value of a swept knob - either a list of values, or a range -
+/// returning `None` for knobs with just a single value.
+fn values(knob: &str, value: &Json) -> Result<Option<Vec<Json>>, String> {
+    if let Some(values) = value.as_array() {
+        if values.is_empty() {
+            return Err(format!("`{knob}` has no values"));
+        }
+
+        return Ok(Some(values.to_vec()));
+    }
+
+    let (Some(from), Some(to), Some(step)) =
+        (value.get("from"), value.get("to"), value.get("step"))
+    else {
+        return Ok(None);
+    };
+
+    let from: f64 = number(knob, from)?;
+    let to: f64 = number(knob, to)?;
+    let step: f64 = number(knob, step)?;
+
+    if step <= 0.0 || to < from {
+        return Err(format!("`{knob}` has an invalid range"));
+    }
+
+    // Leeway for the rounding errors, so that the range includes `to`
+    let count = ((to - from) / step + 1e-9).floor() as usize + 1;
+
+    let values = (0..count)
+        .map(|idx| {
+            // Trims the rounding errors (e.g. `0.30000000000000004`)
+            let value = format!("{:.9}", from + idx as f64 * step);
+            let value = value.trim_end_matches('0').trim_end_matches('.');
+
+            Json::Number(value.to_string())
+        })
+        .collect();
+
+    Ok(Some(values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn experiments() {
+        let sweep = Sweep::parse(
+            r#"{
+                "seeds": [1, 2],
+                "generations": 5,
+                "config": {
+                    "foods": 10,
+                    "population": [20, 40],
+                    "mutation_chance": { "from": 0.1, "to": 0.3, "step": 0.1 }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(sweep.seeds, [1, 2]);
+
+        let experiments = sweep.experiments().unwrap();
+
+        let combinations: Vec<_> = experiments
+            .iter()
+            .map(|(values, _)| {
+                values
+                    .iter()
+                    .map(Json::to_string)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect();
+
+        assert_eq!(
+            combinations,
+            ["20 0.1", "20 0.2", "20 0.3", "40 0.1", "40 0.2", "40 0.3"]
+        );
+
+        let (_, experiment) = &experiments[4];
+
+        assert_eq!(experiment.generations, 5);
+        assert_eq!(experiment.config.foods, 10);
+        assert_eq!(experiment.config.population, 40);
+        assert_eq!(experiment.config.mutation_chance, 0.2);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Sweep::parse(r#"{"config": {"population": []}}"#),
+            Err("`population` has no values".into())
+        );
+
+        assert_eq!(
+            Sweep::parse(r#"{"config": {"foods": {"from": 2, "to": 1, "step": 1}}}"#),
+            Err("`foods` has an invalid range".into())
+        );
+
+        assert_eq!(
+            Sweep::parse(r#"{"config": {"foods": 5, "eye_cells": [1, 0]}}"#),
+            Err("invalid config for eye_cells=0: `eye_cells` has to be positive".into())
+        );
+    }
+}
@@ -0,0 +1,165 @@
+use crate::*;
+
+/// Trains birds described by an experiment file; the output directory
+/// (current one by default) gets:
+///
+/// - `stats.csv` (or `stats.jsonl`, with `--stats-format jsonl`), with
+///   statistics of each world's generations, written as they go by,
+/// - `checkpoint.json`, with the entire training, every
+///   `--checkpoint-every` generations - for `--resume` to continue from,
+/// - `champion.json`, with genes of the best bird of the last generation,
+/// - `replay.json` (with `--replay`), with replays of the first, best and
+///   last generation of the first world - for the browser to play back.
+///
+/// Generations trained before `--resume` don't count as the replay's first
+/// or best ones, since they weren't recorded.
+///
+/// Progress gets printed after each generation - or, with `--dashboard`,
+/// shown on a live-updating summary.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Train {
+    pub experiment: PathBuf,
+    pub out: PathBuf,
+    pub format: StatsFormat,
+    pub checkpoint_every: Option<usize>,
+    pub resume: Option<PathBuf>,
+    pub dashboard: bool,
+    pub replay: bool,
+}
+
+impl Train {
+    pub fn new(experiment: &str, args: &Args) -> Result<Self, String> {
+        let format = args
+            .option("stats-format")
+            .map(StatsFormat::parse)
+            .transpose()?
+            .unwrap_or_default();
+
+        let checkpoint_every = args.parsed_option("checkpoint-every")?;
+
+        if checkpoint_every == Some(0) {
+            return Err("`--checkpoint-every` has to be positive".into());
+        }
+
+        Ok(Self {
+            experiment: PathBuf::from(experiment),
+            out: PathBuf::from(args.option("out").unwrap_or(".")),
+            format,
+            checkpoint_every,
+            resume: args.option("resume").map(PathBuf::from),
+            dashboard: args.switch("dashboard"),
+            replay: args.switch("replay"),
+        })
+    }
+
+    pub fn run(&self) -> Result<(), String> {
+        let experiment = ExperimentFile::parse(&read(&self.experiment)?)?;
+
+        fs::create_dir_all(&self.out)
+            .map_err(|err| format!("couldn't create {}: {err}", self.out.display()))?;
+
+        let mut trainer = match &self.resume {
+            Some(checkpoint) => sim::ParallelTrainer::load(experiment.config, &read(checkpoint)?)
+                .map_err(|err| {
+                format!("couldn't resume from {}: {err}", checkpoint.display())
+            })?,
+
+            None => sim::ParallelTrainer::new(
+                experiment.config,
+                experiment.seed,
+                experiment.worlds,
+                experiment.merge_every,
+            ),
+        };
+
+        let stats_path = self.out.join(format!("stats.{}", self.format.extension()));
+        let mut stats_writer = StatsWriter::create(&stats_path, self.format)?;
+
+        // Generations trained before the checkpoint get rewritten from the
+        // simulations' histories, so that the stats don't end up with ones
+        // trained after it, but before the interruption
+        for generation in 0..trainer.generations() {
+            let stats: Vec<_> = trainer
+                .simulations()
+                .iter()
+                .map(|sim| sim.history()[generation].clone())
+                .collect();
+
+            stats_writer.append(&stats)?;
+        }
+
+        let mut dashboard = self
+            .dashboard
+            .then(|| Dashboard::new(experiment.generations, trainer.generations()));
+
+        let mut highlights = self.replay.then(HighlightsRecorder::default);
+
+        while trainer.generations() < experiment.generations {
+            let stats = match &mut highlights {
+                Some(highlights) => {
+                    let mut recorder = HighlightsRecorder::recorder();
+                    let stats = trainer.train_recorded(&mut recorder);
+
+                    highlights.push(stats[0].generation, stats[0].max_fitness, recorder.finish());
+
+                    stats
+                }
+
+                None => trainer.train(),
+            };
+
+            stats_writer.append(&stats)?;
+
+            if let Some(dashboard) = &mut dashboard {
+                dashboard.update(&trainer, &stats);
+            } else {
+                let best = stats
+                    .iter()
+                    .max_by(|a, b| a.max_fitness.total_cmp(&b.max_fitness))
+                    .unwrap();
+
+                eprintln!(
+                    "generation {}: min={:.2}, max={:.2}, avg={:.2}",
+                    best.generation, best.min_fitness, best.max_fitness, best.avg_fitness
+                );
+            }
+
+            if let Some(every) = self.checkpoint_every {
+                if trainer.generations().is_multiple_of(every) {
+                    self.checkpoint(&trainer)?;
+                }
+            }
+        }
+
+        if let Some(champion) = champion(trainer.simulations()) {
+            write(&self.out.join("champion.json"), &sim::save_genes(champion))?;
+        }
+
+        if let Some(highlights) = highlights {
+            write(&self.out.join("replay.json"), &highlights.finish().save())?;
+        }
+
+        Ok(())
+    }
+
+    /// Saves the trainer, making sure that an interruption can't leave a
+    /// half-written checkpoint behind.
+    fn checkpoint(&self, trainer: &sim::ParallelTrainer) -> Result<(), String> {
+        let path = self.out.join("checkpoint.json");
+        let tmp_path = self.out.join("checkpoint.json.tmp");
+
+        write(&tmp_path, &trainer.save())?;
+
+        fs::rename(&tmp_path, &path)
+            .map_err(|err| format!("couldn't write {}: {err}", path.display()))
+    }
+}
+
+/// Returns champion of the world that's done best in the last generation.
+fn champion(simulations: &[sim::Simulation]) -> Option<&ga::Chromosome> {
+    simulations
+        .iter()
+        .filter_map(|sim| Some((sim.history().last()?.max_fitness, sim.champion()?)))
+        .max_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, champion)| champion)
+}
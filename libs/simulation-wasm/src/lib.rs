@@ -1,41 +1,884 @@
 #![allow(dead_code, unused_variables)]
 
 use lib_simulation as sim;
+use nalgebra as na;
 use rand::prelude::*;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use wasm_bindgen::prelude::*;
 
+/// Returns the wasm memory - for JavaScript to view buffers such as
+/// `Simulation::animal_buffer()` as `Float32Array`s.
+#[wasm_bindgen]
+pub fn memory() -> JsValue {
+    wasm_bindgen::memory()
+}
+
+/// Number of events kept 'till `Simulation::drain_events()`; when there's
+/// more, the oldest ones are dropped.
+const MAX_EVENTS: usize = 10_000;
+
+/// Number of steps `step_dt()` performs per second of real time (at speed
+/// of one) - i.e. the same pace `step()` goes at on a 60 Hz display.
+const STEPS_PER_SECOND: f32 = 60.0;
+
+/// Longest time `step_dt()` catches up on at once - e.g. after the tab's
+/// been put in the background - so that it doesn't freeze the page.
+const MAX_DT: f32 = 0.25;
+
+/// Checks whether the world can be given these dimensions (see
+/// `Config::width`).
+fn validate_size(width: f32, height: f32) -> Result<(), String> {
+    if width > 0.0 && height > 0.0 {
+        Ok(())
+    } else {
+        Err("`width` and `height` have to be positive".into())
+    }
+}
+
+/// Converts a rotation from the unit square the simulation happens in into
+/// the world's dimensions, so that - once the square gets stretched to
+/// them - things still point in the direction they're moving in.
+fn stretch_rotation(size: na::Vector2<f32>, rotation: f32) -> f32 {
+    let heading = (na::Rotation2::new(rotation) * na::Vector2::y()).component_mul(&size);
+
+    na::Rotation2::rotation_between(&na::Vector2::y(), &heading).angle()
+}
+
+/// Simulation driven from JavaScript; there's no global state, so any
+/// number of them - each with its own config - can run side by side, with
+/// the JS objects serving as their handles.
 #[wasm_bindgen]
 pub struct Simulation {
-    rng: ThreadRng,
     sim: sim::Simulation,
+
+    /// Makes `step()` (and `step_many()`) do nothing
+    paused: bool,
+
+    /// Number of steps each `step()` performs
+    speed: u32,
+
+    /// Fraction of a step `step_dt()` has yet to perform
+    pending_steps: f32,
+
+    /// Bird picked through `select_animal_at()`
+    selected: Option<sim::AnimalId>,
+
+    /// Dimensions the world gets stretched to, see `Config::width`
+    size: na::Vector2<f32>,
+
+    /// Living birds as `x, y, rotation` triples, see `animal_buffer()`
+    animal_buffer: Vec<f32>,
+
+    /// Same birds as in `animal_buffer`, but as they were a step earlier,
+    /// see `previous_animal_buffer()`
+    previous_animal_buffer: Vec<f32>,
+
+    /// Birds' transforms from before the last step
+    previous_transforms: HashMap<sim::AnimalId, [f32; 3]>,
+
+    /// Available foods as `x, y` pairs, see `food_buffer()`
+    food_buffer: Vec<f32>,
+
+    /// Events that have happened since the last `drain_events()`
+    events: Arc<Mutex<VecDeque<sim::Event>>>,
 }
 
 #[wasm_bindgen]
 impl Simulation {
+    /// Creates a simulation with given seed (or a random one, if omitted)
+    /// and config (or the default one); fails if the config is invalid.
     #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        let mut rng = thread_rng();
-        let sim = sim::Simulation::random(&mut rng);
+    pub fn new(seed: Option<u64>, config: Option<Config>) -> Result<Simulation, JsError> {
+        let seed = seed.unwrap_or_else(|| thread_rng().next_u64());
+        let config = config.unwrap_or_default();
 
-        Self { rng, sim }
+        config.validate().map_err(|err| JsError::new(&err))?;
+
+        let sim = sim::Simulation::new(sim::Config::from(&config), seed);
+
+        Ok(Self::from_sim(sim, &config))
+    }
+
+    /// Captures the entire simulation (including its config) as a string -
+    /// e.g. to keep it in `localStorage`.
+    pub fn save(&self) -> String {
+        format!("{}\n{}", self.config().to_query(), self.sim.save())
+    }
+
+    /// Restores simulation captured with `save()`.
+    pub fn load(snapshot: &str) -> Result<Simulation, JsError> {
+        Self::restore(snapshot).map_err(|err| JsError::new(&err))
+    }
+
+    /// Message-oriented entry point - e.g. for a simulation living inside a
+    /// Web Worker, so that long trainings don't freeze the page.
+    ///
+    /// Takes a JSON command and returns a JSON reply echoing its `command`,
+    /// along with either the results or an `error`:
+    ///
+    /// - `{"command": "step", "steps": 10}` (steps default to one),
+    /// - `{"command": "train", "generations": 3}` replies with `stats` of
+    ///   each generation (generations default to one),
+    /// - `{"command": "world"}` replies with `animals`, `foods` and
+    ///   `predators`, just like `world()`,
+    /// - `{"command": "save"}` replies with `snapshot`, just like `save()`,
+    /// - `{"command": "load", "snapshot": "..."}` replaces this simulation
+    ///   with a saved one.
+    pub fn handle_command(&mut self, command: &str) -> String {
+        let command = match sim::Json::parse(command) {
+            Ok(command) => command,
+            Err(offset) => {
+                return sim::Json::object([(
+                    "error",
+                    sim::Json::String(format!("command is not a valid JSON (at byte {offset})")),
+                )])
+                .to_string();
+            }
+        };
+
+        let name = command
+            .get("command")
+            .and_then(sim::Json::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        let reply = match self.execute(&name, &command) {
+            Ok(fields) => fields,
+            Err(err) => vec![("error", sim::Json::String(err))],
+        };
+
+        sim::Json::object(
+            [("command", sim::Json::String(name))]
+                .into_iter()
+                .chain(reply),
+        )
+        .to_string()
+    }
+
+    /// Returns the config this simulation runs with right now (including
+    /// changes made through the setters).
+    pub fn config(&self) -> Config {
+        Config::from_sim(self.sim.config(), self.size)
+    }
+
+    /// Returns the seed; passing it to the constructor replays the exact
+    /// same run.
+    pub fn seed(&self) -> u64 {
+        self.sim.seed()
     }
 
     pub fn world(&self) -> World {
-        World::from(self.sim.world())
+        World::new(self.sim.world(), self.size)
     }
 
+    /// Returns just the predators - there's few enough of them not to need
+    /// a buffer.
+    pub fn predators(&self) -> Vec<Predator> {
+        self.sim
+            .world()
+            .predators()
+            .iter()
+            .map(|predator| {
+                let mut predator = Predator::from(predator);
+
+                predator.x *= self.size.x;
+                predator.y *= self.size.y;
+                predator.rotation = stretch_rotation(self.size, predator.rotation);
+                predator
+            })
+            .collect()
+    }
+
+    /// Changes the world's dimensions (see `Config::width`), rescaling
+    /// positions of everything in it - e.g. to follow the canvas; fails
+    /// (keeping the dimensions as they were) if they're not positive, e.g.
+    /// for a hidden canvas.
+    pub fn resize(&mut self, width: f32, height: f32) -> Result<(), JsError> {
+        validate_size(width, height).map_err(|err| JsError::new(&err))?;
+
+        self.size = na::Vector2::new(width, height);
+
+        // They're in the old dimensions
+        self.previous_transforms.clear();
+        self.refresh_buffers();
+
+        Ok(())
+    }
+
+    /// Returns pointer to living birds' positions and rotations, as
+    /// `x, y, rotation` triples (in the world's dimensions) - for rendering
+    /// lots of birds without creating an object for each of them:
+    ///
+    /// ```js
+    /// new Float32Array(memory().buffer, sim.animal_buffer(), sim.animal_buffer_len())
+    /// ```
+    ///
+    /// The buffer gets refreshed in place whenever the world changes; the
+    /// view should be re-created each frame though, as the pointer moves
+    /// when the buffer (or the wasm memory) grows.
+    pub fn animal_buffer(&self) -> *const f32 {
+        self.animal_buffer.as_ptr()
+    }
+
+    /// Number of floats (not birds) in `animal_buffer()`.
+    pub fn animal_buffer_len(&self) -> usize {
+        self.animal_buffer.len()
+    }
+
+    /// Returns pointer to the same birds as `animal_buffer()` (and in the
+    /// same layout), but as they were a step earlier - birds that have
+    /// just appeared are given as they are now.
+    ///
+    /// Together with `interpolation()`, it lets the renderer draw birds in
+    /// between steps; mind that birds wrap around the world's edges, and
+    /// rotations around `-PI..=PI`, though.
+    pub fn previous_animal_buffer(&self) -> *const f32 {
+        self.previous_animal_buffer.as_ptr()
+    }
+
+    /// Returns how far `step_dt()` has got towards the next step, within
+    /// `0.0..1.0` - i.e. how far to interpolate from
+    /// `previous_animal_buffer()` to `animal_buffer()`.
+    pub fn interpolation(&self) -> f32 {
+        self.pending_steps
+    }
+
+    /// Returns pointer to available foods' positions, as `x, y` pairs - see
+    /// `animal_buffer()`.
+    pub fn food_buffer(&self) -> *const f32 {
+        self.food_buffer.as_ptr()
+    }
+
+    /// Number of floats (not foods) in `food_buffer()`.
+    pub fn food_buffer_len(&self) -> usize {
+        self.food_buffer.len()
+    }
+
+    pub fn width(&self) -> f32 {
+        self.size.x
+    }
+
+    pub fn height(&self) -> f32 {
+        self.size.y
+    }
+
+    /// Converts a point given in the world's dimensions into the unit
+    /// square the simulation happens in.
+    fn to_unit(&self, x: f32, y: f32) -> na::Point2<f32> {
+        na::Point2::new(
+            (x / self.size.x).clamp(0.0, 1.0),
+            (y / self.size.y).clamp(0.0, 1.0),
+        )
+    }
+
+    /// Performs as many steps as `set_speed()` says (one by default).
     pub fn step(&mut self) {
-        self.sim.step(&mut self.rng);
+        if !self.paused {
+            self.advance(self.speed as usize);
+        }
     }
 
-    pub fn train(&mut self) -> String {
-        let stats = self.sim.train(&mut self.rng);
+    /// Performs `n` steps at once, sparing JavaScript from calling `step()`
+    /// in a loop.
+    pub fn step_many(&mut self, n: usize) {
+        if !self.paused {
+            self.advance(n);
+        }
+    }
+
+    /// Advances the simulation by as many steps as fit into `seconds` of
+    /// real time (times `set_speed()`), carrying the leftover fraction over
+    /// to the next call - so that birds fly at the same pace regardless of
+    /// the display's refresh rate.
+    pub fn step_dt(&mut self, seconds: f32) {
+        if self.paused || seconds.is_nan() || seconds <= 0.0 {
+            return;
+        }
+
+        self.pending_steps += seconds.min(MAX_DT) * STEPS_PER_SECOND * self.speed as f32;
+
+        let steps = self.pending_steps.floor();
+        self.pending_steps -= steps;
+
+        if steps > 0.0 {
+            self.step_many(steps as usize);
+        }
+    }
+
+    /// Makes each `step()` advance the simulation by `multiplier` steps -
+    /// e.g. to fast-forward boring early generations.
+    pub fn set_speed(&mut self, multiplier: u32) {
+        self.speed = multiplier.max(1);
+    }
+
+    pub fn speed(&self) -> u32 {
+        self.speed
+    }
+
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Performs a single step, even while paused.
+    pub fn step_once(&mut self) {
+        self.advance(1);
+    }
+
+    /// Fast-forwards 'till the end of the current generation.
+    pub fn train(&mut self) -> Stats {
+        let stats = Stats::from(&self.sim.train());
+
+        self.refresh_buffers();
+        stats
+    }
+
+    /// Returns genes of the best bird of the last generation (or, before
+    /// the first one ends, of the current one) as a JSON string.
+    pub fn export_best_brain(&self) -> Option<String> {
+        let chromosome = match self.sim.champion() {
+            Some(champion) => champion.clone(),
+            None => self.sim.best_animal()?.as_chromosome(),
+        };
+
+        Some(sim::save_genes(&chromosome))
+    }
+
+    /// Puts a bird grown from genes exported by `export_best_brain()` into
+    /// the world, returning its id.
+    pub fn import_brain(&mut self, json: &str) -> Result<u64, JsError> {
+        let chromosome = sim::load_genes(json)?;
+        let animal = self.sim.inject(chromosome)?;
+
+        self.refresh_buffers();
+
+        Ok(self.sim.world().animals()[animal].id().0)
+    }
+
+    /// Drops food at given point - e.g. where the user has clicked.
+    pub fn add_food(&mut self, x: f32, y: f32) {
+        self.sim.add_food(self.to_unit(x, y), 0);
+        self.refresh_buffers();
+    }
+
+    /// Puts a fresh bird at given point, returning its id.
+    pub fn add_animal(&mut self, x: f32, y: f32) -> u64 {
+        let animal = self.sim.add_animal(self.to_unit(x, y));
+
+        self.refresh_buffers();
+        self.sim.world().animals()[animal].id().0
+    }
+
+    /// Changes the probability of mutating each gene, starting with the
+    /// next generation; fails if it's not between zero and one.
+    pub fn set_mutation_chance(&mut self, chance: f32) -> Result<(), JsError> {
+        if !(0.0..=1.0).contains(&chance) {
+            return Err(JsError::new(
+                "mutation chance has to be between zero and one",
+            ));
+        }
+
+        let coeff = self.sim.config().mutation_coeff;
+        self.sim.set_mutation(chance, coeff);
+
+        Ok(())
+    }
+
+    /// Changes the magnitude of mutations, starting with the next
+    /// generation.
+    pub fn set_mutation_coeff(&mut self, coeff: f32) {
+        let chance = self.sim.config().mutation_chance;
+        self.sim.set_mutation(chance, coeff);
+    }
+
+    pub fn mutation_chance(&self) -> f32 {
+        self.sim.config().mutation_chance
+    }
+
+    pub fn mutation_coeff(&self) -> f32 {
+        self.sim.config().mutation_coeff
+    }
+
+    /// Selects the living bird nearest to given point (as long as it's
+    /// close enough - e.g. the one that's been clicked on), returning its
+    /// id - or clears the selection, returning `None`, if there's no bird
+    /// around.
+    pub fn select_animal_at(&mut self, x: f32, y: f32) -> Option<u64> {
+        let point = self.to_unit(x, y);
+        let world = self.sim.world();
+        let animal = world.animal_at(point, 0.02);
+
+        self.selected = animal.map(|animal| world.animals()[animal].id());
+        self.selected.map(|id| id.0)
+    }
+
+    /// Returns what the selected bird sees and thinks right now (`None` if
+    /// nothing's selected, or the bird is dead or gone).
+    pub fn selected_animal_debug(&mut self) -> Option<AnimalDebug> {
+        let animal = self.sim.world().find_animal(self.selected?)?;
+
+        self.sim.inspect(animal).map(AnimalDebug::from)
+    }
+
+    /// Returns events that have happened since the last call (up to a few
+    /// thousand most recent ones), oldest first - e.g. to play a sound
+    /// whenever a bird eats.
+    pub fn drain_events(&self) -> Vec<Event> {
+        let mut events = self.events.lock().unwrap();
+
+        events.drain(..).map(|event| Event::from(&event)).collect()
+    }
+
+    /// Returns statistics of all the generations so far, oldest first -
+    /// e.g. to plot them.
+    pub fn history(&self) -> Vec<Stats> {
+        self.sim.history().iter().map(Stats::from).collect()
+    }
+}
+
+impl Simulation {
+    fn restore(snapshot: &str) -> Result<Self, String> {
+        let (config, snapshot) = snapshot.split_once('\n').ok_or("snapshot lacks config")?;
+
+        let config = Config::from_query(config)?;
+        config.validate()?;
+
+        let sim = sim::Simulation::load(sim::Config::from(&config), snapshot)
+            .map_err(|err| err.to_string())?;
+
+        Ok(Self::from_sim(sim, &config))
+    }
+
+    fn execute(
+        &mut self,
+        name: &str,
+        command: &sim::Json,
+    ) -> Result<Vec<(&'static str, sim::Json)>, String> {
+        let count = |field: &str| match command.get(field) {
+            Some(count) => count
+                .as_number()
+                .ok_or_else(|| format!("invalid `{field}`")),
+            None => Ok(1),
+        };
+
+        match name {
+            "step" => {
+                self.advance(count("steps")?);
+                Ok(Vec::new())
+            }
+
+            "train" => {
+                let stats = (0..count("generations")?)
+                    .map(|_| Stats::from(&self.sim.train()).to_json())
+                    .collect();
+
+                self.refresh_buffers();
+
+                Ok(vec![("stats", sim::Json::Array(stats))])
+            }
+
+            "world" => {
+                let world = self.world();
+
+                Ok(vec![
+                    (
+                        "animals",
+                        sim::Json::Array(world.animals.iter().map(Animal::to_json).collect()),
+                    ),
+                    (
+                        "foods",
+                        sim::Json::Array(world.foods.iter().map(Food::to_json).collect()),
+                    ),
+                    (
+                        "predators",
+                        sim::Json::Array(world.predators.iter().map(Predator::to_json).collect()),
+                    ),
+                ])
+            }
+
+            "save" => Ok(vec![("snapshot", sim::Json::String(self.save()))]),
+
+            "load" => {
+                let snapshot = command
+                    .get("snapshot")
+                    .and_then(sim::Json::as_str)
+                    .ok_or("invalid `snapshot`")?;
+
+                *self = Self::restore(snapshot)?;
+                Ok(Vec::new())
+            }
+
+            _ => Err(format!("unknown command `{name}`")),
+        }
+    }
+
+    fn from_sim(mut sim: sim::Simulation, config: &Config) -> Self {
+        let events = Arc::new(Mutex::new(VecDeque::new()));
+
+        sim.subscribe(Box::new({
+            let events = events.clone();
+
+            move |event| {
+                let mut events = events.lock().unwrap();
+
+                if events.len() == MAX_EVENTS {
+                    events.pop_front();
+                }
+
+                events.push_back(event.clone());
+            }
+        }));
+
+        let mut this = Self {
+            sim,
+            paused: false,
+            speed: 1,
+            pending_steps: 0.0,
+            selected: None,
+            size: na::Vector2::new(config.width, config.height),
+            animal_buffer: Vec::new(),
+            previous_animal_buffer: Vec::new(),
+            previous_transforms: HashMap::new(),
+            food_buffer: Vec::new(),
+            events,
+        };
+
+        this.refresh_buffers();
+        this
+    }
+
+    /// Performs `n` steps, remembering where the birds were before the
+    /// last one.
+    fn advance(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+
+        self.sim.step_many(n - 1);
+
+        let size = self.size;
+
+        // Refilled in place, so that frames don't allocate
+        self.previous_transforms.clear();
+
+        self.previous_transforms.extend(
+            self.sim
+                .world()
+                .animals()
+                .iter()
+                .map(|animal| (animal.id(), Self::transform(size, animal))),
+        );
+
+        self.sim.step();
+        self.refresh_buffers();
+    }
+
+    /// Returns bird's position (in the world's dimensions) and rotation.
+    fn transform(size: na::Vector2<f32>, animal: &sim::Animal) -> [f32; 3] {
+        [
+            animal.position().x * size.x,
+            animal.position().y * size.y,
+            stretch_rotation(size, animal.rotation().angle()),
+        ]
+    }
+
+    /// Rewrites `animal_buffer`, `previous_animal_buffer` and `food_buffer`
+    /// with the current state of the world, reusing their allocations.
+    fn refresh_buffers(&mut self) {
+        let world = self.sim.world();
+
+        self.animal_buffer.clear();
+        self.previous_animal_buffer.clear();
+
+        for animal in world.animals().iter().filter(|animal| animal.is_alive()) {
+            let transform = Self::transform(self.size, animal);
+
+            let previous = self
+                .previous_transforms
+                .get(&animal.id())
+                .unwrap_or(&transform);
+
+            self.animal_buffer.extend(transform);
+            self.previous_animal_buffer.extend(previous);
+        }
+
+        self.food_buffer.clear();
+
+        for food in world.foods().iter().filter(|food| food.is_available()) {
+            self.food_buffer.extend([
+                food.position().x * self.size.x,
+                food.position().y * self.size.y,
+            ]);
+        }
+    }
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        Self::new(None, None).expect("default config is valid")
+    }
+}
+
+/// Knobs of a simulation that can be tweaked from JavaScript; everything
+/// else stays as in `sim::Config::default()`.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Number of birds (per species)
+    pub population: usize,
+
+    /// Number of bird populations evolving independently of each other
+    pub species: usize,
 
+    /// Number of foods
+    pub foods: usize,
+
+    /// How far birds see
+    pub eye_fov_range: f32,
+
+    /// How wide (in radians) birds see
+    pub eye_fov_angle: f32,
+
+    /// Number of photoreceptors each eye has
+    pub eye_cells: usize,
+
+    /// Probability of changing each gene during mutation
+    pub mutation_chance: f32,
+
+    /// Magnitude of changes applied to mutated genes
+    pub mutation_coeff: f32,
+
+    /// Number of steps after which the population gets evolved
+    pub generation_length: usize,
+
+    pub speed_min: f32,
+    pub speed_max: f32,
+    pub speed_accel: f32,
+    pub rotation_accel: f32,
+
+    /// Number of pieces each step's movement gets split into
+    pub substeps: usize,
+
+    /// Dimensions of the world, in whatever units JavaScript prefers (e.g.
+    /// the canvas' pixels); all positions going in and out are expressed
+    /// in them.
+    ///
+    /// Birds still fly in a unit square, which simply gets stretched to
+    /// these dimensions; rotations are converted, so that birds point where
+    /// they're flying, but on a non-square world distances get stretched
+    /// unevenly - e.g. birds see farther along its longer side than across
+    /// the shorter one.
+    pub width: f32,
+    pub height: f32,
+}
+
+#[wasm_bindgen]
+impl Config {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self::from_sim(&sim::Config::default(), na::Vector2::new(1.0, 1.0))
+    }
+
+    /// Reads config out of a brain exported by `how-to-fly-cli export` -
+    /// i.e. of a JSON object whose `config` field has the knobs; knobs that
+    /// are missing stay at their defaults.
+    ///
+    /// The brain itself goes to `Simulation::import_brain()` then.
+    pub fn load(json: &str) -> Result<Config, JsError> {
+        let json = sim::Json::parse(json)
+            .map_err(|offset| JsError::new(&format!("not a valid JSON (at byte {offset})")))?;
+
+        let Some(sim::Json::Object(knobs)) = json.get("config") else {
+            return Err(JsError::new("missing `config`"));
+        };
+
+        let mut config = Self::new();
+
+        for (name, value) in knobs {
+            let sim::Json::Number(value) = value else {
+                return Err(JsError::new(&format!("invalid value of `{name}`")));
+            };
+
+            config.set(name, value).map_err(|err| JsError::new(&err))?;
+        }
+
+        Ok(config)
+    }
+}
+
+impl Config {
+    fn from_sim(config: &sim::Config, size: na::Vector2<f32>) -> Self {
+        Self {
+            population: config.population,
+            species: config.species,
+            foods: config.foods,
+            eye_fov_range: config.eye_fov_range,
+            eye_fov_angle: config.eye_fov_angle,
+            eye_cells: config.eye_cells,
+            mutation_chance: config.mutation_chance,
+            mutation_coeff: config.mutation_coeff,
+            generation_length: config.generation_length,
+            speed_min: config.speed_min,
+            speed_max: config.speed_max,
+            speed_accel: config.speed_accel,
+            rotation_accel: config.rotation_accel,
+            substeps: config.substeps,
+            width: size.x,
+            height: size.y,
+        }
+    }
+
+    /// Encodes config in the same `population=40&foods=40&...` format the
+    /// frontend accepts in its URL.
+    fn to_query(&self) -> String {
         format!(
-            "min={:.2}, max={:.2}, avg={:.2}",
-            stats.min_fitness, stats.max_fitness, stats.avg_fitness,
+            "population={}&species={}&foods={}&eye_fov_range={}&eye_fov_angle={}\
+             &eye_cells={}&mutation_chance={}&mutation_coeff={}&generation_length={}\
+             &speed_min={}&speed_max={}&speed_accel={}&rotation_accel={}&substeps={}\
+             &width={}&height={}",
+            self.population,
+            self.species,
+            self.foods,
+            self.eye_fov_range,
+            self.eye_fov_angle,
+            self.eye_cells,
+            self.mutation_chance,
+            self.mutation_coeff,
+            self.generation_length,
+            self.speed_min,
+            self.speed_max,
+            self.speed_accel,
+            self.rotation_accel,
+            self.substeps,
+            self.width,
+            self.height,
         )
     }
+
+    /// Checks whether a simulation can run with this config.
+    fn validate(&self) -> Result<(), String> {
+        validate_size(self.width, self.height)?;
+
+        sim::Config::from(self)
+            .validate()
+            .map_err(|err| err.to_string())
+    }
+
+    /// Decodes config encoded with `to_query()`; knobs that are missing
+    /// stay at their defaults.
+    fn from_query(query: &str) -> Result<Self, String> {
+        let mut config = Self::new();
+
+        for knob in query.split('&') {
+            let (name, value) = knob
+                .split_once('=')
+                .ok_or_else(|| format!("invalid knob `{knob}`"))?;
+
+            config.set(name, value)?;
+        }
+
+        Ok(config)
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let invalid = || format!("invalid value of `{name}`");
+
+        match name {
+            "population" => self.population = value.parse().map_err(|_| invalid())?,
+            "species" => self.species = value.parse().map_err(|_| invalid())?,
+            "foods" => self.foods = value.parse().map_err(|_| invalid())?,
+            "eye_fov_range" => self.eye_fov_range = value.parse().map_err(|_| invalid())?,
+            "eye_fov_angle" => self.eye_fov_angle = value.parse().map_err(|_| invalid())?,
+            "eye_cells" => self.eye_cells = value.parse().map_err(|_| invalid())?,
+            "mutation_chance" => self.mutation_chance = value.parse().map_err(|_| invalid())?,
+            "mutation_coeff" => self.mutation_coeff = value.parse().map_err(|_| invalid())?,
+            "generation_length" => self.generation_length = value.parse().map_err(|_| invalid())?,
+            "speed_min" => self.speed_min = value.parse().map_err(|_| invalid())?,
+            "speed_max" => self.speed_max = value.parse().map_err(|_| invalid())?,
+            "speed_accel" => self.speed_accel = value.parse().map_err(|_| invalid())?,
+            "rotation_accel" => self.rotation_accel = value.parse().map_err(|_| invalid())?,
+            "substeps" => self.substeps = value.parse().map_err(|_| invalid())?,
+            "width" => self.width = value.parse().map_err(|_| invalid())?,
+            "height" => self.height = value.parse().map_err(|_| invalid())?,
+            _ => return Err(format!("unknown knob `{name}`")),
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&Config> for sim::Config {
+    fn from(config: &Config) -> Self {
+        Self {
+            population: config.population,
+            species: config.species,
+            foods: config.foods,
+            eye_fov_range: config.eye_fov_range,
+            eye_fov_angle: config.eye_fov_angle,
+            eye_cells: config.eye_cells,
+            mutation_chance: config.mutation_chance,
+            mutation_coeff: config.mutation_coeff,
+            generation_length: config.generation_length,
+            speed_min: config.speed_min,
+            speed_max: config.speed_max,
+            speed_accel: config.speed_accel,
+            rotation_accel: config.rotation_accel,
+            substeps: config.substeps,
+            ..Self::default()
+        }
+    }
+}
+
+/// Summary of a finished generation, see `sim::Statistics`.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Stats {
+    pub generation: usize,
+    pub min_fitness: f32,
+    pub max_fitness: f32,
+    pub avg_fitness: f32,
+    pub median_fitness: f32,
+    pub avg_age: f32,
+}
+
+impl Stats {
+    fn to_json(&self) -> sim::Json {
+        sim::Json::object([
+            ("generation", sim::Json::number(self.generation)),
+            ("min_fitness", sim::Json::number(self.min_fitness)),
+            ("max_fitness", sim::Json::number(self.max_fitness)),
+            ("avg_fitness", sim::Json::number(self.avg_fitness)),
+            ("median_fitness", sim::Json::number(self.median_fitness)),
+            ("avg_age", sim::Json::number(self.avg_age)),
+        ])
+    }
+}
+
+impl From<&sim::Statistics> for Stats {
+    fn from(stats: &sim::Statistics) -> Self {
+        Self {
+            generation: stats.generation,
+            min_fitness: stats.min_fitness,
+            max_fitness: stats.max_fitness,
+            avg_fitness: stats.avg_fitness,
+            median_fitness: stats.median_fitness,
+            avg_age: stats.avg_age,
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -46,31 +889,107 @@ pub struct World {
 
     #[wasm_bindgen(getter_with_clone)]
     pub foods: Vec<Food>,
+
+    #[wasm_bindgen(getter_with_clone)]
+    pub predators: Vec<Predator>,
 }
 
 #[wasm_bindgen]
 #[derive(Clone, Debug)]
 pub struct Animal {
+    /// Stays the same for as long as the bird lives, see `sim::AnimalId`
+    pub id: u64,
     pub x: f32,
     pub y: f32,
     pub rotation: f32,
+    pub species: usize,
+
+    /// Zero, unless the simulation has been configured with energy
+    pub energy: f32,
+
+    /// Total value of foods eaten
+    pub satiation: f32,
+
+    /// Dead birds stay in the world 'till the end of their generation
+    pub alive: bool,
+
+    /// Whether the bird has come from `import_brain()`
+    pub injected: bool,
+}
+
+impl World {
+    fn new(world: &sim::World, size: na::Vector2<f32>) -> Self {
+        let mut this = Self::from(world);
+
+        for animal in &mut this.animals {
+            animal.x *= size.x;
+            animal.y *= size.y;
+            animal.rotation = stretch_rotation(size, animal.rotation);
+        }
+
+        for food in &mut this.foods {
+            food.x *= size.x;
+            food.y *= size.y;
+        }
+
+        for predator in &mut this.predators {
+            predator.x *= size.x;
+            predator.y *= size.y;
+            predator.rotation = stretch_rotation(size, predator.rotation);
+        }
+
+        this
+    }
 }
 
 impl From<&sim::World> for World {
     fn from(world: &sim::World) -> Self {
         let animals = world.animals().iter().map(Animal::from).collect();
-        let foods = world.foods().iter().map(Food::from).collect();
+        let foods = world
+            .foods()
+            .iter()
+            .filter(|food| food.is_available())
+            .map(Food::from)
+            .collect();
 
-        Self { animals, foods }
+        let predators = world.predators().iter().map(Predator::from).collect();
+
+        Self {
+            animals,
+            foods,
+            predators,
+        }
+    }
+}
+
+impl Animal {
+    fn to_json(&self) -> sim::Json {
+        sim::Json::object([
+            ("id", sim::Json::number(self.id)),
+            ("x", sim::Json::number(self.x)),
+            ("y", sim::Json::number(self.y)),
+            ("rotation", sim::Json::number(self.rotation)),
+            ("species", sim::Json::number(self.species)),
+            ("energy", sim::Json::number(self.energy)),
+            ("satiation", sim::Json::number(self.satiation)),
+            ("alive", sim::Json::Bool(self.alive)),
+            ("injected", sim::Json::Bool(self.injected)),
+        ])
     }
 }
 
 impl From<&sim::Animal> for Animal {
     fn from(animal: &sim::Animal) -> Self {
         Self {
+            id: animal.id().0,
             x: animal.position().x,
             y: animal.position().y,
             rotation: animal.rotation().angle(),
+            species: animal.species(),
+            energy: animal.energy(),
+            satiation: animal.satiation(),
+            alive: animal.is_alive(),
+            injected: animal.is_injected(),
         }
     }
 }
@@ -80,6 +999,19 @@ impl From<&sim::Animal> for Animal {
 pub struct Food {
     pub x: f32,
     pub y: f32,
+
+    /// Index into the simulation's `Config::food_kinds`
+    pub kind: usize,
+}
+
+impl Food {
+    fn to_json(&self) -> sim::Json {
+        sim::Json::object([
+            ("x", sim::Json::number(self.x)),
+            ("y", sim::Json::number(self.y)),
+            ("kind", sim::Json::number(self.kind)),
+        ])
+    }
 }
 
 impl From<&sim::Food> for Food {
@@ -87,6 +1019,249 @@ impl From<&sim::Food> for Food {
         Self {
             x: food.position().x,
             y: food.position().y,
+            kind: food.kind(),
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    FoodEaten,
+    AnimalDied,
+    AnimalBorn,
+    GenerationEnded,
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    Starved,
+    Caught,
+    Retired,
+}
+
+/// Something noteworthy that has happened, see `sim::Event`; fields that
+/// don't apply to given kind are left empty.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Event {
+    pub kind: EventKind,
+
+    /// Bird that has eaten, died or been born
+    pub animal: Option<u64>,
+
+    /// Parent of the bird that has been born
+    pub parent: Option<u64>,
+
+    pub cause: Option<DeathCause>,
+
+    /// Statistics of the generation that has ended
+    #[wasm_bindgen(getter_with_clone)]
+    pub stats: Option<Stats>,
+}
+
+impl From<&sim::Event> for Event {
+    fn from(event: &sim::Event) -> Self {
+        let mut this = Self {
+            kind: EventKind::FoodEaten,
+            animal: None,
+            parent: None,
+            cause: None,
+            stats: None,
+        };
+
+        match event {
+            sim::Event::FoodEaten { id, .. } => {
+                this.animal = Some(id.0);
+            }
+
+            sim::Event::AnimalDied { id, cause, .. } => {
+                this.kind = EventKind::AnimalDied;
+                this.animal = Some(id.0);
+
+                this.cause = Some(match cause {
+                    sim::DeathCause::Starved => DeathCause::Starved,
+                    sim::DeathCause::Caught => DeathCause::Caught,
+                    sim::DeathCause::Retired => DeathCause::Retired,
+                });
+            }
+
+            sim::Event::AnimalBorn { id, parent_id, .. } => {
+                this.kind = EventKind::AnimalBorn;
+                this.animal = Some(id.0);
+                this.parent = Some(parent_id.0);
+            }
+
+            sim::Event::GenerationEnded { stats } => {
+                this.kind = EventKind::GenerationEnded;
+                this.stats = Some(Stats::from(stats));
+            }
+        }
+
+        this
+    }
+}
+
+/// Insides of a bird's head, see `sim::Inspection`.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct AnimalDebug {
+    /// Eye cells of all the channels, one after another
+    #[wasm_bindgen(getter_with_clone)]
+    pub vision: Vec<f32>,
+
+    /// Outputs of all the brain's layers (starting with its inputs), one
+    /// after another
+    #[wasm_bindgen(getter_with_clone)]
+    pub activations: Vec<f32>,
+
+    /// Number of neurons in each layer of `activations`
+    #[wasm_bindgen(getter_with_clone)]
+    pub layer_sizes: Vec<usize>,
+
+    /// Change of speed the brain asks for
+    pub speed: f32,
+
+    /// Change of rotation the brain asks for
+    pub rotation: f32,
+}
+
+impl From<sim::Inspection> for AnimalDebug {
+    fn from(inspection: sim::Inspection) -> Self {
+        Self {
+            layer_sizes: inspection.activations.iter().map(Vec::len).collect(),
+            activations: inspection.activations.concat(),
+            vision: inspection.vision,
+            speed: inspection.outputs[0],
+            rotation: inspection.outputs[1],
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Predator {
+    pub x: f32,
+    pub y: f32,
+    pub rotation: f32,
+}
+
+impl Predator {
+    fn to_json(&self) -> sim::Json {
+        sim::Json::object([
+            ("x", sim::Json::number(self.x)),
+            ("y", sim::Json::number(self.y)),
+            ("rotation", sim::Json::number(self.rotation)),
+        ])
+    }
+}
+
+impl From<&sim::Predator> for Predator {
+    fn from(predator: &sim::Predator) -> Self {
+        Self {
+            x: predator.position().x,
+            y: predator.position().y,
+            rotation: predator.rotation().angle(),
         }
     }
 }
+
+/// Generations recorded by `how-to-fly-cli train --replay`, played back
+/// frame by frame; like the simulation's, positions are given in the
+/// world's dimensions.
+#[wasm_bindgen]
+pub struct Replay {
+    highlights: sim::Highlights,
+    size: na::Vector2<f32>,
+}
+
+#[wasm_bindgen]
+impl Replay {
+    /// Clips without any frames get skipped, so that each of `clips()` has
+    /// something to play.
+    pub fn load(highlights: &str, width: f32, height: f32) -> Result<Replay, JsError> {
+        validate_size(width, height).map_err(|err| JsError::new(&err))?;
+
+        let mut highlights =
+            sim::Highlights::load(highlights).map_err(|err| JsError::new(&err.to_string()))?;
+
+        highlights.clips.retain(|clip| !clip.replay.is_empty());
+
+        Ok(Self {
+            highlights,
+            size: na::Vector2::new(width, height),
+        })
+    }
+
+    /// Returns number of recorded generations.
+    pub fn clips(&self) -> usize {
+        self.highlights.clips.len()
+    }
+
+    /// Returns why given generation got recorded - e.g. `first, best`.
+    pub fn label(&self, clip: usize) -> Result<String, JsError> {
+        Ok(self.clip(clip)?.labels.join(", "))
+    }
+
+    pub fn generation(&self, clip: usize) -> Result<usize, JsError> {
+        Ok(self.clip(clip)?.generation)
+    }
+
+    /// Returns number of frames given generation lasts for.
+    pub fn frames(&self, clip: usize) -> Result<usize, JsError> {
+        Ok(self.clip(clip)?.replay.len())
+    }
+
+    pub fn frame(&self, clip: usize, step: usize) -> Result<Option<ReplayFrame>, JsError> {
+        let Some(frame) = self.clip(clip)?.replay.frame(step) else {
+            return Ok(None);
+        };
+
+        let transform = |pose: &sim::Pose| {
+            [
+                pose.position.x * self.size.x,
+                pose.position.y * self.size.y,
+                stretch_rotation(self.size, pose.rotation),
+            ]
+        };
+
+        Ok(Some(ReplayFrame {
+            animals: frame.animals.iter().flatten().flat_map(transform).collect(),
+            foods: frame
+                .foods
+                .iter()
+                .flatten()
+                .flat_map(|food| [food.x * self.size.x, food.y * self.size.y])
+                .collect(),
+            predators: frame.predators.iter().flat_map(transform).collect(),
+        }))
+    }
+}
+
+impl Replay {
+    fn clip(&self, clip: usize) -> Result<&sim::Clip, JsError> {
+        self.highlights
+            .clips
+            .get(clip)
+            .ok_or_else(|| JsError::new(&format!("there's no clip #{clip}")))
+    }
+}
+
+/// State of the world in a `Replay`, laid out like
+/// `Simulation::animal_buffer()` and `Simulation::food_buffer()`.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct ReplayFrame {
+    /// Positions and rotations of living birds
+    #[wasm_bindgen(getter_with_clone)]
+    pub animals: Vec<f32>,
+
+    /// Positions of available foods
+    #[wasm_bindgen(getter_with_clone)]
+    pub foods: Vec<f32>,
+
+    /// Positions and rotations of predators
+    #[wasm_bindgen(getter_with_clone)]
+    pub predators: Vec<f32>,
+}
@@ -0,0 +1,44 @@
+//! Measures `Simulation::step()` for a few world sizes, with the default
+//! and a short-sighted eye (denser worlds see proportionally more, so the
+//! latter is closer to what a larger world looks like).
+//!
+//! ```text
+//! cargo bench -p lib-simulation --bench step
+//! ```
+
+use lib_simulation::{Config, Simulation};
+use std::hint::black_box;
+use std::time::Instant;
+
+const STEPS: u32 = 20;
+
+fn main() {
+    for &(population, foods) in &[(40, 40), (200, 2_000), (1_000, 10_000), (2_000, 40_000)] {
+        for eye_fov_range in [0.25, 0.05] {
+            run(Config {
+                population,
+                foods,
+                eye_fov_range,
+                ..Config::default()
+            });
+        }
+    }
+}
+
+fn run(config: Config) {
+    let (population, foods, range) = (config.population, config.foods, config.eye_fov_range);
+
+    let mut sim = Simulation::new(config, 0);
+    let started_at = Instant::now();
+
+    for _ in 0..STEPS {
+        black_box(sim.step());
+    }
+
+    let elapsed = started_at.elapsed();
+
+    println!(
+        "step {population} birds, {foods} foods, range {range}: {:>10.1} us/step",
+        elapsed.as_micros() as f64 / STEPS as f64,
+    );
+}
@@ -1,21 +1,76 @@
 use crate::*;
 
+/// Identifies a bird for as long as it lives - unlike its index into
+/// `World::animals()`, which changes as other birds die or get born.
+///
+/// Ids are unique within a single world; birds of the next generation
+/// get new ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AnimalId(pub u64);
+
 #[derive(Debug)]
 pub struct Animal {
+    /// Given by `World` once the bird enters it
+    pub(crate) id: AnimalId,
     pub(crate) position: na::Point2<f32>,
     pub(crate) rotation: na::Rotation2<f32>,
     pub(crate) speed: f32,
+
+    /// Change in rotation during the last step
+    pub(crate) turn: f32,
     pub(crate) eye: Eye,
     pub(crate) brain: Brain,
-    pub(crate) satiation: usize,
+    pub(crate) body: Body,
+
+    /// What the bird has heard this step - other birds and predators (see
+    /// `Senses::hearing`)
+    pub(crate) sound: [f32; 2],
+
+    /// Index of the population this bird belongs to (see
+    /// `Config::species`)
+    pub(crate) species: usize,
+
+    /// Index into `Config::baselines`, for birds following a policy
+    /// instead of their brain
+    pub(crate) baseline: Option<usize>,
+
+    /// Whether this bird has been put into the world through
+    /// `Simulation::inject()` (instead of having evolved)
+    pub(crate) injected: bool,
+
+    /// Total value of foods eaten
+    pub(crate) satiation: f32,
+
+    /// Age at which the first food has been eaten
+    pub(crate) first_food: Option<usize>,
+
+    /// Total distance travelled
+    pub(crate) distance: f32,
+
+    /// Sum of all the movements - i.e. where the bird would've ended up
+    /// relative to its start, if the world didn't wrap around
+    pub(crate) displacement: na::Vector2<f32>,
+
+    /// Ignored, unless `Config::energy` is enabled
+    pub(crate) energy: f32,
+    pub(crate) energy_spent: f32,
+
+    /// Number of steps this bird has been alive for
+    pub(crate) age: usize,
+
+    /// Number of steps this bird was able to move
+    pub(crate) survived: usize,
+
+    pub(crate) alive: bool,
 }
 
 impl Animal {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::random(rng, &eye);
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let body = Body::random(config, rng);
+        let eye = body.eye(config);
+        let brain = Brain::random(rng, &eye, &config.channels(), &config.senses);
 
-        Self::new(eye, brain, rng)
+        Self::new(config, eye, brain, body, rng)
     }
 
     /// "Restores" bird from a chromosome.
@@ -24,22 +79,40 @@ impl Animal {
     /// chromosomes encode only the brains - and while we restore the
     /// bird, we have to also randomize its position, direction, etc.
     /// (so it's stuff that wouldn't make sense to keep in the genome.)
-    pub(crate) fn from_chromosome(chromosome: ga::Chromosome, rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::from_chromosome(chromosome, &eye);
+    pub(crate) fn from_chromosome(
+        config: &Config,
+        chromosome: ga::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        let mut genes: Vec<_> = chromosome.into_iter().collect();
+        let body = genes.split_off(genes.len() - config.traits.gene_count());
 
-        Self::new(eye, brain, rng)
+        let body = Body::from_genes(config, body);
+        let eye = body.eye(config);
+        let brain = Brain::from_chromosome(
+            genes.into_iter().collect(),
+            &eye,
+            &config.channels(),
+            &config.senses,
+        );
+
+        Self::new(config, eye, brain, body, rng)
     }
 
-    pub(crate) fn as_chromosome(&self) -> ga::Chromosome {
-        // We evolve only our birds' brains, but technically there's no
-        // reason not to simulate e.g. physical properties such as size.
-        //
-        // If that was to happen, this function could be adjusted to
-        // return a longer chromosome that encodes not only the brain,
-        // but also, say, birdie's color.
+    /// Returns bird's genes - e.g. to carry it over to another simulation
+    /// with `Simulation::seed_with()`.
+    pub fn as_chromosome(&self) -> ga::Chromosome {
+        // Brain goes first, followed by the physical traits (if any are
+        // enabled through `Config::traits`)
+        self.brain
+            .as_chromosome()
+            .into_iter()
+            .chain(self.body.genes.iter().copied())
+            .collect()
+    }
 
-        self.brain.as_chromosome()
+    pub fn id(&self) -> AnimalId {
+        self.id
     }
 
     pub fn position(&self) -> na::Point2<f32> {
@@ -50,14 +123,133 @@ impl Animal {
         self.rotation
     }
 
-    fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
+    pub fn eye(&self) -> &Eye {
+        &self.eye
+    }
+
+    pub fn body(&self) -> &Body {
+        &self.body
+    }
+
+    pub fn age(&self) -> usize {
+        self.age
+    }
+
+    pub fn species(&self) -> usize {
+        self.species
+    }
+
+    pub fn baseline(&self) -> Option<usize> {
+        self.baseline
+    }
+
+    pub fn is_injected(&self) -> bool {
+        self.injected
+    }
+
+    /// Whether this bird takes part in the evolution - i.e. it's neither a
+    /// baseline nor an injected one.
+    pub(crate) fn is_evolved(&self) -> bool {
+        self.baseline.is_none() && !self.injected
+    }
+
+    pub(crate) fn with_species(self, species: usize) -> Self {
+        Self { species, ..self }
+    }
+
+    pub fn energy(&self) -> f32 {
+        self.energy
+    }
+
+    /// Total value of foods eaten
+    pub fn satiation(&self) -> f32 {
+        self.satiation
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    pub(crate) fn can_move(&self, config: &Config) -> bool {
+        self.alive && (config.energy.is_none() || self.energy > 0.0)
+    }
+
+    /// Pays for this step's movement and for turning by `rotation`.
+    pub(crate) fn spend_energy(&mut self, config: &EnergyConfig, rotation: f32) {
+        let cost =
+            config.idle_cost + config.move_cost * self.speed + config.turn_cost * rotation.abs();
+
+        let cost = cost.min(self.energy);
+
+        self.energy_spent += cost;
+        self.energy -= cost;
+
+        if self.energy <= 0.0 && config.on_empty == OnEmpty::Die {
+            self.alive = false;
+        }
+    }
+
+    fn new(config: &Config, eye: Eye, brain: Brain, body: Body, rng: &mut dyn RngCore) -> Self {
         Self {
+            id: AnimalId::default(),
             position: rng.gen(),
             rotation: rng.gen(),
             speed: 0.002,
+            turn: 0.0,
             eye,
             brain,
-            satiation: 0,
+            body,
+            sound: [0.0, 0.0],
+            species: 0,
+            baseline: None,
+            injected: false,
+            satiation: 0.0,
+            first_food: None,
+            distance: 0.0,
+            displacement: na::Vector2::zeros(),
+            energy: config.energy.as_ref().map_or(0.0, |energy| energy.initial),
+            energy_spent: 0.0,
+            age: 0,
+            survived: 0,
+            alive: true,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn spend_energy() {
+        let energy = EnergyConfig {
+            initial: 0.01,
+            idle_cost: 0.002,
+            move_cost: 1.0,
+            turn_cost: 0.0,
+            on_empty: OnEmpty::Die,
+            ..EnergyConfig::default()
+        };
+
+        let config = Config {
+            energy: Some(energy.clone()),
+            ..Config::default()
+        };
+
+        let mut animal = Animal::random(&config, &mut StepRng::new(0, 1));
+
+        animal.spend_energy(&energy, 1.0);
+        assert_relative_eq!(animal.energy, 0.01 - 0.002 - 0.002);
+        assert!(animal.can_move(&config));
+
+        animal.spend_energy(&energy, 1.0);
+        animal.spend_energy(&energy, 1.0);
+
+        assert_relative_eq!(animal.energy, 0.0);
+        assert_relative_eq!(animal.energy_spent, 0.01);
+        assert!(!animal.is_alive());
+        assert!(!animal.can_move(&config));
+    }
+}
@@ -1,7 +1,7 @@
 use crate::*;
 
 pub struct AnimalIndividual {
-    fitness: f32,
+    fitness: ga::Float,
     chromosome: ga::Chromosome,
 }
 
@@ -17,20 +17,28 @@ impl ga::Individual for AnimalIndividual {
         &self.chromosome
     }
 
-    fn fitness(&self) -> f32 {
+    fn fitness(&self) -> ga::Float {
         self.fitness
     }
+
+    fn into_chromosome(self) -> ga::Chromosome {
+        self.chromosome
+    }
 }
 
 impl AnimalIndividual {
-    pub fn from_animal(animal: &Animal) -> Self {
+    pub fn new(fitness: ga::Float, chromosome: ga::Chromosome) -> Self {
         Self {
-            fitness: animal.satiation as f32,
-            chromosome: animal.as_chromosome(),
+            fitness,
+            chromosome,
         }
     }
 
-    pub fn into_animal(self, rng: &mut dyn RngCore) -> Animal {
-        Animal::from_chromosome(self.chromosome, rng)
+    pub fn from_animal(animal: &Animal, fitness: &dyn FitnessFn) -> Self {
+        Self::new(score(fitness, animal) as ga::Float, animal.as_chromosome())
+    }
+
+    pub fn into_animal(self, config: &Config, rng: &mut dyn RngCore) -> Animal {
+        Animal::from_chromosome(config, self.chromosome, rng)
     }
 }
@@ -0,0 +1,158 @@
+use crate::*;
+
+/// Flocking and foraging metrics of a generation.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Behavior {
+    /// Average distance between a bird and its nearest neighbour (lower
+    /// means tighter flocks)
+    pub nearest_neighbor: f32,
+
+    /// How much birds head the same way, from zero (every which way) to
+    /// one (all in parallel)
+    pub alignment: f32,
+
+    /// Fraction of the world visited by at least one bird
+    pub coverage: f32,
+
+    /// How winding birds' paths are, from zero (straight lines) to one
+    /// (ending up where they've started)
+    pub tortuosity: f32,
+}
+
+/// Nearest neighbours are costly to find, so they (and alignment) are
+/// sampled only every couple of steps.
+const SAMPLING: usize = 10;
+
+/// Coverage is measured on a grid of this many cells along each axis.
+const COVERAGE_RESOLUTION: usize = 20;
+
+/// Accumulates `Behavior` over a generation.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct BehaviorTracker {
+    pub(crate) steps: usize,
+    pub(crate) samples: usize,
+    pub(crate) nearest_neighbor: f32,
+    pub(crate) alignment: f32,
+    pub(crate) visited: Vec<bool>,
+}
+
+impl BehaviorTracker {
+    pub(crate) fn new() -> Self {
+        Self {
+            steps: 0,
+            samples: 0,
+            nearest_neighbor: 0.0,
+            alignment: 0.0,
+            visited: vec![false; COVERAGE_RESOLUTION * COVERAGE_RESOLUTION],
+        }
+    }
+
+    /// Takes a look at the birds; supposed to be called after each step.
+    pub(crate) fn observe(&mut self, animals: &[Animal]) {
+        let alive: Vec<_> = animals.iter().filter(|animal| animal.alive).collect();
+
+        for animal in &alive {
+            let coord = |value: f32| {
+                ((value * COVERAGE_RESOLUTION as f32) as usize).min(COVERAGE_RESOLUTION - 1)
+            };
+
+            let cell = coord(animal.position.y) * COVERAGE_RESOLUTION + coord(animal.position.x);
+            self.visited[cell] = true;
+        }
+
+        if self.steps.is_multiple_of(SAMPLING) && alive.len() >= 2 {
+            let nearest_neighbor = alive
+                .iter()
+                .enumerate()
+                .map(|(idx, animal)| {
+                    alive
+                        .iter()
+                        .enumerate()
+                        .filter(|&(other, _)| other != idx)
+                        .map(|(_, other)| na::distance(&animal.position, &other.position))
+                        .fold(f32::INFINITY, f32::min)
+                })
+                .sum::<f32>();
+
+            let heading = alive
+                .iter()
+                .map(|animal| animal.rotation * na::Vector2::y())
+                .sum::<na::Vector2<f32>>();
+
+            self.nearest_neighbor += nearest_neighbor / alive.len() as f32;
+            self.alignment += heading.norm() / alive.len() as f32;
+            self.samples += 1;
+        }
+
+        self.steps += 1;
+    }
+
+    /// Returns the generation's metrics and starts tracking anew.
+    pub(crate) fn finish(&mut self, animals: &[Animal]) -> Behavior {
+        let samples = self.samples.max(1) as f32;
+
+        let paths: Vec<_> = animals
+            .iter()
+            .filter(|animal| animal.distance > 0.0)
+            .map(|animal| 1.0 - (animal.displacement.norm() / animal.distance).min(1.0))
+            .collect();
+
+        let behavior = Behavior {
+            nearest_neighbor: self.nearest_neighbor / samples,
+            alignment: self.alignment / samples,
+            coverage: self.visited.iter().filter(|&&visited| visited).count() as f32
+                / self.visited.len() as f32,
+            tortuosity: paths.iter().sum::<f32>() / paths.len().max(1) as f32,
+        };
+
+        *self = Self::new();
+
+        behavior
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::rngs::mock::StepRng;
+
+    fn animal(x: f32, y: f32, rotation: f32) -> Animal {
+        Animal {
+            position: na::Point2::new(x, y),
+            rotation: na::Rotation2::new(rotation),
+            ..Animal::random(&Config::default(), &mut StepRng::new(0, 1))
+        }
+    }
+
+    #[test]
+    fn flock() {
+        let mut animals = vec![
+            animal(0.1, 0.1, 0.0),
+            animal(0.1, 0.2, 0.0),
+            animal(0.9, 0.9, PI),
+            animal(0.9, 0.6, PI),
+        ];
+
+        // Flew straight ahead...
+        animals[0].distance = 0.5;
+        animals[0].displacement = na::Vector2::new(0.0, 0.5);
+
+        // ... and back
+        animals[1].distance = 0.5;
+        animals[1].displacement = na::Vector2::zeros();
+
+        let mut tracker = BehaviorTracker::new();
+        tracker.observe(&animals);
+
+        let behavior = tracker.finish(&animals);
+
+        assert_relative_eq!(behavior.nearest_neighbor, (0.1 + 0.1 + 0.3 + 0.3) / 4.0);
+        assert_relative_eq!(behavior.alignment, 0.0, epsilon = 1e-6);
+        assert_relative_eq!(behavior.coverage, 4.0 / 400.0);
+        assert_relative_eq!(behavior.tortuosity, 0.5);
+
+        // Tracker starts anew
+        assert_eq!(tracker, BehaviorTracker::new());
+    }
+}
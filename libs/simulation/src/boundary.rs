@@ -0,0 +1,105 @@
+use crate::*;
+
+/// What happens to things that fly off the edge of the (unit-sized)
+/// world.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Boundary {
+    /// Reappear on the opposite side, as if the world was a torus
+    #[default]
+    Wrap,
+
+    /// Bounce off the edge, like a ball off a wall
+    Bounce,
+
+    /// Stop at the edge, keeping the direction
+    Clamp,
+}
+
+impl Boundary {
+    pub(crate) fn apply(self, position: &mut na::Point2<f32>, rotation: &mut na::Rotation2<f32>) {
+        match self {
+            Boundary::Wrap => {
+                position.x = na::wrap(position.x, 0.0, 1.0);
+                position.y = na::wrap(position.y, 0.0, 1.0);
+            }
+
+            Boundary::Bounce => {
+                // Rotation of `a` points towards (-sin a, cos a), so
+                // mirroring the x component gives `-a` and mirroring the
+                // y component gives `PI - a`
+                if !(0.0..=1.0).contains(&position.x) {
+                    position.x = reflect(position.x);
+                    *rotation = na::Rotation2::new(-rotation.angle());
+                }
+
+                if !(0.0..=1.0).contains(&position.y) {
+                    position.y = reflect(position.y);
+                    *rotation = na::Rotation2::new(PI - rotation.angle());
+                }
+            }
+
+            Boundary::Clamp => {
+                position.x = position.x.clamp(0.0, 1.0);
+                position.y = position.y.clamp(0.0, 1.0);
+            }
+        }
+    }
+}
+
+fn reflect(value: f32) -> f32 {
+    if value < 0.0 {
+        (-value).min(1.0)
+    } else {
+        (2.0 - value).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn apply(boundary: Boundary, x: f32, y: f32, angle: f32) -> (f32, f32, f32) {
+        let mut position = na::Point2::new(x, y);
+        let mut rotation = na::Rotation2::new(angle);
+
+        boundary.apply(&mut position, &mut rotation);
+
+        (position.x, position.y, rotation.angle())
+    }
+
+    #[test]
+    fn wrap() {
+        let (x, y, angle) = apply(Boundary::Wrap, 1.1, -0.25, 0.5);
+
+        assert_relative_eq!(x, 0.1, epsilon = 1e-6);
+        assert_relative_eq!(y, 0.75);
+        assert_relative_eq!(angle, 0.5);
+    }
+
+    #[test]
+    fn bounce() {
+        // Flying to the right, i.e. towards (1, 0)
+        let (x, y, angle) = apply(Boundary::Bounce, 1.1, 0.5, -FRAC_PI_2);
+
+        assert_relative_eq!(x, 0.9, epsilon = 1e-6);
+        assert_relative_eq!(y, 0.5);
+        assert_relative_eq!(angle, FRAC_PI_2);
+
+        // Flying up, i.e. towards (0, 1)
+        let (x, y, angle) = apply(Boundary::Bounce, 0.5, 1.2, 0.0);
+
+        assert_relative_eq!(x, 0.5);
+        assert_relative_eq!(y, 0.8, epsilon = 1e-6);
+        assert_relative_eq!(angle.abs(), PI);
+    }
+
+    #[test]
+    fn clamp() {
+        let (x, y, angle) = apply(Boundary::Clamp, 1.1, -0.2, 0.5);
+
+        assert_relative_eq!(x, 1.0);
+        assert_relative_eq!(y, 0.0);
+        assert_relative_eq!(angle, 0.5);
+    }
+}
@@ -6,31 +6,108 @@ pub struct Brain {
 }
 
 impl Brain {
-    pub fn random(rng: &mut dyn RngCore, eye: &Eye) -> Self {
+    /// Creates a brain for an `eye` looking through given `channels` (and
+    /// feeling through given `senses`).
+    pub fn random(rng: &mut dyn RngCore, eye: &Eye, channels: &[Channel], senses: &Senses) -> Self {
         Self {
-            nn: nn::Network::random(rng, &Self::topology(eye)),
+            nn: Self::builder(eye, channels, senses).build(rng),
         }
     }
 
-    pub(crate) fn from_chromosome(chromosome: ga::Chromosome, eye: &Eye) -> Self {
+    pub(crate) fn from_chromosome(
+        chromosome: ga::Chromosome,
+        eye: &Eye,
+        channels: &[Channel],
+        senses: &Senses,
+    ) -> Self {
+        let builder = Self::builder(eye, channels, senses);
+
+        // Genes and weights can be of different precisions, depending on
+        // the features enabled in both crates
+        let weights = chromosome.into_iter().map(|gene| gene as nn::Float);
+        let nn = nn::Network::from_weights(&builder.topology(), weights);
+
         Self {
-            nn: nn::Network::from_weights(&Self::topology(eye), chromosome),
+            nn: builder.name(nn).expect("names always fit the topology"),
         }
     }
 
     pub(crate) fn as_chromosome(&self) -> ga::Chromosome {
-        self.nn.weights().collect()
+        self.nn
+            .weights()
+            .map(|weight| weight as ga::Float)
+            .collect()
     }
 
-    fn topology(eye: &Eye) -> [nn::LayerTopology; 3] {
-        [
-            nn::LayerTopology {
-                neurons: eye.cells(),
-            },
-            nn::LayerTopology {
-                neurons: 2 * eye.cells(),
-            },
-            nn::LayerTopology { neurons: 2 },
-        ]
+    /// Returns the brain's response - i.e. the change in speed and
+    /// rotation.
+    pub(crate) fn propagate(
+        &self,
+        inputs: &[f32],
+        scratch: &mut nn::PropagationScratch,
+    ) -> [f32; 2] {
+        let speed = self.nn.output_index("speed").unwrap();
+        let rotation = self.nn.output_index("rotation").unwrap();
+
+        let inputs = inputs.iter().map(|&input| input as nn::Float);
+        let outputs = self.nn.propagate_with(inputs, scratch);
+
+        // No-op for the default precision
+        #[allow(clippy::unnecessary_cast)]
+        let outputs = [outputs[speed] as f32, outputs[rotation] as f32];
+
+        outputs
+    }
+
+    /// Like `propagate()`, but also returns activations of all the layers
+    /// (starting with the inputs).
+    pub(crate) fn propagate_traced(&self, inputs: &[f32]) -> (Vec<Vec<f32>>, [f32; 2]) {
+        let speed = self.nn.output_index("speed").unwrap();
+        let rotation = self.nn.output_index("rotation").unwrap();
+
+        let inputs = inputs.iter().map(|&input| input as nn::Float).collect();
+        let trace = self.nn.propagate_traced(inputs);
+
+        // No-op for the default precision
+        #[allow(clippy::unnecessary_cast)]
+        let activations: Vec<Vec<f32>> = trace
+            .activations
+            .iter()
+            .map(|layer| layer.iter().map(|&neuron| neuron as f32).collect())
+            .collect();
+
+        let outputs = activations.last().unwrap();
+        let outputs = [outputs[speed], outputs[rotation]];
+
+        (activations, outputs)
+    }
+
+    /// Describes which genes of `as_chromosome()` belong to which layer.
+    pub(crate) fn segment_layout(
+        eye: &Eye,
+        channels: &[Channel],
+        senses: &Senses,
+    ) -> ga::SegmentLayout {
+        nn::Network::layer_weight_counts(&Self::builder(eye, channels, senses).topology()).collect()
+    }
+
+    /// Inputs are named after the channels and cells (`food_0`, `food_1`,
+    /// ..., `predator_0` etc.), followed by the senses (see `Senses`);
+    /// outputs are `speed` and `rotation`.
+    pub(crate) fn builder(eye: &Eye, channels: &[Channel], senses: &Senses) -> nn::NetworkBuilder {
+        let inputs: Vec<_> = channels
+            .iter()
+            .flat_map(|channel| {
+                (0..eye.cells()).map(move |cell| format!("{}_{cell}", channel.name()))
+            })
+            .chain(senses.names().into_iter().map(String::from))
+            .collect();
+
+        let hidden = 2 * inputs.len();
+
+        nn::Network::builder()
+            .input_named(inputs)
+            .hidden(hidden, nn::Activation::Relu)
+            .output_named(["speed", "rotation"], nn::Activation::Relu)
     }
 }
@@ -0,0 +1,88 @@
+use crate::*;
+
+/// Kind of objects seen by an eye.
+///
+/// Each channel gets its own set of photoreceptors, so that e.g. a bird
+/// can tell food from a predator; brains get all the channels one after
+/// another, in the order returned by `Config::channels()`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    /// Foods of given kind (an index into `Config::food_kinds`)
+    Food(usize),
+    Predators,
+}
+
+impl Channel {
+    /// Prefix of the brain inputs fed by this channel - `food`, `food1`,
+    /// `food2`, ..., `predator`.
+    pub(crate) fn name(self) -> String {
+        match self {
+            Channel::Food(0) => "food".into(),
+            Channel::Food(kind) => format!("food{kind}"),
+            Channel::Predators => "predator".into(),
+        }
+    }
+
+    pub(crate) fn see(
+        self,
+        eye: &Eye,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        world: &WorldView,
+        cells: &mut [f32],
+    ) {
+        match self {
+            Channel::Food(kind) => eye.see(
+                position,
+                rotation,
+                world
+                    .food_grid
+                    .query(position, eye.fov_range())
+                    .into_iter()
+                    .map(|idx| &world.foods[idx])
+                    .filter(|food| food.available && food.kind == kind)
+                    .map(|food| food.position),
+                world.obstacles,
+                cells,
+            ),
+            Channel::Predators => eye.see(
+                position,
+                rotation,
+                world.predators.iter().map(|predator| predator.position),
+                world.obstacles,
+                cells,
+            ),
+        }
+    }
+}
+
+/// Parts of the world that can be seen, borrowed separately from the animals
+/// (which get modified while they're looking around).
+pub(crate) struct WorldView<'a> {
+    pub(crate) foods: &'a [Food],
+
+    /// Available `foods`, bucketed by their position
+    pub(crate) food_grid: &'a Grid,
+    pub(crate) predators: &'a [Predator],
+    pub(crate) obstacles: &'a [Obstacle],
+    pub(crate) scent: Option<&'a ScentField>,
+
+    /// Current daylight (see `DayNight::light()`; one if it's disabled)
+    pub(crate) light: f32,
+
+    /// Fraction of the eyes' range that's visible right now
+    pub(crate) vision: f32,
+}
+
+/// Returns what `animal` sees through all of the `channels`.
+pub(crate) fn look(animal: &Animal, channels: &[Channel], world: &WorldView) -> Vec<f32> {
+    let eye = animal.eye.dimmed(world.vision);
+    let cells = eye.cells();
+    let mut vision = vec![0.0; cells * channels.len()];
+
+    for (channel, cells) in channels.iter().zip(vision.chunks_exact_mut(cells)) {
+        channel.see(&eye, animal.position, animal.rotation, world, cells);
+    }
+
+    vision
+}
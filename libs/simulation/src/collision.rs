@@ -0,0 +1,153 @@
+use crate::*;
+
+/// Keeps birds from flying through each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Collisions {
+    /// Distance below which two birds collide
+    pub distance: f32,
+    pub response: CollisionResponse,
+}
+
+impl Default for Collisions {
+    fn default() -> Self {
+        Self {
+            distance: 0.01,
+            response: CollisionResponse::default(),
+        }
+    }
+}
+
+/// What happens to birds that have collided.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CollisionResponse {
+    /// Birds get pushed apart and bounce off each other
+    #[default]
+    Bounce,
+
+    /// Birds keep flying, but each loses given energy (ignored, unless
+    /// `Config::energy` is enabled)
+    Penalty { energy: f32 },
+}
+
+/// Finds colliding birds and applies `config.response` to them.
+pub(crate) fn collide(config: &Collisions, animals: &mut [Animal]) {
+    let mut grid = Grid::new(config.distance);
+
+    grid.rebuild(
+        animals
+            .iter()
+            .enumerate()
+            .filter(|(_, animal)| animal.alive)
+            .map(|(idx, animal)| (idx, animal.position)),
+    );
+
+    // Pairs are found first, so that the response doesn't affect which
+    // birds collide
+    let mut pairs = Vec::new();
+
+    for (a, animal) in animals.iter().enumerate() {
+        if !animal.alive {
+            continue;
+        }
+
+        let mut nearby = grid.query(animal.position, config.distance);
+        nearby.sort_unstable();
+
+        for b in nearby {
+            if b > a && na::distance(&animal.position, &animals[b].position) < config.distance {
+                pairs.push((a, b));
+            }
+        }
+    }
+
+    for (a, b) in pairs {
+        match config.response {
+            CollisionResponse::Bounce => {
+                let offset = animals[b].position - animals[a].position;
+
+                // Birds right on top of each other get pushed apart sideways
+                let normal = offset
+                    .try_normalize(f32::EPSILON)
+                    .unwrap_or_else(na::Vector2::x);
+
+                let push = normal * (config.distance - offset.norm()) / 2.0;
+
+                animals[a].position -= push;
+                animals[b].position += push;
+
+                bounce(&mut animals[a], -normal);
+                bounce(&mut animals[b], normal);
+            }
+
+            CollisionResponse::Penalty { energy } => {
+                for idx in [a, b] {
+                    let animal = &mut animals[idx];
+                    let cost = energy.min(animal.energy);
+
+                    animal.energy -= cost;
+                    animal.energy_spent += cost;
+                }
+            }
+        }
+    }
+}
+
+/// Reflects bird's heading off a surface facing `normal`, if it's flying
+/// into it.
+fn bounce(animal: &mut Animal, normal: na::Vector2<f32>) {
+    let heading = animal.rotation * na::Vector2::y();
+    let dot = heading.dot(&normal);
+
+    if dot < 0.0 {
+        let heading = heading - 2.0 * dot * normal;
+        animal.rotation = na::Rotation2::new(f32::atan2(-heading.x, heading.y));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::rngs::mock::StepRng;
+
+    fn animals() -> Vec<Animal> {
+        // Two birds flying head-on into each other, plus one far away
+        [(0.5, 0.5, 0.0), (0.5, 0.506, PI), (0.9, 0.9, 0.0)]
+            .into_iter()
+            .map(|(x, y, rotation)| Animal {
+                position: na::Point2::new(x, y),
+                rotation: na::Rotation2::new(rotation),
+                energy: 1.0,
+                ..Animal::random(&Config::default(), &mut StepRng::new(0, 1))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn bounce() {
+        let mut animals = animals();
+        collide(&Collisions::default(), &mut animals);
+
+        assert_relative_eq!(animals[0].position, na::Point2::new(0.5, 0.498));
+        assert_relative_eq!(animals[1].position, na::Point2::new(0.5, 0.508));
+
+        // ... and turned back
+        assert_relative_eq!(animals[0].rotation.angle().abs(), PI);
+        assert_relative_eq!(animals[1].rotation.angle(), 0.0, epsilon = 1e-6);
+        assert_relative_eq!(animals[2].position, na::Point2::new(0.9, 0.9));
+    }
+
+    #[test]
+    fn penalty() {
+        let config = Collisions {
+            response: CollisionResponse::Penalty { energy: 0.25 },
+            ..Collisions::default()
+        };
+
+        let mut animals = animals();
+        collide(&config, &mut animals);
+
+        let energies: Vec<_> = animals.iter().map(|animal| animal.energy).collect();
+        assert_eq!(energies, [0.75, 0.75, 1.0]);
+    }
+}
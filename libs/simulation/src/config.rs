@@ -0,0 +1,493 @@
+use crate::*;
+use std::f32::consts::*;
+use std::fmt;
+
+/// Knobs of a simulation; `Config::default()` describes the classic
+/// setup of 40 birds chasing 40 foods.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// Number of birds (per species)
+    pub population: usize,
+
+    /// Number of bird populations evolving independently of each other
+    /// (while competing for the same food)
+    pub species: usize,
+
+    /// Scripted birds mixed into the world; with `population` of zero,
+    /// the world is inhabited by baselines only - e.g. to benchmark them
+    /// on their own
+    pub baselines: Vec<Baseline>,
+
+    /// Number of foods
+    pub foods: usize,
+
+    /// Kinds of foods lying around, each seen through a separate channel;
+    /// has to contain at least one kind
+    pub food_kinds: Vec<FoodKind>,
+
+    /// Where eaten foods reappear
+    pub food_spawn: FoodSpawn,
+
+    /// Lets dead birds become food; `None` makes them simply disappear
+    pub corpses: Option<Corpses>,
+
+    /// How birds produce offspring
+    pub reproduction: Reproduction,
+
+    /// Number of steps after which the population gets evolved (ignored
+    /// for `Reproduction::Continuous`)
+    pub generation_length: usize,
+
+    /// Lets generations end before (or after) `generation_length` steps
+    pub generation_end: GenerationEnd,
+
+    /// Changes of difficulty applied as generations go by (ignored for
+    /// `Reproduction::Continuous`); `Simulation::config()` returns the
+    /// config with the stages reached so far applied
+    pub curriculum: Vec<Stage>,
+
+    /// Number of steps after which birds retire (i.e. die) even if the
+    /// generation hasn't ended yet; `None` lets them live 'till its end
+    pub lifespan: Option<usize>,
+
+    /// Probability of changing each gene during mutation
+    pub mutation_chance: f32,
+
+    /// Magnitude of changes applied to mutated genes
+    pub mutation_coeff: f32,
+
+    /// How parents get picked (ignored for `Reproduction::Continuous`)
+    pub selection: Selection,
+
+    /// How parents' genes get mixed (ignored for
+    /// `Reproduction::Continuous`)
+    pub crossover: Crossover,
+
+    /// How far birds see; see `Eye`
+    pub eye_fov_range: f32,
+
+    /// How wide (in radians) birds see
+    pub eye_fov_angle: f32,
+
+    /// Number of photoreceptors each eye has - i.e. the vision's
+    /// resolution
+    pub eye_cells: usize,
+
+    /// Brain inputs besides vision
+    pub senses: Senses,
+
+    /// Physical parameters evolved alongside brains
+    pub traits: Traits,
+
+    pub speed_min: f32,
+    pub speed_max: f32,
+
+    /// How much the speed can change during a single step
+    pub speed_accel: f32,
+
+    /// How much (in radians) the rotation can change during a single step
+    pub rotation_accel: f32,
+
+    /// Number of pieces each step's movement gets split into, with
+    /// collisions checked in between - so that fast birds don't fly
+    /// through foods
+    pub substeps: usize,
+
+    /// Energy model; `None` gives birds unlimited energy
+    pub energy: Option<EnergyConfig>,
+
+    /// Predators hunting birds; `None` disables them
+    pub predators: Option<PredatorConfig>,
+
+    /// Scent field birds can smell (see `Senses::scent`); `None` disables
+    /// it
+    pub scent: Option<ScentConfig>,
+
+    /// How far birds hear each other (and predators); used only with
+    /// `Senses::hearing`
+    pub hearing: HearingConfig,
+
+    /// Daylight cycle limiting how far birds see; `None` keeps it always
+    /// bright
+    pub day_night: Option<DayNight>,
+
+    /// Air flow pushing flying birds around
+    pub wind: Wind,
+
+    /// Slows birds down (or speeds them up) depending on where they fly;
+    /// `None` makes it the same everywhere
+    pub terrain: Option<Terrain>,
+
+    /// Keeps birds from overlapping; `None` lets them fly through each
+    /// other
+    pub collisions: Option<Collisions>,
+
+    /// What happens to birds (and predators) reaching the world's edge
+    pub boundary: Boundary,
+
+    /// Obstacles placed in the world at the beginning (more can be added
+    /// later through `Simulation::add_obstacle()`)
+    pub obstacles: Vec<Obstacle>,
+
+    pub fitness: Fitness,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            population: 40,
+            species: 1,
+            baselines: Vec::new(),
+            foods: 40,
+            food_kinds: vec![FoodKind::default()],
+            food_spawn: FoodSpawn::default(),
+            corpses: None,
+            reproduction: Reproduction::default(),
+            generation_length: 2500,
+            generation_end: GenerationEnd::default(),
+            curriculum: Vec::new(),
+            lifespan: None,
+            mutation_chance: 0.1,
+            mutation_coeff: 0.3,
+            selection: Selection::default(),
+            crossover: Crossover::default(),
+            eye_fov_range: 0.25,
+            eye_fov_angle: PI + FRAC_PI_4,
+            eye_cells: 9,
+            senses: Senses::default(),
+            traits: Traits::default(),
+            speed_min: 0.001,
+            speed_max: 0.005,
+            speed_accel: 0.2,
+            rotation_accel: FRAC_PI_2,
+            substeps: 1,
+            energy: None,
+            predators: None,
+            scent: None,
+            hearing: HearingConfig::default(),
+            day_night: None,
+            wind: Wind::default(),
+            terrain: None,
+            collisions: None,
+            boundary: Boundary::default(),
+            obstacles: Vec::new(),
+            fitness: Fitness::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Returns what birds' eyes can see - i.e. what their brains get as
+    /// inputs.
+    pub fn channels(&self) -> Vec<Channel> {
+        let mut channels: Vec<_> = (0..self.food_kinds.len()).map(Channel::Food).collect();
+
+        if self.predators.is_some() {
+            channels.push(Channel::Predators);
+        }
+
+        channels
+    }
+
+    /// Checks whether a simulation can run with this config (while
+    /// `Simulation::new()` and its steps panic otherwise) - e.g. for
+    /// configs coming from users.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let check = |valid: bool, field, requirement| {
+            if valid {
+                Ok(())
+            } else {
+                Err(ConfigError { field, requirement })
+            }
+        };
+
+        check(self.species > 0, "species", "has to be positive")?;
+
+        check(
+            self.population > 0 || !self.baselines.is_empty(),
+            "population",
+            "has to be positive (unless there are baselines)",
+        )?;
+
+        check(
+            self.baselines.iter().all(|baseline| baseline.count > 0),
+            "baselines",
+            "have to have positive counts",
+        )?;
+
+        check(!self.food_kinds.is_empty(), "food_kinds", "can't be empty")?;
+
+        check(
+            self.food_kinds.iter().all(|kind| non_negative(kind.weight))
+                && self.food_kinds.iter().map(|kind| kind.weight).sum::<f32>() > 0.0,
+            "food_kinds",
+            "need non-negative weights summing up to a positive number",
+        )?;
+
+        check(
+            (0.0..=1.0).contains(&self.mutation_chance),
+            "mutation_chance",
+            "has to be between zero and one",
+        )?;
+
+        if let Selection::Tournament { size } = self.selection {
+            check(size > 0, "selection", "needs a positive tournament size")?;
+        }
+
+        check(
+            self.eye_fov_range > 0.0,
+            "eye_fov_range",
+            "has to be positive",
+        )?;
+        check(
+            self.eye_fov_angle > 0.0,
+            "eye_fov_angle",
+            "has to be positive",
+        )?;
+        check(self.eye_cells > 0, "eye_cells", "has to be positive")?;
+        check(self.substeps > 0, "substeps", "has to be positive")?;
+
+        check(
+            self.speed_min.is_finite() && self.speed_max >= self.speed_min,
+            "speed_max",
+            "can't be below the minimum speed",
+        )?;
+        check(
+            non_negative(self.speed_accel),
+            "speed_accel",
+            "has to be non-negative",
+        )?;
+        check(
+            non_negative(self.rotation_accel),
+            "rotation_accel",
+            "has to be non-negative",
+        )?;
+
+        check(
+            self.traits
+                .rotation_accel
+                .is_none_or(|bounds| bounds.min() >= 0.0),
+            "traits",
+            "can't let rotation_accel evolve below zero",
+        )?;
+
+        check(
+            [self.traits.eye_fov_range, self.traits.eye_fov_angle]
+                .iter()
+                .flatten()
+                .all(|bounds| bounds.min() > 0.0),
+            "traits",
+            "have to keep eye_fov_range and eye_fov_angle positive",
+        )?;
+
+        check(
+            self.curriculum.iter().all(|stage| stage.generation > 0),
+            "curriculum",
+            "can't start at generation zero",
+        )?;
+
+        check(
+            self.curriculum.iter().all(|stage| {
+                stage
+                    .speed_max
+                    .is_none_or(|speed_max| speed_max >= self.speed_min)
+            }),
+            "curriculum",
+            "can't slow birds down below the minimum speed",
+        )?;
+
+        if let Some(corpses) = &self.corpses {
+            check(
+                corpses.kind < self.food_kinds.len(),
+                "corpses",
+                "has to be one of the food kinds",
+            )?;
+        }
+
+        if let Some(energy) = &self.energy {
+            check(
+                non_negative(energy.max),
+                "energy",
+                "needs a non-negative max",
+            )?;
+        }
+
+        if let Some(predators) = &self.predators {
+            check(
+                non_negative(predators.rotation_accel),
+                "predators",
+                "need a non-negative rotation_accel",
+            )?;
+        }
+
+        if let Some(day_night) = &self.day_night {
+            check(day_night.period > 0, "day_night", "needs a positive period")?;
+
+            check(
+                day_night.night_vision > 0.0,
+                "day_night",
+                "needs a positive night vision",
+            )?;
+        }
+
+        if let Some(scent) = &self.scent {
+            check(scent.resolution > 0, "scent", "needs a positive resolution")?;
+        }
+
+        if let Reproduction::Continuous { .. } = self.reproduction {
+            check(
+                self.energy.is_some(),
+                "reproduction",
+                "has to be generational without energy",
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Whether `value` works as a limit (e.g. of a `clamp()`) - i.e. it's
+/// neither negative, NaN nor infinite.
+fn non_negative(value: f32) -> bool {
+    value.is_finite() && value >= 0.0
+}
+
+/// Reason why a `Config` is invalid, see `Config::validate()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigError {
+    /// Name of the offending field, e.g. `eye_cells`
+    pub field: &'static str,
+
+    /// What's required of the field, e.g. `has to be positive`
+    pub requirement: &'static str,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` {}", self.field, self.requirement)
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Reproduction {
+    /// Entire population gets evolved at once, every
+    /// `Config::generation_length` steps (see also `Config::generation_end`)
+    #[default]
+    Generational,
+
+    /// Birds reproduce inside the running world: whenever a bird has at
+    /// least `threshold` energy, it hands `cost` of it to an offspring
+    /// (mutated copy of itself) - as long as there are fewer than
+    /// `max_population` birds around.
+    ///
+    /// Birds that run out of energy die (regardless of
+    /// `EnergyConfig::on_empty`) and are removed from the world; requires
+    /// `Config::energy`.
+    Continuous {
+        threshold: f32,
+        cost: f32,
+        max_population: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FoodKind {
+    /// Added to the satiation of birds eating this food (and, multiplied
+    /// by `EnergyConfig::per_food`, to their energy); negative for poison
+    pub value: f32,
+
+    /// Relative chance of a (re)spawned food being of this kind
+    pub weight: f32,
+}
+
+impl Default for FoodKind {
+    fn default() -> Self {
+        Self {
+            value: 1.0,
+            weight: 1.0,
+        }
+    }
+}
+
+/// Energy budget of birds: moving and turning drains it, eating refills
+/// it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnergyConfig {
+    /// Energy each bird starts with
+    pub initial: f32,
+
+    /// Energy above which eating doesn't help anymore
+    pub max: f32,
+
+    /// Energy gained by eating a single food (of value 1.0)
+    pub per_food: f32,
+
+    /// Energy spent each step just for being alive
+    pub idle_cost: f32,
+
+    /// Energy spent per unit of distance travelled
+    pub move_cost: f32,
+
+    /// Energy spent per radian turned
+    pub turn_cost: f32,
+
+    /// What happens to birds that have run out of energy
+    pub on_empty: OnEmpty,
+}
+
+impl Default for EnergyConfig {
+    fn default() -> Self {
+        Self {
+            initial: 1.0,
+            max: 2.0,
+            per_food: 0.5,
+            idle_cost: 0.0001,
+            move_cost: 0.1,
+            turn_cost: 0.001,
+            on_empty: OnEmpty::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OnEmpty {
+    /// Bird stops moving, until it eats something (which can happen only
+    /// if food lands on it)
+    #[default]
+    Stop,
+
+    /// Bird is out of the game 'till the end of the generation
+    Die,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PredatorConfig {
+    /// Number of predators
+    pub count: usize,
+
+    /// Distance travelled by a predator during a single step
+    pub speed: f32,
+
+    /// How much (in radians) a predator can turn during a single step
+    pub rotation_accel: f32,
+
+    /// How far predators notice birds
+    pub sight: f32,
+
+    /// Distance at which a predator catches a bird (which then dies)
+    pub catch_distance: f32,
+}
+
+impl Default for PredatorConfig {
+    fn default() -> Self {
+        Self {
+            count: 2,
+            speed: 0.003,
+            rotation_accel: FRAC_PI_8,
+            sight: 0.3,
+            catch_distance: 0.02,
+        }
+    }
+}
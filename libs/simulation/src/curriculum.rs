@@ -0,0 +1,117 @@
+use crate::*;
+
+/// Change of difficulty, applied to the config once the simulation reaches
+/// given generation (see `Config::curriculum`).
+///
+/// Fields left as `None` stay as they were. The world always spans the unit
+/// square - to make it effectively larger, shorten the birds' sight and
+/// slow them down.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stage {
+    /// Generation the stage starts at; has to be at least one, since
+    /// generation zero is what the config itself describes
+    pub generation: usize,
+
+    /// Number of foods
+    pub foods: Option<usize>,
+
+    /// Number of predators (used only if they're enabled)
+    pub predators: Option<usize>,
+
+    /// Predators' speed (used only if they're enabled)
+    pub predator_speed: Option<f32>,
+
+    pub eye_fov_range: Option<f32>,
+    pub speed_max: Option<f32>,
+    pub generation_length: Option<usize>,
+}
+
+impl Stage {
+    pub(crate) fn apply(&self, config: &mut Config) {
+        if let Some(foods) = self.foods {
+            config.foods = foods;
+        }
+
+        if let Some(predators) = &mut config.predators {
+            if let Some(count) = self.predators {
+                predators.count = count;
+            }
+
+            if let Some(speed) = self.predator_speed {
+                predators.speed = speed;
+            }
+        }
+
+        if let Some(eye_fov_range) = self.eye_fov_range {
+            config.eye_fov_range = eye_fov_range;
+        }
+
+        if let Some(speed_max) = self.speed_max {
+            config.speed_max = speed_max;
+        }
+
+        if let Some(generation_length) = self.generation_length {
+            config.generation_length = generation_length;
+        }
+    }
+}
+
+/// Applies all stages starting at `generation` to `config`, returning
+/// whether there were any.
+pub(crate) fn advance_curriculum(config: &mut Config, generation: usize) -> bool {
+    let stages: Vec<_> = config
+        .curriculum
+        .iter()
+        .filter(|stage| stage.generation == generation)
+        .copied()
+        .collect();
+
+    for stage in &stages {
+        stage.apply(config);
+    }
+
+    !stages.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn advance() {
+        let mut config = Config {
+            predators: Some(PredatorConfig::default()),
+            curriculum: vec![
+                Stage {
+                    generation: 2,
+                    foods: Some(20),
+                    predator_speed: Some(0.004),
+                    ..Stage::default()
+                },
+                Stage {
+                    generation: 2,
+                    foods: Some(10),
+                    ..Stage::default()
+                },
+                Stage {
+                    generation: 3,
+                    eye_fov_range: Some(0.2),
+                    ..Stage::default()
+                },
+            ],
+            ..Config::default()
+        };
+
+        assert!(!advance_curriculum(&mut config, 1));
+        assert_eq!(config.foods, 40);
+
+        // Later stages win
+        assert!(advance_curriculum(&mut config, 2));
+        assert_eq!(config.foods, 10);
+        assert_eq!(config.predators.as_ref().unwrap().speed, 0.004);
+        assert_eq!(config.eye_fov_range, 0.25);
+
+        assert!(advance_curriculum(&mut config, 3));
+        assert_eq!(config.eye_fov_range, 0.2);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::*;
+
+/// Daylight changing over time; the darker it is, the less far birds see.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DayNight {
+    /// Length of a full day, in steps (starting at noon)
+    pub period: usize,
+
+    /// Fraction of the eyes' range birds still see at midnight
+    pub night_vision: f32,
+}
+
+impl Default for DayNight {
+    fn default() -> Self {
+        Self {
+            period: 1000,
+            night_vision: 0.3,
+        }
+    }
+}
+
+impl DayNight {
+    /// Returns daylight at given step (counted since the simulation has
+    /// started), from zero (midnight) to one (noon).
+    pub fn light(&self, step: usize) -> f32 {
+        let phase = (step % self.period) as f32 / self.period as f32;
+
+        (1.0 + (TAU * phase).cos()) / 2.0
+    }
+
+    /// Returns fraction of the eyes' range visible at given step.
+    pub(crate) fn vision(&self, step: usize) -> f32 {
+        self.night_vision + (1.0 - self.night_vision) * self.light(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn light() {
+        let day_night = DayNight {
+            period: 100,
+            night_vision: 0.2,
+        };
+
+        assert_relative_eq!(day_night.light(0), 1.0);
+        assert_relative_eq!(day_night.light(25), 0.5);
+        assert_relative_eq!(day_night.light(50), 0.0);
+        assert_relative_eq!(day_night.light(100), 1.0);
+
+        assert_relative_eq!(day_night.vision(0), 1.0);
+        assert_relative_eq!(day_night.vision(50), 0.2);
+    }
+}
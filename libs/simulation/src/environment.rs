@@ -0,0 +1,384 @@
+use crate::*;
+
+/// Task agents can be evolved to solve - e.g. a maze, a balanced pole or a
+/// race track - driven by `Evolution`, which provides the brains; the birds'
+/// own world is available as `BirdWorld`.
+///
+/// Each agent gets its own brain; actions and observations are given in
+/// the same order as the agents.
+pub trait Environment {
+    /// Number of agents taking part in each episode - i.e. the size of the
+    /// population
+    fn agents(&self) -> usize;
+
+    /// Number of inputs each agent gets per step
+    fn observation_size(&self) -> usize;
+
+    /// Number of outputs each agent produces per step
+    fn action_size(&self) -> usize;
+
+    /// Starts a new episode, returning the first observations.
+    fn reset(&mut self, rng: &mut dyn RngCore) -> Vec<Vec<f32>>;
+
+    fn step(&mut self, actions: &[Vec<f32>], rng: &mut dyn RngCore) -> Transition;
+
+    /// Describes the agents' brains; by default it's a single hidden layer
+    /// with outputs within `-1.0..=1.0`.
+    fn brain(&self) -> nn::NetworkBuilder {
+        nn::Network::builder()
+            .input(self.observation_size())
+            .hidden(2 * self.observation_size(), nn::Activation::Relu)
+            .output(self.action_size(), nn::Activation::Tanh)
+    }
+
+    /// Number of genes each agent has.
+    fn genome_len(&self) -> usize {
+        nn::Network::weight_count(&self.brain().topology())
+    }
+}
+
+/// Outcome of a single `Environment::step()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Transition {
+    pub observations: Vec<Vec<f32>>,
+
+    /// Added up over the episode, they become the agents' fitness; single
+    /// rewards can be negative, but their totals can't (that's what
+    /// roulette-wheel selection requires)
+    pub rewards: Vec<f32>,
+
+    /// Whether the episode has ended
+    pub done: bool,
+}
+
+/// Evolves brains of an `Environment`'s agents, one episode per generation.
+pub struct Evolution<E> {
+    env: E,
+    ga: GeneticAlgorithm,
+    brains: Vec<nn::Network>,
+
+    /// Genes of the last generation, which the next one gets built in
+    recycled: Vec<ga::Chromosome>,
+
+    generation: usize,
+    rng: ChaCha8Rng,
+}
+
+impl<E> Evolution<E>
+where
+    E: Environment,
+{
+    /// Mutates genes the same way `Simulation` with `Config::default()`
+    /// does.
+    pub fn new(env: E, seed: u64) -> Self {
+        assert!(env.agents() > 0);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let builder = env.brain();
+        let brains = (0..env.agents()).map(|_| builder.build(&mut rng)).collect();
+        let ga = genetic_algorithm(&Config::default());
+
+        Self {
+            env,
+            ga,
+            brains,
+            recycled: Vec::new(),
+            generation: 0,
+            rng,
+        }
+    }
+
+    pub fn env(&self) -> &E {
+        &self.env
+    }
+
+    /// Number of generations evolved so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Runs a single episode and evolves the agents based on their rewards.
+    ///
+    /// Panics if any agent's total reward is negative.
+    pub fn train(&mut self) -> ga::Statistics {
+        let mut observations = self.env.reset(&mut self.rng);
+        let mut fitness = vec![0.0; self.brains.len()];
+
+        loop {
+            let actions: Vec<_> = self
+                .brains
+                .iter()
+                .zip(&observations)
+                .map(|(brain, observation)| {
+                    let inputs = observation.iter().map(|&input| input as nn::Float);
+
+                    // No-op for the default precision
+                    #[allow(clippy::unnecessary_cast)]
+                    brain
+                        .propagate(inputs.collect())
+                        .into_iter()
+                        .map(|output| output as f32)
+                        .collect()
+                })
+                .collect();
+
+            let transition = self.env.step(&actions, &mut self.rng);
+
+            for (fitness, reward) in fitness.iter_mut().zip(&transition.rewards) {
+                *fitness += reward;
+            }
+
+            if transition.done {
+                break;
+            }
+
+            observations = transition.observations;
+        }
+
+        let population: Vec<_> = self
+            .brains
+            .iter()
+            .zip(fitness)
+            .map(|(brain, fitness)| {
+                assert!(fitness >= 0.0, "total rewards can't be negative");
+
+                AnimalIndividual::new(
+                    fitness as ga::Float,
+                    brain.weights().map(|weight| weight as ga::Float).collect(),
+                )
+            })
+            .collect();
+
+        let (evolved, stats) = self
+            .ga
+            .evolve_into(&mut self.rng, &population, &mut self.recycled);
+
+        self.recycled
+            .extend(population.into_iter().map(Individual::into_chromosome));
+
+        let topology = self.env.brain().topology();
+
+        self.brains = evolved
+            .into_iter()
+            .map(|individual| {
+                let weights = individual.into_chromosome().into_iter();
+                nn::Network::from_weights(&topology, weights.map(|gene| gene as nn::Float))
+            })
+            .collect();
+
+        self.generation += 1;
+
+        stats
+    }
+}
+
+/// Birds' world described by a `Config`, as an `Environment` - one episode
+/// is one generation, and agents are the brain-driven birds.
+///
+/// Agents get rewarded for whatever increases their `Config::fitness`, so
+/// that the total reward is their fitness at the end of the generation;
+/// physical traits and the curriculum aren't used in here, since it's
+/// `Evolution` that does the evolving.
+pub struct BirdWorld {
+    sim: Simulation,
+
+    /// Agents' fitness as of the last step
+    scores: Vec<f32>,
+}
+
+impl BirdWorld {
+    pub fn new(config: Config) -> Self {
+        assert_eq!(
+            config.reproduction,
+            Reproduction::Generational,
+            "episodes require generations"
+        );
+
+        let sim = Simulation::new(config, 0);
+        let scores = sim.scores();
+
+        Self { sim, scores }
+    }
+
+    /// Returns the current episode, e.g. to render it.
+    pub fn simulation(&self) -> &Simulation {
+        &self.sim
+    }
+}
+
+impl Environment for BirdWorld {
+    fn agents(&self) -> usize {
+        self.scores.len()
+    }
+
+    fn observation_size(&self) -> usize {
+        self.brain().topology()[0].neurons
+    }
+
+    /// Change in speed and rotation, like the birds' own brains produce.
+    fn action_size(&self) -> usize {
+        2
+    }
+
+    fn reset(&mut self, rng: &mut dyn RngCore) -> Vec<Vec<f32>> {
+        self.sim = Simulation::new(self.sim.config().clone(), rng.next_u64());
+        self.scores = self.sim.scores();
+        self.sim.observations()
+    }
+
+    fn step(&mut self, actions: &[Vec<f32>], _rng: &mut dyn RngCore) -> Transition {
+        self.sim.advance(Some(actions));
+
+        let done = self.sim.generation_ended();
+        let scores = self.sim.scores();
+
+        let rewards = scores
+            .iter()
+            .zip(&self.scores)
+            .map(|(score, prev_score)| score - prev_score)
+            .collect();
+
+        self.scores = scores;
+
+        Transition {
+            observations: self.sim.observations(),
+            rewards,
+            done,
+        }
+    }
+
+    fn brain(&self) -> nn::NetworkBuilder {
+        let config = self.sim.config();
+        let eye = Eye::new(config.eye_fov_range, config.eye_fov_angle, config.eye_cells);
+
+        Brain::builder(&eye, &config.channels(), &config.senses)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Agents moving along a line, rewarded for staying close to its middle.
+    struct Line {
+        positions: Vec<f32>,
+        steps: usize,
+    }
+
+    impl Environment for Line {
+        fn agents(&self) -> usize {
+            self.positions.len()
+        }
+
+        fn observation_size(&self) -> usize {
+            1
+        }
+
+        fn action_size(&self) -> usize {
+            1
+        }
+
+        fn reset(&mut self, rng: &mut dyn RngCore) -> Vec<Vec<f32>> {
+            self.steps = 0;
+
+            for position in &mut self.positions {
+                *position = rng.gen();
+            }
+
+            self.positions
+                .iter()
+                .map(|&position| vec![position])
+                .collect()
+        }
+
+        fn step(&mut self, actions: &[Vec<f32>], rng: &mut dyn RngCore) -> Transition {
+            self.steps += 1;
+
+            for (position, action) in self.positions.iter_mut().zip(actions) {
+                *position = (*position + 0.1 * action[0]).clamp(0.0, 1.0);
+            }
+
+            Transition {
+                observations: self
+                    .positions
+                    .iter()
+                    .map(|&position| vec![position])
+                    .collect(),
+                rewards: self
+                    .positions
+                    .iter()
+                    .map(|position| 1.0 - (position - 0.5).abs())
+                    .collect(),
+                done: self.steps == 10,
+            }
+        }
+    }
+
+    #[test]
+    fn train() {
+        let env = Line {
+            positions: vec![0.0; 8],
+            steps: 0,
+        };
+
+        assert_eq!(env.genome_len(), 7);
+
+        let mut evolution = Evolution::new(env, 0);
+        let stats = evolution.train();
+
+        assert_eq!(evolution.generation(), 1);
+        assert_eq!(evolution.env().steps, 10);
+        assert!(stats.max_fitness > 0.0 && stats.max_fitness <= 10.0);
+    }
+
+    #[test]
+    fn bird_world() {
+        let config = Config {
+            population: 4,
+            foods: 40,
+            generation_length: 30,
+            ..Config::default()
+        };
+
+        let mut env = BirdWorld::new(config);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+
+        assert_eq!(env.agents(), 4);
+        assert_eq!(env.reset(&mut rng).len(), 4);
+        assert!(env
+            .reset(&mut rng)
+            .iter()
+            .all(|observation| observation.len() == env.observation_size()));
+
+        let mut totals = vec![0.0; 4];
+
+        loop {
+            let transition = env.step(&vec![vec![1.0, 0.1]; 4], &mut rng);
+
+            for (total, reward) in totals.iter_mut().zip(transition.rewards) {
+                *total += reward;
+            }
+
+            if transition.done {
+                break;
+            }
+        }
+
+        let satiations: Vec<_> = env
+            .simulation()
+            .world()
+            .animals()
+            .iter()
+            .map(|animal| animal.satiation)
+            .collect();
+
+        approx::assert_relative_eq!(totals.as_slice(), satiations.as_slice());
+        assert_eq!(env.simulation().progress().age, 30);
+
+        let mut evolution = Evolution::new(env, 0);
+        let stats = evolution.train();
+
+        assert_eq!(evolution.generation(), 1);
+        assert!(stats.max_fitness >= stats.avg_fitness);
+    }
+}
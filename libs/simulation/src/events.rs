@@ -0,0 +1,47 @@
+use crate::*;
+
+/// Something noteworthy that has happened during a step.
+///
+/// Birds and foods are identified by their indices into `World::animals()`
+/// and `World::foods()` as of the moment the event has happened; birds
+/// also by their ids, which stay valid afterwards.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    FoodEaten {
+        animal: usize,
+        id: AnimalId,
+        food: usize,
+    },
+    AnimalDied {
+        animal: usize,
+        id: AnimalId,
+        cause: DeathCause,
+    },
+
+    /// Emitted only for `Reproduction::Continuous`
+    AnimalBorn {
+        animal: usize,
+        id: AnimalId,
+        parent: usize,
+        parent_id: AnimalId,
+    },
+
+    GenerationEnded {
+        stats: Statistics,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DeathCause {
+    /// Ran out of energy
+    Starved,
+
+    /// Caught by a predator
+    Caught,
+
+    /// Lived past `Config::lifespan`
+    Retired,
+}
+
+/// Callback notified about each `Event`, see `Simulation::subscribe()`.
+pub type Observer = Box<dyn FnMut(&Event) + Send>;
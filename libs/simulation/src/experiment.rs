@@ -0,0 +1,239 @@
+use crate::*;
+
+/// Batch of trainings sharing the same config, one per seed - e.g. to tell
+/// how a config does on average, rather than how lucky a single seed was.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Experiment {
+    /// Has to use `Reproduction::Generational`
+    pub config: Config,
+
+    pub seeds: Vec<u64>,
+    pub termination: Termination,
+}
+
+/// Tells when a training is over.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Termination {
+    /// After given number of generations
+    Generations(usize),
+
+    /// Once a generation reaches `max_fitness` of at least `target` - or
+    /// after `max_generations`, whichever comes first
+    Fitness { target: f32, max_generations: usize },
+
+    /// Once the best fitness so far hasn't improved for `patience`
+    /// generations - or after `max_generations`, whichever comes first
+    Plateau {
+        patience: usize,
+        max_generations: usize,
+    },
+}
+
+impl Termination {
+    fn done(&self, history: &[Statistics]) -> bool {
+        match *self {
+            Termination::Generations(generations) => history.len() >= generations,
+
+            Termination::Fitness {
+                target,
+                max_generations,
+            } => {
+                history.len() >= max_generations
+                    || history
+                        .last()
+                        .is_some_and(|stats| stats.max_fitness >= target)
+            }
+
+            Termination::Plateau {
+                patience,
+                max_generations,
+            } => {
+                let best_at = history
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .max_by(|(_, a), (_, b)| a.max_fitness.total_cmp(&b.max_fitness))
+                    .map_or(0, |(idx, _)| idx + 1);
+
+                history.len() >= max_generations || history.len() - best_at >= patience
+            }
+        }
+    }
+}
+
+/// Outcome of training a single seed.
+#[derive(Clone, Debug)]
+pub struct Run {
+    pub seed: u64,
+
+    /// Statistics of all the generations, oldest first
+    pub history: Vec<Statistics>,
+
+    /// Genes of the best bird of the last generation
+    pub champion: Option<ga::Chromosome>,
+}
+
+/// Outcome of an `Experiment`, with runs in the order of its seeds.
+#[derive(Clone, Debug, Default)]
+pub struct Results {
+    pub runs: Vec<Run>,
+}
+
+impl Experiment {
+    /// Trains all the seeds - on native targets each on its own thread.
+    pub fn run(&self) -> Results {
+        #[cfg(not(target_arch = "wasm32"))]
+        let runs = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .seeds
+                .iter()
+                .map(|&seed| scope.spawn(move || self.run_seed(seed)))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        #[cfg(target_arch = "wasm32")]
+        let runs = self.seeds.iter().map(|&seed| self.run_seed(seed)).collect();
+
+        Results { runs }
+    }
+
+    /// Trains a single seed (which doesn't have to be among `seeds`).
+    pub fn run_seed(&self, seed: u64) -> Run {
+        assert_eq!(
+            self.config.reproduction,
+            Reproduction::Generational,
+            "experiments require generations"
+        );
+
+        let mut sim = Simulation::new(self.config.clone(), seed);
+
+        while !self.termination.done(sim.history()) {
+            sim.train();
+        }
+
+        Run {
+            seed,
+            history: sim.history().to_vec(),
+            champion: sim.champion().cloned(),
+        }
+    }
+}
+
+impl Results {
+    /// Returns `metric` of each run's last generation.
+    pub fn finals(&self, metric: impl Fn(&Statistics) -> f32) -> Vec<f32> {
+        self.runs
+            .iter()
+            .filter_map(|run| run.history.last())
+            .map(metric)
+            .collect()
+    }
+
+    /// Returns `metric` averaged over the runs, generation by generation;
+    /// once the shorter runs end, only the longer ones are accounted for.
+    pub fn mean_history(&self, metric: impl Fn(&Statistics) -> f32) -> Vec<f32> {
+        let generations = self
+            .runs
+            .iter()
+            .map(|run| run.history.len())
+            .max()
+            .unwrap_or(0);
+
+        (0..generations)
+            .map(|generation| {
+                let values: Vec<_> = self
+                    .runs
+                    .iter()
+                    .filter_map(|run| run.history.get(generation))
+                    .map(&metric)
+                    .collect();
+
+                values.iter().sum::<f32>() / values.len() as f32
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn genes(chromosome: &ga::Chromosome) -> Vec<ga::Float> {
+        chromosome.iter().copied().collect()
+    }
+
+    fn experiment(termination: Termination) -> Experiment {
+        Experiment {
+            config: Config {
+                population: 4,
+                generation_length: 10,
+                ..Config::default()
+            },
+            seeds: vec![0, 1],
+            termination,
+        }
+    }
+
+    #[test]
+    fn run() {
+        let experiment = experiment(Termination::Generations(3));
+        let results = experiment.run();
+
+        assert_eq!(results.runs.len(), 2);
+
+        for (run, seed) in results.runs.iter().zip([0, 1]) {
+            let mut sim = Simulation::new(experiment.config.clone(), seed);
+
+            assert_eq!(run.seed, seed);
+            assert_eq!(run.history, sim.train_generations(3));
+            assert_eq!(run.champion.as_ref().map(genes), sim.champion().map(genes));
+        }
+
+        let finals = results.finals(|stats| stats.max_fitness);
+
+        assert_eq!(finals.len(), 2);
+        assert_eq!(
+            results.mean_history(|stats| stats.max_fitness)[2],
+            (finals[0] + finals[1]) / 2.0
+        );
+    }
+
+    #[test]
+    fn termination() {
+        let stats = |max_fitness| Statistics {
+            generation: 0,
+            min_fitness: 0.0,
+            max_fitness,
+            avg_fitness: 0.0,
+            median_fitness: 0.0,
+            avg_age: 0.0,
+            behavior: Behavior::default(),
+        };
+
+        let history = [stats(1.0), stats(3.0), stats(2.0), stats(3.0)];
+
+        let fitness = Termination::Fitness {
+            target: 3.0,
+            max_generations: 10,
+        };
+
+        assert!(!fitness.done(&history[..1]));
+        assert!(fitness.done(&history[..2]));
+
+        // Catching up with the best doesn't count as an improvement
+        let plateau = Termination::Plateau {
+            patience: 2,
+            max_generations: 10,
+        };
+
+        assert!(!plateau.done(&history[..3]));
+        assert!(plateau.done(&history));
+
+        assert!(Termination::Generations(4).done(&history));
+    }
+}
@@ -1,11 +1,9 @@
 use crate::*;
 use std::f32::consts::*;
 
-const FOV_RANGE: f32 = 0.25;
-const FOV_ANGLE: f32 = PI + FRAC_PI_4;
-const CELLS: usize = 9;
-
-#[derive(Debug)]
+/// Bird's vision: `cells` photoreceptors spread evenly over an arc of
+/// `fov_angle` radians, each seeing up to `fov_range` ahead.
+#[derive(Clone, Debug)]
 pub struct Eye {
     fov_range: f32,
     fov_angle: f32,
@@ -13,7 +11,7 @@ pub struct Eye {
 }
 
 impl Eye {
-    fn new(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
+    pub fn new(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
         assert!(fov_range > 0.0);
         assert!(fov_angle > 0.0);
         assert!(cells > 0);
@@ -25,10 +23,30 @@ impl Eye {
         }
     }
 
+    pub(crate) fn from_config(config: &Config) -> Self {
+        Self::new(config.eye_fov_range, config.eye_fov_angle, config.eye_cells)
+    }
+
+    pub fn fov_range(&self) -> f32 {
+        self.fov_range
+    }
+
+    pub fn fov_angle(&self) -> f32 {
+        self.fov_angle
+    }
+
     pub fn cells(&self) -> usize {
         self.cells
     }
 
+    /// Returns this eye with its range shrunk to `vision` of it.
+    pub(crate) fn dimmed(&self, vision: f32) -> Self {
+        Self {
+            fov_range: self.fov_range * vision,
+            ..self.clone()
+        }
+    }
+
     pub(crate) fn process_vision(
         &self,
         position: na::Point2<f32>,
@@ -37,8 +55,31 @@ impl Eye {
     ) -> Vec<f32> {
         let mut cells = vec![0.0; self.cells];
 
-        for food in foods {
-            let vec = food.position - position;
+        self.see(
+            position,
+            rotation,
+            foods.iter().map(|food| food.position),
+            &[],
+            &mut cells,
+        );
+
+        cells
+    }
+
+    /// Adds how well each of `objects` is visible to the photoreceptors'
+    /// `cells`; objects hidden behind any of the `obstacles` aren't seen.
+    pub(crate) fn see(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        objects: impl IntoIterator<Item = na::Point2<f32>>,
+        obstacles: &[Obstacle],
+        cells: &mut [f32],
+    ) {
+        assert_eq!(cells.len(), self.cells);
+
+        for object in objects {
+            let vec = object - position;
             let dist = vec.norm();
 
             if dist > self.fov_range {
@@ -53,20 +94,25 @@ impl Eye {
                 continue;
             }
 
+            if obstacles
+                .iter()
+                .any(|obstacle| obstacle.blocks(position, object))
+            {
+                continue;
+            }
+
             let angle = angle + self.fov_angle / 2.0;
             let cell = angle / self.fov_angle * (self.cells as f32);
             let cell = (cell as usize).min(cells.len() - 1);
 
             cells[cell] += (self.fov_range - dist) / self.fov_range;
         }
-
-        cells
     }
 }
 
 impl Default for Eye {
     fn default() -> Self {
-        Self::new(FOV_RANGE, FOV_ANGLE, CELLS)
+        Self::from_config(&Config::default())
     }
 }
 
@@ -77,9 +123,54 @@ mod tests {
 
     const TEST_EYE_CELLS: usize = 13;
 
+    #[test]
+    fn obstacles_occlude_vision() {
+        let eye = Eye::new(1.0, FRAC_PI_2, 1);
+        let position = na::Point2::new(0.5, 0.5);
+        let rotation = na::Rotation2::new(0.0);
+        let food = na::Point2::new(0.5, 0.9);
+
+        let wall = Obstacle::Rect {
+            min: na::Point2::new(0.4, 0.7),
+            max: na::Point2::new(0.6, 0.75),
+        };
+
+        let mut cells = [0.0];
+        eye.see(position, rotation, [food], &[], &mut cells);
+        assert!(cells[0] > 0.0);
+
+        let mut cells = [0.0];
+        eye.see(position, rotation, [food], &[wall], &mut cells);
+        assert_eq!(cells[0], 0.0);
+    }
+
+    #[test]
+    fn from_config() {
+        let config = Config {
+            eye_fov_range: 0.5,
+            eye_fov_angle: PI,
+            eye_cells: 5,
+            ..Config::default()
+        };
+
+        let eye = Eye::from_config(&config);
+
+        assert_eq!(eye.fov_range(), 0.5);
+        assert_eq!(eye.fov_angle(), PI);
+        assert_eq!(eye.cells(), 5);
+
+        let mut rng = rand::rngs::mock::StepRng::new(0, 1);
+        let brain = Brain::random(&mut rng, &eye, &config.channels(), &config.senses);
+
+        assert_eq!(brain.nn.input_size(), 5);
+    }
+
     fn food(x: f32, y: f32) -> Food {
         Food {
             position: na::Point2::new(x, y),
+            kind: 0,
+            available: true,
+            value: None,
         }
     }
 
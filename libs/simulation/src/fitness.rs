@@ -0,0 +1,146 @@
+use crate::*;
+
+/// Decides what the genetic algorithm rewards birds for.
+///
+/// Built-in objectives are selected through `Config::fitness`; custom ones
+/// (including closures) can be plugged in with `Simulation::set_fitness()`.
+pub trait FitnessFn: Send {
+    /// Negative scores are treated as zero, since that's what
+    /// roulette-wheel selection requires.
+    fn score(&self, animal: &AnimalSummary) -> f32;
+}
+
+impl<F> FitnessFn for F
+where
+    F: Fn(&AnimalSummary) -> f32 + Send,
+{
+    fn score(&self, animal: &AnimalSummary) -> f32 {
+        self(animal)
+    }
+}
+
+/// What a bird has achieved during its life, as seen by a `FitnessFn`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimalSummary {
+    pub species: usize,
+
+    /// Total value of foods eaten
+    pub satiation: f32,
+
+    /// Total distance travelled
+    pub distance: f32,
+
+    /// Age at which the first food has been eaten
+    pub first_food: Option<usize>,
+
+    /// Energy left (zero, unless `Config::energy` is enabled)
+    pub energy: f32,
+    pub energy_spent: f32,
+
+    /// Number of steps the bird has been alive for
+    pub age: usize,
+
+    /// Number of steps the bird was able to move (i.e. had some energy
+    /// left)
+    pub survived: usize,
+
+    pub alive: bool,
+}
+
+impl From<&Animal> for AnimalSummary {
+    fn from(animal: &Animal) -> Self {
+        Self {
+            species: animal.species,
+            satiation: animal.satiation,
+            distance: animal.distance,
+            first_food: animal.first_food,
+            energy: animal.energy,
+            energy_spent: animal.energy_spent,
+            age: animal.age,
+            survived: animal.survived,
+            alive: animal.alive,
+        }
+    }
+}
+
+/// Built-in `FitnessFn`s.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Fitness {
+    /// Total value of foods eaten
+    #[default]
+    Satiation,
+
+    /// Number of steps the bird was able to move (i.e. had some energy
+    /// left); makes sense only with `Config::energy` enabled
+    Survival,
+
+    /// Total value of foods eaten per the energy spent to find them
+    Efficiency,
+
+    /// Total distance travelled
+    Distance,
+
+    /// Total value of foods eaten per the distance travelled to find them
+    FoodPerDistance,
+
+    /// How early the first food has been found - i.e. number of steps the
+    /// bird has lived for since then (zero if it's never eaten anything)
+    FirstFood,
+}
+
+impl FitnessFn for Fitness {
+    fn score(&self, animal: &AnimalSummary) -> f32 {
+        match self {
+            Fitness::Satiation => animal.satiation,
+            Fitness::Survival => animal.survived as f32,
+            Fitness::Efficiency => animal.satiation / (1.0 + animal.energy_spent),
+            Fitness::Distance => animal.distance,
+            Fitness::FoodPerDistance => animal.satiation / (1.0 + animal.distance),
+            Fitness::FirstFood => animal
+                .first_food
+                .map_or(0.0, |first_food| (animal.age - first_food) as f32),
+        }
+    }
+}
+
+/// Scores `animal` the way the genetic algorithm sees it - i.e. never
+/// below zero (even if the bird has eaten mostly poison).
+pub(crate) fn score(fitness: &dyn FitnessFn, animal: &Animal) -> f32 {
+    fitness.score(&AnimalSummary::from(animal)).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn builtins() {
+        let mut animal = Animal::random(&Config::default(), &mut StepRng::new(0, 1));
+
+        animal.satiation = 3.0;
+        animal.distance = 0.5;
+        animal.energy_spent = 2.0;
+        animal.survived = 10;
+        animal.age = 12;
+        animal.first_food = Some(4);
+
+        assert_eq!(score(&Fitness::Satiation, &animal), 3.0);
+        assert_eq!(score(&Fitness::Survival, &animal), 10.0);
+        assert_eq!(score(&Fitness::Efficiency, &animal), 1.0);
+        assert_eq!(score(&Fitness::Distance, &animal), 0.5);
+        assert_eq!(score(&Fitness::FoodPerDistance, &animal), 2.0);
+        assert_eq!(score(&Fitness::FirstFood, &animal), 8.0);
+    }
+
+    #[test]
+    fn closure() {
+        let mut animal = Animal::random(&Config::default(), &mut StepRng::new(0, 1));
+        animal.satiation = -2.0;
+
+        let fitness = |animal: &AnimalSummary| animal.satiation * 2.0;
+
+        assert_eq!(fitness.score(&AnimalSummary::from(&animal)), -4.0);
+        assert_eq!(score(&fitness, &animal), 0.0);
+    }
+}
@@ -3,16 +3,108 @@ use crate::*;
 #[derive(Debug)]
 pub struct Food {
     pub(crate) position: na::Point2<f32>,
+
+    /// Index into `Config::food_kinds`
+    pub(crate) kind: usize,
+
+    /// Eaten foods stay unavailable until `FoodSpawner` lets them
+    /// reappear.
+    pub(crate) available: bool,
+
+    /// Overrides the kind's value; set for corpses, which (unlike other
+    /// foods) disappear for good once eaten
+    pub(crate) value: Option<f32>,
 }
 
 impl Food {
     pub fn random(rng: &mut dyn RngCore) -> Self {
         Self {
             position: rng.gen(),
+            kind: 0,
+            available: true,
+            value: None,
         }
     }
 
     pub fn position(&self) -> na::Point2<f32> {
         self.position
     }
+
+    pub fn kind(&self) -> usize {
+        self.kind
+    }
+
+    pub fn is_available(&self) -> bool {
+        self.available
+    }
+
+    pub fn is_corpse(&self) -> bool {
+        self.value.is_some()
+    }
+}
+
+/// Turns birds that have died into foods (see `Config::corpses`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Corpses {
+    /// Fraction of the bird's mass - i.e. of the total value of foods it's
+    /// eaten - the corpse is worth
+    pub fraction: f32,
+
+    /// Index into `Config::food_kinds` corpses are seen as
+    pub kind: usize,
+}
+
+impl Default for Corpses {
+    fn default() -> Self {
+        Self {
+            fraction: 0.5,
+            kind: 0,
+        }
+    }
+}
+
+/// Picks a random kind, with chances proportional to the kinds' weights.
+pub(crate) fn pick_food_kind(kinds: &[FoodKind], rng: &mut dyn RngCore) -> usize {
+    if kinds.len() == 1 {
+        return 0;
+    }
+
+    let total: f32 = kinds.iter().map(|kind| kind.weight).sum();
+    let mut pick = rng.gen_range(0.0..total);
+
+    for (idx, kind) in kinds.iter().enumerate() {
+        if pick < kind.weight {
+            return idx;
+        }
+
+        pick -= kind.weight;
+    }
+
+    kinds.len() - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn pick_food_kind() {
+        let kinds = [
+            FoodKind {
+                value: 1.0,
+                weight: 0.0,
+            },
+            FoodKind {
+                value: -1.0,
+                weight: 1.0,
+            },
+        ];
+
+        let mut rng = StepRng::new(0, u64::MAX / 7);
+
+        for _ in 0..10 {
+            assert_eq!(super::pick_food_kind(&kinds, &mut rng), 1);
+        }
+    }
 }
@@ -0,0 +1,185 @@
+use crate::*;
+use std::fmt;
+
+/// Decides where eaten food reappears.
+///
+/// Built-in spawners are selected through `Config::food_spawn`; custom ones
+/// can be plugged in with `Simulation::set_food_spawner()`.
+pub trait FoodSpawner: fmt::Debug + Send {
+    /// Returns where a food should reappear at given `step` (counted since
+    /// the simulation started), or `None` if it should stay gone for now
+    /// - in which case it's asked again during the next step.
+    fn spawn(&mut self, rng: &mut dyn RngCore, step: usize) -> Option<na::Point2<f32>>;
+}
+
+/// Selects one of the built-in `FoodSpawner`s.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum FoodSpawn {
+    /// Respawn immediately, anywhere
+    #[default]
+    Random,
+
+    /// Respawn immediately, within `radius` of one of `count` randomly
+    /// placed cluster centers
+    Clusters { count: usize, radius: f32 },
+
+    /// Respawn immediately, at one of given points
+    Fixed { points: Vec<na::Point2<f32>> },
+
+    /// Respawn anywhere, but with a chance varying between `min_chance`
+    /// and one over a cycle of `period` steps
+    Seasonal { period: usize, min_chance: f32 },
+}
+
+impl FoodSpawn {
+    pub(crate) fn build(&self, rng: &mut dyn RngCore) -> Box<dyn FoodSpawner> {
+        match self {
+            FoodSpawn::Random => Box::new(RandomSpawner),
+            FoodSpawn::Clusters { count, radius } => {
+                Box::new(ClusterSpawner::random(rng, *count, *radius))
+            }
+            FoodSpawn::Fixed { points } => Box::new(FixedSpawner::new(points.clone())),
+            FoodSpawn::Seasonal { period, min_chance } => {
+                Box::new(SeasonalSpawner::new(*period, *min_chance))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct RandomSpawner;
+
+impl FoodSpawner for RandomSpawner {
+    fn spawn(&mut self, rng: &mut dyn RngCore, _: usize) -> Option<na::Point2<f32>> {
+        Some(rng.gen())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ClusterSpawner {
+    centers: Vec<na::Point2<f32>>,
+    radius: f32,
+}
+
+impl ClusterSpawner {
+    pub fn new(centers: Vec<na::Point2<f32>>, radius: f32) -> Self {
+        assert!(!centers.is_empty());
+
+        Self { centers, radius }
+    }
+
+    pub fn random(rng: &mut dyn RngCore, count: usize, radius: f32) -> Self {
+        Self::new((0..count).map(|_| rng.gen()).collect(), radius)
+    }
+}
+
+impl FoodSpawner for ClusterSpawner {
+    fn spawn(&mut self, rng: &mut dyn RngCore, _: usize) -> Option<na::Point2<f32>> {
+        let center = self.centers[rng.gen_range(0..self.centers.len())];
+
+        // Square root makes the points spread evenly over the disc,
+        // instead of bunching up in its middle
+        let distance = self.radius * rng.gen::<f32>().sqrt();
+        let angle = rng.gen_range(0.0..TAU);
+
+        let point = center + na::Vector2::new(angle.cos(), angle.sin()) * distance;
+
+        Some(na::Point2::new(
+            na::wrap(point.x, 0.0, 1.0),
+            na::wrap(point.y, 0.0, 1.0),
+        ))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FixedSpawner {
+    points: Vec<na::Point2<f32>>,
+}
+
+impl FixedSpawner {
+    pub fn new(points: Vec<na::Point2<f32>>) -> Self {
+        assert!(!points.is_empty());
+
+        Self { points }
+    }
+}
+
+impl FoodSpawner for FixedSpawner {
+    fn spawn(&mut self, rng: &mut dyn RngCore, _: usize) -> Option<na::Point2<f32>> {
+        Some(self.points[rng.gen_range(0..self.points.len())])
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SeasonalSpawner {
+    period: usize,
+    min_chance: f32,
+}
+
+impl SeasonalSpawner {
+    pub fn new(period: usize, min_chance: f32) -> Self {
+        assert!(period > 0);
+        assert!((0.0..=1.0).contains(&min_chance));
+
+        Self { period, min_chance }
+    }
+
+    /// Chance of respawning at given step - one at the beginning of each
+    /// period ("summer"), `min_chance` at its half ("winter").
+    pub fn chance(&self, step: usize) -> f32 {
+        let phase = (step % self.period) as f32 / self.period as f32;
+        let abundance = 0.5 + 0.5 * (TAU * phase).cos();
+
+        self.min_chance + (1.0 - self.min_chance) * abundance
+    }
+}
+
+impl FoodSpawner for SeasonalSpawner {
+    fn spawn(&mut self, rng: &mut dyn RngCore, step: usize) -> Option<na::Point2<f32>> {
+        if rng.gen::<f32>() < self.chance(step) {
+            Some(rng.gen())
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn cluster() {
+        let center = na::Point2::new(0.5, 0.5);
+        let mut spawner = ClusterSpawner::new(vec![center], 0.1);
+        let mut rng = StepRng::new(0, u64::MAX / 7);
+
+        for step in 0..20 {
+            let point = spawner.spawn(&mut rng, step).unwrap();
+            assert!(na::distance(&point, &center) <= 0.1 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn fixed() {
+        let points = vec![na::Point2::new(0.1, 0.2), na::Point2::new(0.3, 0.4)];
+        let mut spawner = FixedSpawner::new(points.clone());
+        let mut rng = StepRng::new(0, u64::MAX / 7);
+
+        for step in 0..10 {
+            assert!(points.contains(&spawner.spawn(&mut rng, step).unwrap()));
+        }
+    }
+
+    #[test]
+    fn seasonal_chance() {
+        let spawner = SeasonalSpawner::new(100, 0.2);
+
+        assert_relative_eq!(spawner.chance(0), 1.0);
+        assert_relative_eq!(spawner.chance(50), 0.2);
+        assert_relative_eq!(spawner.chance(100), 1.0);
+        assert_relative_eq!(spawner.chance(25), 0.6, epsilon = 1e-6);
+    }
+}
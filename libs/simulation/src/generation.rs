@@ -0,0 +1,140 @@
+use crate::*;
+
+/// Decides when a generation ends (for `Reproduction::Generational`).
+///
+/// The built-in policy is configured through `Config::generation_end`;
+/// custom ones (including closures) can be plugged in with
+/// `Simulation::set_generation_policy()`.
+pub trait GenerationPolicy: Send {
+    fn ended(&self, config: &Config, progress: &GenerationProgress) -> bool;
+}
+
+impl<F> GenerationPolicy for F
+where
+    F: Fn(&Config, &GenerationProgress) -> bool + Send,
+{
+    fn ended(&self, config: &Config, progress: &GenerationProgress) -> bool {
+        self(config, progress)
+    }
+}
+
+/// State of the current generation, as seen by a `GenerationPolicy`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenerationProgress {
+    /// Number of steps since the generation has started
+    pub age: usize,
+
+    /// Number of foods that are still available
+    pub foods_left: usize,
+
+    /// Number of birds that are alive and have energy to move
+    pub animals_active: usize,
+
+    /// Fitness of the best bird so far
+    pub best_fitness: f32,
+
+    /// Number of steps since `best_fitness` has last improved
+    pub steps_since_improvement: usize,
+}
+
+/// Built-in `GenerationPolicy`: generations last `Config::generation_length`
+/// steps, unless configured otherwise below.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GenerationEnd {
+    /// Ends the generation early, once there's nothing left to eat (which
+    /// happens only with spawners that let eaten foods stay gone)
+    pub when_foods_eaten: bool,
+
+    /// Ends the generation early, once no bird can move anymore
+    pub when_all_stopped: bool,
+
+    /// Lets the generation run past `Config::generation_length` for as
+    /// long as its best bird keeps improving
+    pub extension: Option<Extension>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Extension {
+    /// Number of steps without any improvement after which the generation
+    /// ends
+    pub patience: usize,
+
+    /// Number of steps after which the generation ends regardless
+    pub max_length: usize,
+}
+
+impl GenerationPolicy for GenerationEnd {
+    fn ended(&self, config: &Config, progress: &GenerationProgress) -> bool {
+        if self.when_foods_eaten && progress.foods_left == 0 {
+            return true;
+        }
+
+        if self.when_all_stopped && progress.animals_active == 0 {
+            return true;
+        }
+
+        if progress.age < config.generation_length {
+            return false;
+        }
+
+        match self.extension {
+            Some(extension) => {
+                progress.age >= extension.max_length
+                    || progress.steps_since_improvement >= extension.patience
+            }
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(age: usize, steps_since_improvement: usize) -> GenerationProgress {
+        GenerationProgress {
+            age,
+            foods_left: 10,
+            animals_active: 10,
+            best_fitness: 1.0,
+            steps_since_improvement,
+        }
+    }
+
+    #[test]
+    fn ended() {
+        let config = Config {
+            generation_length: 100,
+            ..Config::default()
+        };
+
+        let end = GenerationEnd::default();
+
+        assert!(!end.ended(&config, &progress(99, 0)));
+        assert!(end.ended(&config, &progress(100, 0)));
+
+        let end = GenerationEnd {
+            when_foods_eaten: true,
+            ..GenerationEnd::default()
+        };
+
+        let eaten = GenerationProgress {
+            foods_left: 0,
+            ..progress(10, 0)
+        };
+
+        assert!(end.ended(&config, &eaten));
+
+        let end = GenerationEnd {
+            extension: Some(Extension {
+                patience: 20,
+                max_length: 200,
+            }),
+            ..GenerationEnd::default()
+        };
+
+        assert!(!end.ended(&config, &progress(150, 19)));
+        assert!(end.ended(&config, &progress(150, 20)));
+        assert!(end.ended(&config, &progress(200, 0)));
+    }
+}
@@ -0,0 +1,58 @@
+use crate::snapshot::{array, number};
+use crate::*;
+
+const VERSION: u64 = 1;
+
+/// Serializes bird's genes (see `Animal::as_chromosome()`) as a JSON
+/// string, e.g. to share a champion; `load_genes()` followed by
+/// `Simulation::inject()` (or `Simulation::seed_with()`) brings it back.
+pub fn save_genes(chromosome: &ga::Chromosome) -> String {
+    Json::object([
+        ("version", Json::number(VERSION)),
+        (
+            "genes",
+            Json::Array(chromosome.iter().map(Json::number).collect()),
+        ),
+    ])
+    .to_string()
+}
+
+pub fn load_genes(genes: &str) -> Result<ga::Chromosome, SnapshotError> {
+    let json = Json::parse(genes).map_err(|offset| SnapshotError::Syntax { offset })?;
+
+    let version: u64 = number(&json, "version")?;
+
+    if version != VERSION {
+        return Err(SnapshotError::UnsupportedVersion { version });
+    }
+
+    array(&json, "genes")?
+        .iter()
+        .map(|gene| {
+            gene.as_number()
+                .ok_or(SnapshotError::Malformed { field: "genes" })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_load() {
+        let chromosome: ga::Chromosome = [0.5, -1.0, 2.0].into_iter().collect();
+        let genes: Vec<_> = load_genes(&save_genes(&chromosome))
+            .unwrap()
+            .iter()
+            .copied()
+            .collect();
+
+        assert_eq!(genes, [0.5, -1.0, 2.0]);
+
+        assert_eq!(
+            load_genes("{\"version\":1,\"genes\":[1,true]}").map(|_| ()),
+            Err(SnapshotError::Malformed { field: "genes" })
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use crate::*;
+
+/// Uniform grid over the world, bucketing objects by their position so that
+/// finding the ones nearby doesn't require checking all of them.
+#[derive(Clone, Debug)]
+pub(crate) struct Grid {
+    /// Number of cells along each axis
+    size: usize,
+    cells: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    /// Creates a grid with cells of (roughly) `cell_size` - queries are
+    /// the fastest when it's close to the usual query radius.
+    pub(crate) fn new(cell_size: f32) -> Self {
+        assert!(cell_size > 0.0);
+
+        let size = ((1.0 / cell_size).ceil() as usize).clamp(1, 1024);
+
+        Self {
+            size,
+            cells: vec![Vec::new(); size * size],
+        }
+    }
+
+    /// Replaces contents of the grid with given objects (indices and
+    /// positions); objects outside the world land in the border cells.
+    pub(crate) fn rebuild(&mut self, objects: impl IntoIterator<Item = (usize, na::Point2<f32>)>) {
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+
+        for (idx, position) in objects {
+            let cell = self.cell_of(position);
+            self.cells[cell].push(idx);
+        }
+    }
+
+    /// Returns indices of objects that might lie within `radius` of
+    /// `center` - it's up to the caller to check the exact distance.
+    pub(crate) fn query(&self, center: na::Point2<f32>, radius: f32) -> Vec<usize> {
+        let (min_x, min_y) = self.coords_of(center - na::Vector2::new(radius, radius));
+        let (max_x, max_y) = self.coords_of(center + na::Vector2::new(radius, radius));
+
+        let mut found = Vec::new();
+
+        for y in min_y..=max_y {
+            for x in min_x..=max_x {
+                found.extend_from_slice(&self.cells[y * self.size + x]);
+            }
+        }
+
+        found
+    }
+
+    fn cell_of(&self, position: na::Point2<f32>) -> usize {
+        let (x, y) = self.coords_of(position);
+
+        y * self.size + x
+    }
+
+    fn coords_of(&self, position: na::Point2<f32>) -> (usize, usize) {
+        let coord = |value: f32| {
+            // Negative values (and NaNs) saturate to zero
+            ((value * self.size as f32) as usize).min(self.size - 1)
+        };
+
+        (coord(position.x), coord(position.y))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn query_finds_everything_nearby() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        // Some points lie slightly outside the world
+        let points: Vec<na::Point2<f32>> = (0..500)
+            .map(|_| na::Point2::new(rng.gen_range(-0.1..1.1), rng.gen_range(-0.1..1.1)))
+            .collect();
+
+        let mut grid = Grid::new(0.1);
+        grid.rebuild(points.iter().copied().enumerate());
+
+        for _ in 0..100 {
+            let center = na::Point2::new(rng.gen_range(-0.2..1.2), rng.gen_range(-0.2..1.2));
+            let radius = rng.gen_range(0.0..0.3);
+
+            let expected: Vec<_> = (0..points.len())
+                .filter(|&idx| na::distance(&points[idx], &center) <= radius)
+                .collect();
+
+            let mut actual: Vec<_> = grid
+                .query(center, radius)
+                .into_iter()
+                .filter(|&idx| na::distance(&points[idx], &center) <= radius)
+                .collect();
+
+            actual.sort();
+
+            assert_eq!(actual, expected);
+        }
+    }
+}
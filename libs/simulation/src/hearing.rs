@@ -0,0 +1,101 @@
+use crate::*;
+
+/// How sounds carry around (see `Senses::hearing`).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HearingConfig {
+    /// Distance at which sounds fade out completely
+    pub range: f32,
+}
+
+impl Default for HearingConfig {
+    fn default() -> Self {
+        Self { range: 0.2 }
+    }
+}
+
+/// Returns what each bird hears: flapping of other birds (the faster they
+/// fly, the louder) and predators, each fading out with distance.
+pub(crate) fn listen(
+    config: &HearingConfig,
+    animals: &[Animal],
+    predators: &[Predator],
+) -> Vec<[f32; 2]> {
+    let volume = |from: na::Point2<f32>, to: na::Point2<f32>| {
+        (1.0 - na::distance(&from, &to) / config.range).max(0.0)
+    };
+
+    let mut grid = Grid::new(config.range);
+
+    grid.rebuild(
+        animals
+            .iter()
+            .enumerate()
+            .filter(|(_, animal)| animal.alive)
+            .map(|(idx, animal)| (idx, animal.position)),
+    );
+
+    animals
+        .iter()
+        .enumerate()
+        .map(|(idx, animal)| {
+            if !animal.alive {
+                return [0.0, 0.0];
+            }
+
+            let birds = grid
+                .query(animal.position, config.range)
+                .into_iter()
+                .filter(|&other| other != idx)
+                .map(|other| {
+                    let other = &animals[other];
+                    let flapping = other.speed / other.body.speed_max;
+
+                    flapping * volume(animal.position, other.position)
+                })
+                .sum();
+
+            let predators = predators
+                .iter()
+                .map(|predator| volume(animal.position, predator.position))
+                .sum();
+
+            [birds, predators]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn listen() {
+        let config = Config::default();
+
+        let animal = |x: f32, speed: f32| Animal {
+            position: na::Point2::new(x, 0.5),
+            speed,
+            ..Animal::random(&config, &mut StepRng::new(0, 1))
+        };
+
+        let animals = [
+            animal(0.5, config.speed_max),
+            animal(0.6, config.speed_max / 2.0),
+            animal(0.9, config.speed_max),
+        ];
+
+        let predators = [Predator {
+            position: na::Point2::new(0.5, 0.55),
+            rotation: na::Rotation2::identity(),
+        }];
+
+        let sounds = super::listen(&HearingConfig::default(), &animals, &predators);
+
+        assert_relative_eq!(sounds[0][0], 0.5 * 0.5);
+        assert_relative_eq!(sounds[0][1], 0.75);
+        assert_relative_eq!(sounds[1][0], 0.5);
+        assert_relative_eq!(sounds[2][0], 0.0);
+    }
+}
@@ -0,0 +1,373 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Bare-bones JSON document, just enough for snapshots (and for messages
+/// the wasm bindings exchange with JavaScript).
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+
+    /// Kept as text, so that numbers round-trip exactly - whatever type
+    /// they get read as
+    Number(String),
+
+    String(String),
+    Array(Vec<Json>),
+
+    /// Fields are kept in the order they were written in
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn number(value: impl fmt::Display) -> Self {
+        Json::Number(value.to_string())
+    }
+
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Json)>) -> Self {
+        Json::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+        )
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(field, _)| field == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Json::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_number<T: FromStr>(&self) -> Option<T> {
+        match self {
+            Json::Number(value) => value.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Parses a document, returning the byte offset of the first
+    /// unexpected character on failure.
+    pub fn parse(text: &str) -> Result<Self, usize> {
+        let mut parser = Parser {
+            text: text.as_bytes(),
+            pos: 0,
+        };
+
+        let json = parser.value()?;
+
+        parser.skip_whitespace();
+
+        if parser.pos == parser.text.len() {
+            Ok(json)
+        } else {
+            Err(parser.pos)
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{value}"),
+            Json::Number(value) => write!(f, "{value}"),
+            Json::String(value) => write_str(f, value),
+
+            Json::Array(items) => {
+                write!(f, "[")?;
+
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write!(f, "{item}")?;
+                }
+
+                write!(f, "]")
+            }
+
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+
+                for (idx, (key, value)) in fields.iter().enumerate() {
+                    if idx > 0 {
+                        write!(f, ",")?;
+                    }
+
+                    write_str(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_str(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+
+    for ch in value.chars() {
+        match ch {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            ch if ch.is_control() => write!(f, "\\u{:04x}", ch as u32)?,
+            ch => write!(f, "{ch}")?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn value(&mut self) -> Result<Json, usize> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'n') => self.keyword("null", Json::Null),
+            Some(b't') => self.keyword("true", Json::Bool(true)),
+            Some(b'f') => self.keyword("false", Json::Bool(false)),
+            Some(b'"') => self.string().map(Json::String),
+            Some(b'[') => self.array(),
+            Some(b'{') => self.object(),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => Err(self.pos),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, json: Json) -> Result<Json, usize> {
+        if self.text[self.pos..].starts_with(keyword.as_bytes()) {
+            self.pos += keyword.len();
+            Ok(json)
+        } else {
+            Err(self.pos)
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, usize> {
+        let start = self.pos;
+
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.peek() {
+            self.pos += 1;
+        }
+
+        let number = std::str::from_utf8(&self.text[start..self.pos]).unwrap();
+
+        if number.parse::<f64>().is_ok() {
+            Ok(Json::Number(number.to_string()))
+        } else {
+            Err(start)
+        }
+    }
+
+    fn string(&mut self) -> Result<String, usize> {
+        self.expect(b'"')?;
+
+        let mut bytes = Vec::new();
+
+        loop {
+            match self.next() {
+                Some(b'"') => break,
+
+                Some(b'\\') => match self.next() {
+                    Some(b'"') => bytes.push(b'"'),
+                    Some(b'\\') => bytes.push(b'\\'),
+                    Some(b'/') => bytes.push(b'/'),
+                    Some(b'n') => bytes.push(b'\n'),
+                    Some(b'r') => bytes.push(b'\r'),
+                    Some(b't') => bytes.push(b'\t'),
+                    Some(b'b') => bytes.push(0x08),
+                    Some(b'f') => bytes.push(0x0c),
+
+                    Some(b'u') => {
+                        let start = self.pos;
+
+                        let code = self
+                            .text
+                            .get(start..start + 4)
+                            .and_then(|hex| std::str::from_utf8(hex).ok())
+                            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                            .and_then(char::from_u32)
+                            .ok_or(start)?;
+
+                        self.pos += 4;
+
+                        let mut buf = [0; 4];
+                        bytes.extend_from_slice(code.encode_utf8(&mut buf).as_bytes());
+                    }
+
+                    _ => return Err(self.pos - 1),
+                },
+
+                Some(byte) => bytes.push(byte),
+                None => return Err(self.pos),
+            }
+        }
+
+        // Input is a `&str` and we've split it only at ASCII characters,
+        // so this can't fail
+        Ok(String::from_utf8(bytes).unwrap())
+    }
+
+    fn array(&mut self) -> Result<Json, usize> {
+        self.expect(b'[')?;
+
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+
+            match self.next() {
+                Some(b',') => continue,
+                Some(b']') => return Ok(Json::Array(items)),
+                _ => return Err(self.pos - 1),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json, usize> {
+        self.expect(b'{')?;
+
+        let mut fields = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+
+            let key = self.string()?;
+
+            self.skip_whitespace();
+            self.expect(b':')?;
+
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+
+            match self.next() {
+                Some(b',') => continue,
+                Some(b'}') => return Ok(Json::Object(fields)),
+                _ => return Err(self.pos - 1),
+            }
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), usize> {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.pos)
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\n' | b'\r' | b'\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let json = Json::object([
+            ("null", Json::Null),
+            ("bool", Json::Bool(true)),
+            ("number", Json::number(0.1f32)),
+            (
+                "string",
+                Json::String("\"quoted\"\n\u{1}zażółć".to_string()),
+            ),
+            (
+                "array",
+                Json::Array(vec![Json::number(-1), Json::Array(Vec::new())]),
+            ),
+            ("object", Json::object([])),
+        ]);
+
+        let text = json.to_string();
+
+        assert_eq!(Json::parse(&text), Ok(json.clone()));
+        assert_eq!(json.get("number").unwrap().as_number(), Some(0.1f32));
+    }
+
+    #[test]
+    fn whitespace() {
+        let json = Json::parse(" { \"a\" : [ 1 , 2.5e3 ] , \"b\" : \"\\u0041\" } ").unwrap();
+
+        let items = json.get("a").unwrap().as_array().unwrap();
+
+        assert_eq!(items[1].as_number(), Some(2500.0));
+        assert_eq!(json.get("b").unwrap().as_str(), Some("A"));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(Json::parse(""), Err(0));
+        assert_eq!(Json::parse("[1,]"), Err(3));
+        assert_eq!(Json::parse("{\"a\" 1}"), Err(5));
+        assert_eq!(Json::parse("true false"), Err(5));
+        assert_eq!(Json::parse("\"unterminated"), Err(13));
+    }
+}
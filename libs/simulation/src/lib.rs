@@ -2,131 +2,2059 @@
 
 mod animal;
 mod animal_individual;
+mod behavior;
+mod boundary;
 mod brain;
+mod channel;
+mod collision;
+mod config;
+mod curriculum;
+mod day_night;
+mod environment;
+mod events;
+mod experiment;
 mod eye;
+mod fitness;
 mod food;
+mod food_spawner;
+mod generation;
+mod genes;
+mod grid;
+mod hearing;
+mod json;
+mod obstacle;
+mod operators;
+mod policy;
+mod predator;
+mod replay;
+mod scent;
+mod senses;
+mod snapshot;
+mod statistics;
+mod terrain;
+mod think;
+#[cfg(feature = "3d")]
+mod three_d;
+#[cfg(not(target_arch = "wasm32"))]
+mod trainer;
+mod traits;
+mod wind;
 mod world;
 
-pub use self::{animal::*, brain::*, eye::*, food::*, world::*};
+pub use self::{
+    animal::*, behavior::*, boundary::*, brain::*, channel::Channel, collision::*, config::*,
+    curriculum::Stage, day_night::*, environment::*, events::*, experiment::*, eye::*, fitness::*,
+    food::*, food_spawner::*, generation::*, genes::*, hearing::HearingConfig, json::Json,
+    obstacle::*, operators::*, policy::*, predator::*, replay::*, scent::*, senses::*, snapshot::*,
+    statistics::*, terrain::*, traits::*, wind::*, world::*,
+};
 
-use self::animal_individual::*;
+pub use self::think::Inspection;
+
+#[cfg(feature = "3d")]
+pub use self::three_d::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::trainer::*;
+
+use self::{
+    animal_individual::*,
+    channel::*,
+    curriculum::*,
+    grid::*,
+    hearing::*,
+    snapshot::stats_to_json,
+    think::{inspect, observe, think},
+};
+use ga::{Individual, MutationMethod};
 use lib_genetic_algorithm as ga;
 use lib_neural_network as nn;
 use nalgebra as na;
-use rand::{Rng, RngCore};
-use std::f32::consts::FRAC_PI_2;
-
-const SPEED_MIN: f32 = 0.001;
-const SPEED_MAX: f32 = 0.005;
-const SPEED_ACCEL: f32 = 0.2;
-const ROTATION_ACCEL: f32 = FRAC_PI_2;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f32::consts::*;
 
-const GENERATION_LENGTH: usize = 2500;
+/// Simulation advances in fixed steps: `step()` moves everything by the
+/// same amount of simulated time, no matter how often it's called - so,
+/// given the same seed and config, two runs are identical.
 pub struct Simulation {
+    config: Config,
     world: World,
-    ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
+    /// One per species
+    ga: Vec<GeneticAlgorithm>,
+
+    /// Statistics of each species from the last generation
+    species_stats: Vec<ga::Statistics>,
+
+    /// Statistics of all the generations so far
+    history: Vec<Statistics>,
+
+    /// Genes of the best bird of the last generation
+    champion: Option<ga::Chromosome>,
+
+    /// Genes of the last generation, which the next one gets built in
+    recycled: Vec<ga::Chromosome>,
+
+    /// Statistics of each baseline from the last generation
+    baseline_stats: Vec<Statistics>,
+    food_spawner: Box<dyn FoodSpawner>,
+    fitness: Box<dyn FitnessFn>,
+    generation_policy: Box<dyn GenerationPolicy>,
+
+    /// Fitness of the current generation's best bird so far
+    best_fitness: f32,
+
+    /// Age of the current generation when `best_fitness` has last improved
+    improved_at: usize,
+
+    /// Events of the last step
+    events: Vec<Event>,
+    observers: Vec<Observer>,
+
+    /// Steps since the current generation has started
     age: usize,
+
+    /// Steps since the simulation has started
+    steps: usize,
+
+    /// Available foods, bucketed by their position.
+    food_grid: Grid,
+
+    /// Behavior of the current generation, so far
+    behavior: BehaviorTracker,
+
+    /// Shared by all the brains, so that thinking doesn't allocate.
+    scratch: nn::PropagationScratch,
+
+    seed: u64,
+
+    /// Source of all the randomness within the simulation
+    rng: ChaCha8Rng,
 }
 
 impl Simulation {
+    /// Panics if the config is invalid, see `Config::validate()`.
+    pub fn new(config: Config, seed: u64) -> Self {
+        if let Err(err) = config.validate() {
+            panic!("invalid config: {err}");
+        }
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let world = World::random(&config, &mut rng);
+        let food_spawner = config.food_spawn.build(&mut rng);
+        let fitness = Box::new(config.fitness);
+        let generation_policy = Box::new(config.generation_end);
+
+        // Smaller cells make vision queries tighter, but more of them have
+        // to be visited - a quarter of the eye's range works out the best
+        let food_grid = Grid::new(config.eye_fov_range / 4.0);
+        let ga = genetic_algorithms(&config);
+        let mut this = Self {
+            config,
+            world,
+            ga,
+            species_stats: Vec::new(),
+            history: Vec::new(),
+            champion: None,
+            recycled: Vec::new(),
+            baseline_stats: Vec::new(),
+            food_spawner,
+            fitness,
+            generation_policy,
+            best_fitness: 0.0,
+            improved_at: 0,
+            events: Vec::new(),
+            observers: Vec::new(),
+            age: 0,
+            steps: 0,
+            food_grid,
+            behavior: BehaviorTracker::new(),
+            scratch: nn::PropagationScratch::new(),
+            seed,
+            rng,
+        };
+
+        this.scatter_foods();
+        this
+    }
+
+    /// Creates a simulation with a fresh, random seed - use `seed()` to
+    /// find out which one it was.
+    pub fn from_entropy(config: Config) -> Self {
+        Self::new(config, rand::thread_rng().next_u64())
+    }
+
+    /// Creates a simulation with the default `Config` and a seed drawn
+    /// from given PRNG.
     pub fn random(rng: &mut dyn RngCore) -> Self {
-        let world = World::random(rng);
-        let ga = ga::GeneticAlgorithm::new(
-            ga::RouletteWheelSelection,
-            ga::UniformCrossover,
-            ga::GaussianMutation::new(0.1, 0.3),
-        );
-        Self { world, ga, age: 0 }
+        Self::new(Config::default(), rng.next_u64())
+    }
+
+    /// Returns the seed this simulation has been created with; passing it
+    /// (together with the same config) to `Simulation::new()` replays the
+    /// exact same run.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
     }
 
     pub fn world(&self) -> &World {
         &self.world
     }
 
-    pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<ga::Statistics> {
-        self.process_collisions(rng);
-        self.process_brains();
-        self.process_movements();
+    /// Returns current daylight, from zero (midnight) to one (noon); see
+    /// `Config::day_night`.
+    pub fn daylight(&self) -> f32 {
+        self.config
+            .day_night
+            .map_or(1.0, |day_night| day_night.light(self.steps))
+    }
 
-        self.age += 1;
+    /// Returns the average age of living birds (zero if there are none).
+    pub fn avg_age(&self) -> f32 {
+        let (count, sum) = self
+            .world
+            .animals
+            .iter()
+            .filter(|animal| animal.alive)
+            .fold((0, 0), |(count, sum), animal| (count + 1, sum + animal.age));
 
-        if self.age >= GENERATION_LENGTH {
-            Some(self.evolve(rng))
+        if count == 0 {
+            0.0
         } else {
-            None
+            sum as f32 / count as f32
+        }
+    }
+
+    /// Returns statistics of the last generation (`None` until the first
+    /// one ends).
+    pub fn stats(&self) -> Option<&Statistics> {
+        self.history.last()
+    }
+
+    /// Returns the evolved bird with the highest fitness in the current
+    /// generation so far.
+    pub fn best_animal(&self) -> Option<&Animal> {
+        self.world
+            .animals
+            .iter()
+            .filter(|animal| animal.is_evolved())
+            .map(|animal| (animal, score(&*self.fitness, animal)))
+            .reduce(|best, other| if other.1 > best.1 { other } else { best })
+            .map(|(animal, _)| animal)
+    }
+
+    /// Returns genetic diversity of the evolved birds - standard deviation
+    /// of each gene across the birds, averaged over all the genes; zero
+    /// means all of them are the same.
+    pub fn diversity(&self) -> f32 {
+        let chromosomes: Vec<_> = self
+            .world
+            .animals
+            .iter()
+            .filter(|animal| animal.is_evolved())
+            .map(Animal::as_chromosome)
+            .collect();
+
+        let Some(genes) = chromosomes.first().map(ga::Chromosome::len) else {
+            return 0.0;
+        };
+
+        if genes == 0 {
+            return 0.0;
+        }
+
+        let count = chromosomes.len() as f32;
+        let mut sums = vec![0.0; genes];
+        let mut squares = vec![0.0; genes];
+
+        for chromosome in &chromosomes {
+            for (idx, &gene) in chromosome.iter().enumerate() {
+                // No-op for the default precision
+                #[allow(clippy::unnecessary_cast)]
+                let gene = gene as f32;
+
+                sums[idx] += gene;
+                squares[idx] += gene * gene;
+            }
+        }
+
+        let deviations: f32 = sums
+            .iter()
+            .zip(&squares)
+            .map(|(sum, square)| {
+                let mean = sum / count;
+
+                // Rounding errors can make it slightly negative
+                (square / count - mean * mean).max(0.0).sqrt()
+            })
+            .sum();
+
+        deviations / genes as f32
+    }
+
+    /// Returns what the bird of given index sees and thinks right now -
+    /// i.e. what will drive its next move; `None` for dead birds and
+    /// baselines (which don't use their brains).
+    pub fn inspect(&mut self, animal: usize) -> Option<Inspection> {
+        self.index_foods();
+
+        inspect(
+            self.world.animals.get(animal)?,
+            &self.config,
+            &self.world_view(),
+        )
+    }
+
+    fn world_view(&self) -> WorldView<'_> {
+        WorldView {
+            foods: &self.world.foods,
+            food_grid: &self.food_grid,
+            predators: &self.world.predators,
+            obstacles: &self.world.obstacles,
+            scent: self.world.scent.as_ref(),
+            light: self.daylight(),
+            vision: self
+                .config
+                .day_night
+                .map_or(1.0, |day_night| day_night.vision(self.steps)),
+        }
+    }
+
+    /// Returns genes of the best bird of the last generation (`None` until
+    /// the first one ends).
+    pub fn champion(&self) -> Option<&ga::Chromosome> {
+        self.champion.as_ref()
+    }
+
+    /// Replaces all evolved birds with ones grown from `chromosomes` (each
+    /// used in turn, as many times as needed) - e.g. with champions of
+    /// another simulation, for the lack of a better starting point.
+    ///
+    /// Chromosomes have to match this simulation's config (its eye, senses,
+    /// traits, etc.).
+    pub fn seed_with(&mut self, chromosomes: &[ga::Chromosome]) -> Result<(), SnapshotError> {
+        let genes = self.chromosome_len();
+
+        if chromosomes.is_empty() || chromosomes.iter().any(|c| c.len() != genes) {
+            return Err(SnapshotError::ConfigMismatch);
+        }
+
+        let mut chromosomes = chromosomes.iter().cycle();
+
+        for animal in &mut self.world.animals {
+            if !animal.is_evolved() {
+                continue;
+            }
+
+            let chromosome = chromosomes.next().unwrap().clone();
+
+            *animal = Animal::from_chromosome(&self.config, chromosome, &mut self.rng)
+                .with_species(animal.species);
+
+            // Seeded birds are new ones, just like after a generation
+            animal.id = AnimalId(self.world.next_id);
+            self.world.next_id += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Puts a bird grown from `chromosome` (e.g. a hand-tuned brain) into
+    /// the world, returning its index.
+    ///
+    /// Injected birds can be told apart through `Animal::is_injected()`;
+    /// they don't evolve and are left out of the statistics, but they stay
+    /// in the world across generations.
+    pub fn inject(&mut self, chromosome: ga::Chromosome) -> Result<usize, SnapshotError> {
+        if chromosome.len() != self.chromosome_len() {
+            return Err(SnapshotError::ConfigMismatch);
+        }
+
+        let mut animal = Animal::from_chromosome(&self.config, chromosome, &mut self.rng);
+        animal.injected = true;
+
+        self.world.animals.push(animal);
+        self.world.number_animals(self.world.animals.len() - 1);
+
+        Ok(self.world.animals.len() - 1)
+    }
+
+    /// Returns the number of genes birds of this simulation have.
+    fn chromosome_len(&self) -> usize {
+        let brain = Brain::segment_layout(
+            &Eye::from_config(&self.config),
+            &self.config.channels(),
+            &self.config.senses,
+        );
+
+        brain.gene_count() + self.config.traits.gene_count()
+    }
+
+    /// Returns statistics of all the generations so far, oldest first.
+    pub fn history(&self) -> &[Statistics] {
+        &self.history
+    }
+
+    /// Returns `history()` as CSV, one generation per row (with a header).
+    pub fn export_csv(&self) -> String {
+        let mut csv = String::from(Statistics::CSV_HEADER);
+        csv.push('\n');
+
+        for stats in &self.history {
+            csv.push_str(&stats.to_csv_row());
+            csv.push('\n');
+        }
+
+        csv
+    }
+
+    /// Returns `history()` as a JSON array.
+    pub fn export_json(&self) -> String {
+        Json::Array(self.history.iter().map(stats_to_json).collect()).to_string()
+    }
+
+    /// Returns statistics of each species from the last generation (empty
+    /// until the first one ends).
+    pub fn species_stats(&self) -> &[ga::Statistics] {
+        &self.species_stats
+    }
+
+    /// Returns statistics of each of `Config::baselines` from the last
+    /// generation (empty until the first one ends); their `behavior` is
+    /// left out, being tracked for the entire world only.
+    pub fn baseline_stats(&self) -> &[Statistics] {
+        &self.baseline_stats
+    }
+
+    pub fn add_obstacle(&mut self, obstacle: Obstacle) {
+        self.world.obstacles.push(obstacle);
+    }
+
+    /// Drops an extra food of given kind (an index into
+    /// `Config::food_kinds`) at `position`, returning its index; like the
+    /// rest, once eaten it reappears wherever the spawner says.
+    pub fn add_food(&mut self, position: na::Point2<f32>, kind: usize) -> usize {
+        assert!(kind < self.config.food_kinds.len());
+
+        self.world.foods.push(Food {
+            position,
+            kind,
+            available: true,
+            value: None,
+        });
+
+        self.world.foods.len() - 1
+    }
+
+    /// Puts a fresh, random bird at `position`, returning its index; it
+    /// joins the first species, evolving alongside the rest.
+    pub fn add_animal(&mut self, position: na::Point2<f32>) -> usize {
+        let mut animal = Animal::random(&self.config, &mut self.rng);
+        animal.position = position;
+
+        self.world.animals.push(animal);
+        self.world.number_animals(self.world.animals.len() - 1);
+
+        self.world.animals.len() - 1
+    }
+
+    /// Replaces the spawner selected through `Config::food_spawn`.
+    pub fn set_food_spawner(&mut self, spawner: Box<dyn FoodSpawner>) {
+        self.food_spawner = spawner;
+    }
+
+    /// Changes `Config::mutation_chance` and `Config::mutation_coeff`,
+    /// which take effect once the current generation ends (or right away,
+    /// for `Reproduction::Continuous`).
+    pub fn set_mutation(&mut self, chance: f32, coeff: f32) {
+        self.config.mutation_chance = chance;
+        self.config.mutation_coeff = coeff;
+        self.ga = genetic_algorithms(&self.config);
+    }
+
+    /// Replaces the objective selected through `Config::fitness`.
+    pub fn set_fitness(&mut self, fitness: Box<dyn FitnessFn>) {
+        self.fitness = fitness;
+    }
+
+    /// Replaces the policy configured through `Config::generation_end`.
+    pub fn set_generation_policy(&mut self, policy: Box<dyn GenerationPolicy>) {
+        self.generation_policy = policy;
+    }
+
+    /// Returns state of the current generation.
+    pub fn progress(&self) -> GenerationProgress {
+        GenerationProgress {
+            age: self.age,
+            foods_left: self
+                .world
+                .foods
+                .iter()
+                .filter(|food| food.available)
+                .count(),
+            animals_active: self
+                .world
+                .animals
+                .iter()
+                .filter(|animal| animal.can_move(&self.config))
+                .count(),
+            best_fitness: self.best_fitness,
+            steps_since_improvement: self.age - self.improved_at,
+        }
+    }
+
+    /// Returns events that have happened during the last step.
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    /// Registers a callback notified about all the events, as they happen
+    /// (unlike `events()`, this doesn't miss any during `train()`).
+    pub fn subscribe(&mut self, observer: Observer) {
+        self.observers.push(observer);
+    }
+
+    pub fn step(&mut self) -> Option<Statistics> {
+        self.events.clear();
+
+        let stats = self.step_world();
+
+        for observer in &mut self.observers {
+            for event in &self.events {
+                observer(event);
+            }
+        }
+
+        stats
+    }
+
+    fn step_world(&mut self) -> Option<Statistics> {
+        self.advance(None);
+
+        match self.config.reproduction {
+            Reproduction::Generational => {
+                if self.generation_ended() {
+                    Some(self.evolve())
+                } else {
+                    None
+                }
+            }
+
+            Reproduction::Continuous { .. } => {
+                self.process_reproduction();
+                None
+            }
+        }
+    }
+
+    /// Moves everything by a single step, without evolving; given
+    /// `actions` replace decisions of the brain-driven birds (see
+    /// `BirdWorld`).
+    pub(crate) fn advance(&mut self, actions: Option<&[Vec<f32>]>) {
+        self.process_collisions();
+        self.process_foods();
+        self.process_scent();
+        self.process_hearing();
+        self.process_brains(actions);
+        self.process_predators();
+
+        for substep in 0..self.config.substeps {
+            if substep > 0 {
+                self.process_collisions();
+            }
+
+            self.process_movements(1.0 / self.config.substeps as f32);
+        }
+
+        if let Some(collisions) = &self.config.collisions {
+            collide(collisions, &mut self.world.animals);
+        }
+
+        self.process_ages();
+        self.spawn_corpses(0);
+        self.behavior.observe(&self.world.animals);
+
+        self.age += 1;
+        self.steps += 1;
+    }
+
+    /// Asks the generation policy whether the current generation is over,
+    /// keeping track of the best fitness so far.
+    pub(crate) fn generation_ended(&mut self) -> bool {
+        let best_fitness = self
+            .world
+            .animals
+            .iter()
+            .map(|animal| score(&*self.fitness, animal))
+            .fold(0.0, f32::max);
+
+        if best_fitness > self.best_fitness {
+            self.best_fitness = best_fitness;
+            self.improved_at = self.age;
         }
+
+        self.generation_policy.ended(&self.config, &self.progress())
+    }
+
+    /// Returns what each brain-driven bird sees and feels, i.e. its
+    /// brain's inputs.
+    pub(crate) fn observations(&mut self) -> Vec<Vec<f32>> {
+        self.index_foods();
+
+        let world = self.world_view();
+        let channels = self.config.channels();
+
+        self.world
+            .animals
+            .iter()
+            .filter(|animal| animal.baseline.is_none())
+            .map(|animal| observe(animal, &self.config, &channels, &world))
+            .collect()
+    }
+
+    /// Returns fitness of each brain-driven bird.
+    pub(crate) fn scores(&self) -> Vec<f32> {
+        self.world
+            .animals
+            .iter()
+            .filter(|animal| animal.baseline.is_none())
+            .map(|animal| score(&*self.fitness, animal))
+            .collect()
+    }
+
+    /// Performs `n` steps at once, returning statistics of the last
+    /// generation that has ended in the meantime (if any).
+    ///
+    /// Like with `step()`, `events()` returns only the last step's events.
+    pub fn step_many(&mut self, n: usize) -> Option<Statistics> {
+        (0..n).fold(None, |stats, _| self.step().or(stats))
     }
 
     /// Fast-forwards 'till the end of the current generation.
-    pub fn train(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
+    ///
+    /// Panics for `Reproduction::Continuous`, since there are no
+    /// generations in there.
+    pub fn train(&mut self) -> Statistics {
+        self.assert_generational();
+
         loop {
-            if let Some(summary) = self.step(rng) {
+            if let Some(summary) = self.step() {
                 return summary;
             }
         }
     }
 
-    fn process_movements(&mut self) {
-        for animal in &mut self.world.animals {
-            animal.position += animal.rotation * na::Vector2::new(0.0, animal.speed);
+    /// Fast-forwards through `n` generations, returning their statistics.
+    pub fn train_generations(&mut self, n: usize) -> Vec<Statistics> {
+        self.assert_generational();
+
+        (0..n).map(|_| self.train()).collect()
+    }
+
+    /// Fast-forwards through generations until `done` returns true for one
+    /// of them, returning statistics of all of them (including the last
+    /// one).
+    pub fn train_until(&mut self, mut done: impl FnMut(&Statistics) -> bool) -> Vec<Statistics> {
+        self.assert_generational();
+
+        let mut history = Vec::new();
+
+        loop {
+            let stats = self.train();
+            let finished = done(&stats);
+
+            history.push(stats);
 
-            animal.position.x = na::wrap(animal.position.x, 0.0, 1.0);
-            animal.position.y = na::wrap(animal.position.y, 0.0, 1.0);
+            if finished {
+                return history;
+            }
         }
     }
 
-    fn process_brains(&mut self) {
+    fn assert_generational(&self) {
+        assert_eq!(
+            self.config.reproduction,
+            Reproduction::Generational,
+            "training requires generations"
+        );
+    }
+
+    /// Moves birds by `fraction` of a step.
+    fn process_movements(&mut self, fraction: f32) {
         for animal in &mut self.world.animals {
-            let vision =
-                animal
-                    .eye
-                    .process_vision(animal.position, animal.rotation, &self.world.foods);
-            let response = animal.brain.nn.propagate(vision);
+            if !animal.can_move(&self.config) {
+                continue;
+            }
+
+            let speed = animal.speed
+                * self
+                    .config
+                    .terrain
+                    .as_ref()
+                    .map_or(1.0, |terrain| terrain.at(animal.position));
 
-            let speed = response[0].clamp(-SPEED_ACCEL, SPEED_ACCEL);
-            let rotation = response[1].clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
+            let movement = (animal.rotation * na::Vector2::new(0.0, speed)
+                + self.config.wind.at(animal.position))
+                * fraction;
 
-            animal.speed = (animal.speed + speed).clamp(SPEED_MIN, SPEED_MAX);
+            let position = animal.position + movement;
+
+            if !passable(&self.world.obstacles, animal.position, position) {
+                continue;
+            }
+
+            animal.distance += speed * fraction;
+            animal.displacement += movement;
+            animal.position = position;
+
+            self.config
+                .boundary
+                .apply(&mut animal.position, &mut animal.rotation);
+        }
+    }
+
+    fn process_predators(&mut self) {
+        let Some(config) = &self.config.predators else {
+            return;
+        };
+
+        for predator in &mut self.world.predators {
+            predator.hunt(config, &self.world.animals);
+
+            let position =
+                predator.position + predator.rotation * na::Vector2::new(0.0, config.speed);
+
+            if !passable(&self.world.obstacles, predator.position, position) {
+                continue;
+            }
+
+            predator.position = position;
+
+            self.config
+                .boundary
+                .apply(&mut predator.position, &mut predator.rotation);
+        }
+    }
+
+    fn process_brains(&mut self, actions: Option<&[Vec<f32>]>) {
+        self.index_foods();
+
+        let world = WorldView {
+            foods: &self.world.foods,
+            food_grid: &self.food_grid,
+            predators: &self.world.predators,
+            obstacles: &self.world.obstacles,
+            scent: self.world.scent.as_ref(),
+            light: self.daylight(),
+            vision: self
+                .config
+                .day_night
+                .map_or(1.0, |day_night| day_night.vision(self.steps)),
+        };
+
+        // Everyone decides first and moves later, so that (possibly
+        // parallel) thinking doesn't depend on the order of birds
+        let decisions = match actions {
+            Some(actions) => {
+                let mut actions = actions.iter();
+
+                let decisions = self
+                    .world
+                    .animals
+                    .iter()
+                    .map(|animal| {
+                        if animal.baseline.is_some() {
+                            return None;
+                        }
+
+                        let action = actions.next().expect("not enough actions");
+                        animal.alive.then(|| [action[0], action[1]])
+                    })
+                    .collect();
+
+                assert!(actions.next().is_none(), "too many actions");
+                decisions
+            }
+
+            None => think(&self.world.animals, &self.config, &world, &mut self.scratch),
+        };
+
+        for (idx, (animal, decision)) in self.world.animals.iter_mut().zip(decisions).enumerate() {
+            if !animal.alive {
+                continue;
+            }
+
+            let [speed, rotation] = match (decision, animal.baseline) {
+                (Some(decision), _) => decision,
+                (None, Some(baseline)) => self.config.baselines[baseline].policy.decide(
+                    animal,
+                    &self.config,
+                    &world,
+                    &mut self.rng,
+                ),
+                (None, None) => unreachable!(),
+            };
+
+            let speed = speed.clamp(-self.config.speed_accel, self.config.speed_accel);
+            let rotation = rotation.clamp(-animal.body.rotation_accel, animal.body.rotation_accel);
+
+            animal.speed =
+                (animal.speed + speed).clamp(self.config.speed_min, animal.body.speed_max);
             animal.rotation = na::Rotation2::new(animal.rotation.angle() + rotation);
+            animal.turn = rotation;
+
+            if let Some(energy) = &self.config.energy {
+                animal.spend_energy(energy, rotation);
+
+                if !animal.alive {
+                    self.events.push(Event::AnimalDied {
+                        animal: idx,
+                        id: animal.id,
+                        cause: DeathCause::Starved,
+                    });
+                }
+            }
+
+            if animal.can_move(&self.config) {
+                animal.survived += 1;
+            }
         }
     }
 
-    fn process_collisions(&mut self, rng: &mut dyn RngCore) {
-        for animal in &mut self.world.animals {
-            for food in &mut self.world.foods {
+    fn process_collisions(&mut self) {
+        self.index_foods();
+
+        for (animal_idx, animal) in self.world.animals.iter_mut().enumerate() {
+            if !animal.alive {
+                continue;
+            }
+
+            for idx in self.food_grid.query(animal.position, 0.01) {
+                let food = &mut self.world.foods[idx];
+
+                // Might've been just eaten by another bird
+                if !food.available {
+                    continue;
+                }
+
                 let distance = na::distance(&animal.position, &food.position);
                 if distance <= 0.01 {
-                    animal.satiation += 1;
-                    food.position = rng.gen();
+                    let value = food
+                        .value
+                        .unwrap_or(self.config.food_kinds[food.kind].value);
+
+                    animal.satiation += value;
+                    animal.first_food.get_or_insert(animal.age);
+
+                    if let Some(energy) = &self.config.energy {
+                        animal.energy =
+                            (animal.energy + energy.per_food * value).clamp(0.0, energy.max);
+                    }
+
+                    food.available = false;
+
+                    self.events.push(Event::FoodEaten {
+                        animal: animal_idx,
+                        id: animal.id,
+                        food: idx,
+                    });
+                }
+            }
+
+            if let Some(config) = &self.config.predators {
+                let caught = self.world.predators.iter().any(|predator| {
+                    na::distance(&animal.position, &predator.position) <= config.catch_distance
+                });
+
+                if caught {
+                    animal.alive = false;
+
+                    self.events.push(Event::AnimalDied {
+                        animal: animal_idx,
+                        id: animal.id,
+                        cause: DeathCause::Caught,
+                    });
                 }
             }
         }
     }
 
-    fn evolve(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
-        self.age = 0;
+    fn process_ages(&mut self) {
+        for (idx, animal) in self.world.animals.iter_mut().enumerate() {
+            if !animal.alive {
+                continue;
+            }
 
-        // Transforms `Vec<Animal>` to `Vec<AnimalIndividual>`
-        let current_population: Vec<_> = self
-            .world
-            .animals
-            .iter()
-            .map(AnimalIndividual::from_animal)
-            .collect();
+            animal.age += 1;
 
-        // Evolves this `Vec<AnimalIndividual>`
-        let (evolved_population, stats) = self.ga.evolve(rng, &current_population);
+            if self
+                .config
+                .lifespan
+                .is_some_and(|lifespan| animal.age >= lifespan)
+            {
+                animal.alive = false;
 
-        // Transforms `Vec<AnimalIndividual>` back into `Vec<Animal>`
-        self.world.animals = evolved_population
-            .into_iter()
-            .map(|individual| individual.into_animal(rng))
-            .collect();
+                self.events.push(Event::AnimalDied {
+                    animal: idx,
+                    id: animal.id,
+                    cause: DeathCause::Retired,
+                });
+            }
+        }
+    }
 
-        for food in &mut self.world.foods {
-            food.position = rng.gen();
+    fn process_scent(&mut self) {
+        let (Some(config), Some(scent)) = (&self.config.scent, &mut self.world.scent) else {
+            return;
+        };
+
+        for food in &self.world.foods {
+            if food.available {
+                scent.deposit(food.position, config.food_deposit);
+            }
         }
 
-        stats
+        if config.bird_deposit != 0.0 {
+            for animal in &self.world.animals {
+                if animal.alive {
+                    scent.deposit(animal.position, config.bird_deposit);
+                }
+            }
+        }
+
+        scent.diffuse(config.diffusion, config.decay);
+    }
+
+    fn process_hearing(&mut self) {
+        if !self.config.senses.hearing {
+            return;
+        }
+
+        let sounds = listen(
+            &self.config.hearing,
+            &self.world.animals,
+            &self.world.predators,
+        );
+
+        for (animal, sound) in self.world.animals.iter_mut().zip(sounds) {
+            animal.sound = sound;
+        }
+    }
+
+    fn process_foods(&mut self) {
+        self.world
+            .foods
+            .retain(|food| food.available || !food.is_corpse());
+
+        for food in &mut self.world.foods {
+            if food.available {
+                continue;
+            }
+
+            if let Some(position) = self.food_spawner.spawn(&mut self.rng, self.steps) {
+                food.position = position;
+                food.kind = pick_food_kind(&self.config.food_kinds, &mut self.rng);
+                food.available = true;
+            }
+        }
+    }
+
+    /// Removes dead birds and lets the ones with enough energy reproduce.
+    fn process_reproduction(&mut self) {
+        let Reproduction::Continuous {
+            threshold,
+            cost,
+            max_population,
+        } = self.config.reproduction
+        else {
+            return;
+        };
+
+        // Birds that have stopped for the lack of energy die here
+        let deaths = self.events.len();
+
+        for (idx, animal) in self.world.animals.iter().enumerate() {
+            if animal.alive && animal.energy <= 0.0 {
+                self.events.push(Event::AnimalDied {
+                    animal: idx,
+                    id: animal.id,
+                    cause: DeathCause::Starved,
+                });
+            }
+        }
+
+        self.spawn_corpses(deaths);
+
+        self.world
+            .animals
+            .retain(|animal| animal.alive && animal.energy > 0.0);
+
+        let mutation = ga::GaussianMutation::new(
+            self.config.mutation_chance as ga::Float,
+            self.config.mutation_coeff as ga::Float,
+        );
+
+        let mut population = self.world.animals.len();
+        let mut offspring = Vec::new();
+
+        for (idx, parent) in self.world.animals.iter_mut().enumerate() {
+            if population >= max_population {
+                break;
+            }
+
+            if parent.energy < threshold || !parent.is_evolved() {
+                continue;
+            }
+
+            let mut chromosome = parent.as_chromosome();
+            mutation.mutate(&mut self.rng, &mut chromosome);
+
+            let mut child = Animal::from_chromosome(&self.config, chromosome, &mut self.rng)
+                .with_species(parent.species);
+
+            child.id = AnimalId(self.world.next_id);
+            child.position = parent.position;
+            child.energy = cost;
+            parent.energy -= cost;
+
+            self.world.next_id += 1;
+
+            self.events.push(Event::AnimalBorn {
+                animal: population,
+                id: child.id,
+                parent: idx,
+                parent_id: parent.id,
+            });
+
+            offspring.push(child);
+
+            population += 1;
+        }
+
+        self.world.animals.extend(offspring);
+    }
+
+    /// Drops a corpse for each bird that has died since the `from`-th
+    /// event of this step.
+    fn spawn_corpses(&mut self, from: usize) {
+        let Some(corpses) = &self.config.corpses else {
+            return;
+        };
+
+        for event in &self.events[from..] {
+            let Event::AnimalDied { animal, .. } = event else {
+                continue;
+            };
+
+            let animal = &self.world.animals[*animal];
+            let value = corpses.fraction * animal.satiation;
+
+            if value > 0.0 {
+                self.world.foods.push(Food {
+                    position: animal.position,
+                    kind: corpses.kind,
+                    available: true,
+                    value: Some(value),
+                });
+            }
+        }
+    }
+
+    fn index_foods(&mut self) {
+        self.food_grid.rebuild(
+            self.world
+                .foods
+                .iter()
+                .enumerate()
+                .filter(|(_, food)| food.available)
+                .map(|(idx, food)| (idx, food.position)),
+        );
+    }
+
+    /// Places all foods anew, e.g. at the beginning of a generation.
+    fn scatter_foods(&mut self) {
+        self.world.foods.retain(|food| !food.is_corpse());
+
+        for food in &mut self.world.foods {
+            food.available = false;
+        }
+
+        self.process_foods();
+    }
+
+    /// Adjusts the numbers of foods and predators to the config, after a
+    /// curriculum's stage has changed it.
+    fn follow_curriculum(&mut self) {
+        // Corpses don't count, just like in `World::random()`
+        let foods = self
+            .world
+            .foods
+            .iter()
+            .filter(|food| !food.is_corpse())
+            .count();
+
+        if foods > self.config.foods {
+            let mut excess = foods - self.config.foods;
+
+            self.world.foods.retain(|food| {
+                let keep = food.is_corpse() || excess == 0;
+                excess -= usize::from(!keep);
+                keep
+            });
+        } else {
+            for _ in foods..self.config.foods {
+                self.world.foods.push(Food::random(&mut self.rng));
+            }
+        }
+
+        let predators = self
+            .config
+            .predators
+            .as_ref()
+            .map_or(0, |predators| predators.count);
+
+        self.world.predators.truncate(predators);
+
+        while self.world.predators.len() < predators {
+            self.world.predators.push(Predator::random(&mut self.rng));
+        }
+    }
+
+    /// Evolves each species separately, returning statistics of the
+    /// entire population.
+    fn evolve(&mut self) -> Statistics {
+        self.age = 0;
+        self.best_fitness = 0.0;
+        self.improved_at = 0;
+
+        let generation = self.stats().map_or(0, |stats| stats.generation + 1);
+        self.champion = self.best_animal().map(Animal::as_chromosome);
+        let behavior = self.behavior.finish(&self.world.animals);
+
+        let evolved: Vec<_> = self
+            .world
+            .animals
+            .iter()
+            .filter(|animal| animal.is_evolved())
+            .collect();
+
+        // Without any evolved birds, it's the baselines that get measured
+        let stats = if evolved.is_empty() {
+            let animals: Vec<_> = self.world.animals.iter().collect();
+            Statistics::new(generation, &animals, &*self.fitness, behavior)
+        } else {
+            Statistics::new(generation, &evolved, &*self.fitness, behavior)
+        };
+
+        self.baseline_stats = (0..self.config.baselines.len())
+            .map(|baseline| {
+                let animals: Vec<_> = self
+                    .world
+                    .animals
+                    .iter()
+                    .filter(|animal| animal.baseline == Some(baseline))
+                    .collect();
+
+                Statistics::new(generation, &animals, &*self.fitness, Behavior::default())
+            })
+            .collect();
+
+        // Next generation's birds get born already into the harder world
+        if advance_curriculum(&mut self.config, generation + 1) {
+            self.follow_curriculum();
+        }
+
+        let mut animals = Vec::with_capacity(self.world.animals.len());
+        self.species_stats.clear();
+
+        for (species, ga) in self.ga.iter().enumerate() {
+            // Transforms `Vec<Animal>` to `Vec<AnimalIndividual>`
+            let current_population: Vec<_> = self
+                .world
+                .animals
+                .iter()
+                .filter(|animal| animal.species == species && animal.is_evolved())
+                .map(|animal| AnimalIndividual::from_animal(animal, &*self.fitness))
+                .collect();
+
+            if current_population.is_empty() {
+                continue;
+            }
+
+            // Evolves this `Vec<AnimalIndividual>`
+            let (evolved_population, stats) =
+                ga.evolve_into(&mut self.rng, &current_population, &mut self.recycled);
+
+            self.recycled.extend(
+                current_population
+                    .into_iter()
+                    .map(Individual::into_chromosome),
+            );
+
+            // Transforms `Vec<AnimalIndividual>` back into `Vec<Animal>`
+            animals.extend(evolved_population.into_iter().map(|individual| {
+                individual
+                    .into_animal(&self.config, &mut self.rng)
+                    .with_species(species)
+            }));
+
+            self.species_stats.push(stats);
+        }
+
+        animals.extend(spawn_baselines(&self.config, &mut self.rng));
+
+        // Injected birds stay around, starting anew each generation
+        for animal in &self.world.animals {
+            if animal.injected {
+                let mut animal =
+                    Animal::from_chromosome(&self.config, animal.as_chromosome(), &mut self.rng)
+                        .with_species(animal.species);
+
+                animal.injected = true;
+                animals.push(animal);
+            }
+        }
+
+        self.world.animals = animals;
+        self.world.number_animals(0);
+        self.scatter_foods();
+        self.history.push(stats.clone());
+
+        self.events.push(Event::GenerationEnded {
+            stats: stats.clone(),
+        });
+
+        stats
+    }
+}
+
+/// Genetic algorithm birds (and `Evolution`'s agents) get evolved with.
+type GeneticAlgorithm = ga::GeneticAlgorithm<Selection, Crossover, ga::GaussianMutation>;
+
+/// Creates a genetic algorithm for each species.
+fn genetic_algorithms(config: &Config) -> Vec<GeneticAlgorithm> {
+    (0..config.species)
+        .map(|_| genetic_algorithm(config))
+        .collect()
+}
+
+fn genetic_algorithm(config: &Config) -> GeneticAlgorithm {
+    ga::GeneticAlgorithm::new(
+        config.selection,
+        config.crossover,
+        ga::GaussianMutation::new(
+            config.mutation_chance as ga::Float,
+            config.mutation_coeff as ga::Float,
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn species_evolve_separately() {
+        let config = Config {
+            population: 5,
+            species: 3,
+            generation_length: 10,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        sim.train();
+
+        assert_eq!(sim.species_stats().len(), 3);
+        assert_eq!(sim.stats().unwrap().generation, 0);
+        assert_eq!(sim.train().generation, 1);
+
+        for species in 0..3 {
+            let count = sim
+                .world()
+                .animals()
+                .iter()
+                .filter(|animal| animal.species() == species)
+                .count();
+
+            assert_eq!(count, 5);
+        }
+    }
+
+    #[test]
+    fn lifespan() {
+        let config = Config {
+            population: 3,
+            lifespan: Some(4),
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+
+        for _ in 0..3 {
+            sim.step();
+        }
+
+        assert_eq!(sim.avg_age(), 3.0);
+        assert!(sim.world().animals().iter().all(Animal::is_alive));
+
+        sim.step();
+
+        assert!(sim
+            .world()
+            .animals()
+            .iter()
+            .all(|animal| !animal.is_alive()));
+        assert!(sim.world().animals().iter().all(|animal| animal.age() == 4));
+    }
+
+    #[test]
+    fn step_many() {
+        let config = Config {
+            population: 3,
+            generation_length: 4,
+            ..Config::default()
+        };
+
+        let mut a = Simulation::new(config.clone(), 0);
+        let mut b = Simulation::new(config, 0);
+
+        assert_eq!(a.step_many(3), None);
+        assert!(a.step_many(3).is_some());
+
+        for _ in 0..6 {
+            b.step();
+        }
+
+        assert_eq!(a.save(), b.save());
+    }
+
+    #[test]
+    fn substeps() {
+        let eats = |substeps| {
+            let config = Config {
+                population: 1,
+                foods: 1,
+                speed_min: 0.05,
+                speed_max: 0.05,
+                rotation_accel: 0.0,
+                substeps,
+                ..Config::default()
+            };
+
+            let mut sim = Simulation::new(config, 0);
+            sim.world.animals[0].position = na::Point2::new(0.5, 0.5);
+            sim.world.animals[0].rotation = na::Rotation2::new(0.0);
+            sim.world.foods[0].position = na::Point2::new(0.5, 0.525);
+            sim.step();
+
+            sim.events()
+                .iter()
+                .any(|event| matches!(event, Event::FoodEaten { .. }))
+        };
+
+        // In a single step the bird flies right past the food
+        assert!(!eats(1));
+        assert!(eats(4));
+    }
+
+    #[test]
+    fn baselines() {
+        let config = Config {
+            population: 3,
+            foods: 100,
+            generation_length: 200,
+            baselines: vec![
+                Baseline {
+                    policy: Policy::GreedyFood,
+                    count: 2,
+                },
+                Baseline {
+                    policy: Policy::RandomWalk,
+                    count: 1,
+                },
+            ],
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config.clone(), 0);
+        sim.train();
+
+        let baselines: Vec<_> = sim.world().animals().iter().map(Animal::baseline).collect();
+
+        assert_eq!(baselines, [None, None, None, Some(0), Some(0), Some(1)]);
+        assert_eq!(sim.baseline_stats().len(), 2);
+        assert!(sim.baseline_stats()[0].min_fitness > 0.0);
+
+        // Benchmark mode, with baselines only
+        let mut sim = Simulation::new(
+            Config {
+                population: 0,
+                ..config
+            },
+            0,
+        );
+
+        let stats = sim.train();
+
+        assert_eq!(sim.world().animals().len(), 3);
+        assert!(stats.max_fitness >= sim.baseline_stats()[0].max_fitness);
+    }
+
+    #[test]
+    fn terrain() {
+        let mut terrain = Terrain::uniform(2, 1.0);
+        terrain.set(0, 0, 0.0);
+
+        let config = Config {
+            population: 2,
+            terrain: Some(terrain),
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        sim.world.animals[0].position = na::Point2::new(0.25, 0.25);
+        sim.world.animals[1].position = na::Point2::new(0.75, 0.75);
+        sim.step();
+
+        // First bird is stuck in the impassable corner
+        assert_eq!(sim.world.animals[0].position, na::Point2::new(0.25, 0.25));
+        assert_ne!(sim.world.animals[1].position, na::Point2::new(0.75, 0.75));
+    }
+
+    #[test]
+    fn curriculum() {
+        let config = Config {
+            population: 2,
+            foods: 3,
+            generation_length: 10,
+            predators: Some(PredatorConfig::default()),
+            curriculum: vec![Stage {
+                generation: 1,
+                foods: Some(1),
+                predators: Some(3),
+                generation_length: Some(5),
+                ..Stage::default()
+            }],
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config.clone(), 0);
+        sim.train();
+
+        assert_eq!(sim.config().foods, 1);
+        assert_eq!(sim.config().generation_length, 5);
+        assert_eq!(sim.world().foods().len(), 1);
+        assert_eq!(sim.world().predators().len(), 3);
+
+        // Loaded simulation picks up where the curriculum has left off
+        let sim = Simulation::load(config, &sim.save()).unwrap();
+
+        assert_eq!(sim.config().foods, 1);
+        assert_eq!(sim.world().predators().len(), 3);
+    }
+
+    #[test]
+    fn corpses() {
+        let config = Config {
+            population: 2,
+            foods: 3,
+            lifespan: Some(1),
+            corpses: Some(Corpses::default()),
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        sim.world.animals[0].satiation = 2.0;
+        sim.step();
+
+        // Only the bird that's eaten anything leaves something behind
+        let corpses: Vec<_> = sim
+            .world()
+            .foods()
+            .iter()
+            .filter(|food| food.is_corpse())
+            .collect();
+
+        assert_eq!(corpses.len(), 1);
+        assert_eq!(corpses[0].value, Some(1.0));
+        assert_eq!(corpses[0].position, sim.world.animals[0].position);
+    }
+
+    #[test]
+    fn config_validation() {
+        assert_eq!(Config::default().validate(), Ok(()));
+
+        let err = Config {
+            eye_cells: 0,
+            ..Config::default()
+        }
+        .validate()
+        .unwrap_err();
+
+        assert_eq!(err.to_string(), "`eye_cells` has to be positive");
+
+        let config = Config {
+            curriculum: vec![Stage {
+                generation: 1,
+                speed_max: Some(0.0),
+                ..Stage::default()
+            }],
+            ..Config::default()
+        };
+
+        assert_eq!(config.validate().unwrap_err().field, "curriculum");
+
+        let config = Config {
+            mutation_chance: 1.5,
+            ..Config::default()
+        };
+
+        assert_eq!(config.validate().unwrap_err().field, "mutation_chance");
+    }
+
+    /// Checks that `validate()` rejects `config` because of `field`, since
+    /// its steps would panic otherwise.
+    fn assert_invalid(config: Config, field: &str) {
+        assert_eq!(config.validate().map_err(|err| err.field), Err(field));
+    }
+
+    #[test]
+    fn config_validation_of_speed_accel() {
+        for speed_accel in [-0.1, f32::NAN] {
+            let config = Config {
+                speed_accel,
+                ..Config::default()
+            };
+
+            assert_invalid(config, "speed_accel");
+        }
+    }
+
+    #[test]
+    fn config_validation_of_rotation_accel() {
+        let config = Config {
+            rotation_accel: -0.1,
+            ..Config::default()
+        };
+
+        assert_invalid(config, "rotation_accel");
+    }
+
+    #[test]
+    fn config_validation_of_max_energy() {
+        let config = Config {
+            energy: Some(EnergyConfig {
+                max: -1.0,
+                ..EnergyConfig::default()
+            }),
+            ..Config::default()
+        };
+
+        assert_invalid(config, "energy");
+    }
+
+    #[test]
+    fn config_validation_of_food_weights() {
+        let kind = FoodKind {
+            value: 1.0,
+            weight: 0.0,
+        };
+
+        let config = Config {
+            food_kinds: vec![kind.clone(), kind],
+            ..Config::default()
+        };
+
+        assert_invalid(config, "food_kinds");
+    }
+
+    #[test]
+    fn config_validation_of_predators() {
+        let config = Config {
+            predators: Some(PredatorConfig {
+                rotation_accel: -0.1,
+                ..PredatorConfig::default()
+            }),
+            ..Config::default()
+        };
+
+        assert_invalid(config, "predators");
+    }
+
+    #[test]
+    fn continuous_reproduction() {
+        let config = Config {
+            population: 3,
+            generation_length: 2,
+            reproduction: Reproduction::Continuous {
+                threshold: 0.5,
+                cost: 0.25,
+                max_population: 5,
+            },
+            energy: Some(EnergyConfig::default()),
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+
+        assert!(sim.step().is_none());
+        assert_eq!(sim.world().animals().len(), 5);
+
+        let children: Vec<_> = sim.world().animals()[3..].iter().collect();
+        assert!(children.iter().all(|child| child.age() == 0));
+        assert!(children.iter().all(|child| child.energy() == 0.25));
+
+        // No generations in here, so the population doesn't get reset
+        for _ in 0..5 {
+            assert!(sim.step().is_none());
+        }
+
+        assert_eq!(sim.world().animals().len(), 5);
+        assert!(sim.world().animals().iter().any(|animal| animal.age() > 2));
+    }
+
+    #[test]
+    fn continuous_reproduction_starvation() {
+        let config = Config {
+            population: 3,
+            reproduction: Reproduction::Continuous {
+                threshold: 0.5,
+                cost: 0.25,
+                max_population: 5,
+            },
+            energy: Some(EnergyConfig {
+                initial: 0.1,
+                idle_cost: 1.0,
+                ..EnergyConfig::default()
+            }),
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        sim.step();
+
+        assert!(sim.world().animals().is_empty());
+    }
+
+    #[test]
+    fn same_seed_same_run() {
+        let config = Config {
+            population: 5,
+            generation_length: 50,
+            ..Config::default()
+        };
+
+        let mut a = Simulation::new(config.clone(), 1234);
+        let mut b = Simulation::new(config, a.seed());
+
+        assert_eq!(a.train(), b.train());
+
+        for _ in 0..10 {
+            a.step();
+            b.step();
+        }
+
+        for (a, b) in a.world().animals().iter().zip(b.world().animals()) {
+            assert_eq!(a.position(), b.position());
+            assert_eq!(a.rotation(), b.rotation());
+        }
+    }
+
+    #[test]
+    fn from_entropy() {
+        let config = Config {
+            population: 3,
+            ..Config::default()
+        };
+
+        let mut a = Simulation::from_entropy(config.clone());
+        let mut b = Simulation::new(config, a.seed());
+
+        a.step();
+        b.step();
+
+        assert_eq!(a.save(), b.save());
+    }
+
+    #[test]
+    fn generation_end() {
+        let config = Config {
+            population: 3,
+            foods: 1,
+            generation_length: 1000,
+            generation_end: GenerationEnd {
+                when_foods_eaten: true,
+                ..GenerationEnd::default()
+            },
+            ..Config::default()
+        };
+
+        #[derive(Debug)]
+        struct NeverSpawner;
+
+        impl FoodSpawner for NeverSpawner {
+            fn spawn(&mut self, _: &mut dyn RngCore, _: usize) -> Option<na::Point2<f32>> {
+                None
+            }
+        }
+
+        let mut sim = Simulation::new(config, 0);
+        sim.set_food_spawner(Box::new(NeverSpawner));
+        sim.world.foods[0].available = false;
+
+        assert!(sim.step().is_some());
+
+        // Custom policies get plugged in, too
+        sim.set_generation_policy(Box::new(|_: &Config, progress: &GenerationProgress| {
+            progress.age >= 5
+        }));
+
+        sim.train();
+        assert_eq!(sim.steps, 6);
+    }
+
+    #[test]
+    fn seed_with() {
+        let config = Config {
+            population: 4,
+            generation_length: 50,
+            ..Config::default()
+        };
+
+        let mut a = Simulation::new(config.clone(), 0);
+        assert!(a.champion().is_none());
+
+        a.train();
+
+        let champion = a.champion().unwrap().clone();
+        let genes: Vec<_> = champion.iter().copied().collect();
+
+        let mut b = Simulation::new(config, 1);
+        assert_eq!(b.seed_with(&[champion]), Ok(()));
+
+        assert!(b.world().animals().iter().all(|animal| animal
+            .as_chromosome()
+            .iter()
+            .copied()
+            .eq(genes.iter().copied())));
+
+        let ids: Vec<_> = b
+            .world()
+            .animals()
+            .iter()
+            .map(|animal| animal.id())
+            .collect();
+
+        assert_eq!(ids, [AnimalId(4), AnimalId(5), AnimalId(6), AnimalId(7)]);
+        assert_eq!(b.world().find_animal(AnimalId(6)), Some(2));
+
+        let other = Simulation::new(
+            Config {
+                eye_cells: 3,
+                ..Config::default()
+            },
+            0,
+        );
+
+        assert_eq!(
+            b.seed_with(&[other.world().animals()[0].as_chromosome()]),
+            Err(SnapshotError::ConfigMismatch)
+        );
+
+        assert!(b.best_animal().is_some());
+    }
+
+    #[test]
+    fn diversity() {
+        let config = Config {
+            population: 4,
+            generation_length: 10,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        assert!(sim.diversity() > 0.1);
+
+        let chromosome = sim.world().animals()[0].as_chromosome();
+        sim.seed_with(&[chromosome]).unwrap();
+
+        assert!(sim.diversity() < 1e-3);
+    }
+
+    #[test]
+    fn inject() {
+        let config = Config {
+            population: 3,
+            generation_length: 10,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        let chromosome = sim.world().animals()[0].as_chromosome();
+
+        assert_eq!(sim.inject(chromosome), Ok(3));
+        assert!(sim.world().animals()[3].is_injected());
+
+        sim.train();
+
+        let injected: Vec<_> = sim
+            .world()
+            .animals()
+            .iter()
+            .map(Animal::is_injected)
+            .collect();
+
+        assert_eq!(injected, [false, false, false, true]);
+
+        assert_eq!(
+            sim.inject(ga::Chromosome::from_iter([1.0])),
+            Err(SnapshotError::ConfigMismatch)
+        );
+    }
+
+    #[test]
+    fn set_mutation() {
+        let config = Config {
+            population: 3,
+            generation_length: 10,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        sim.set_mutation(0.0, 0.0);
+        sim.train();
+
+        // Without mutations, children get only their parents' genes
+        let genes = |sim: &Simulation| -> Vec<Vec<f32>> {
+            sim.world()
+                .animals()
+                .iter()
+                .map(|animal| animal.as_chromosome().iter().copied().collect())
+                .collect()
+        };
+
+        let before = genes(&sim);
+        sim.train();
+
+        for chromosome in genes(&sim) {
+            for (idx, gene) in chromosome.iter().enumerate() {
+                assert!(before.iter().any(|parent| parent[idx] == *gene));
+            }
+        }
+
+        assert_eq!(sim.config().mutation_chance, 0.0);
+    }
+
+    #[test]
+    fn add_food_and_animal() {
+        let config = Config {
+            population: 2,
+            foods: 1,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+
+        assert_eq!(sim.add_food(na::Point2::new(0.5, 0.5), 0), 1);
+        assert_eq!(sim.add_animal(na::Point2::new(0.5, 0.5)), 2);
+
+        // Bird lands right on the food and eats it
+        sim.step();
+
+        assert_eq!(sim.world().animals()[2].id(), AnimalId(2));
+        assert!(sim.world().animals()[2].satiation > 0.0);
+    }
+
+    #[test]
+    fn animal_ids() {
+        let config = Config {
+            population: 3,
+            generation_length: 10,
+            lifespan: Some(5),
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        let ids = |sim: &Simulation| -> Vec<_> {
+            sim.world()
+                .animals()
+                .iter()
+                .map(|animal| animal.id().0)
+                .collect()
+        };
+
+        assert_eq!(ids(&sim), [0, 1, 2]);
+
+        sim.step_many(5);
+        assert_eq!(ids(&sim), [0, 1, 2]);
+        assert_eq!(sim.world().find_animal(AnimalId(1)), Some(1));
+
+        // Next generation consists of new birds
+        sim.train();
+        assert_eq!(ids(&sim), [3, 4, 5]);
+
+        let snapshot = sim.save();
+        let mut sim = Simulation::load(sim.config().clone(), &snapshot).unwrap();
+        sim.step_many(5);
+
+        let retired: Vec<_> = sim
+            .events()
+            .iter()
+            .filter_map(|event| match event {
+                Event::AnimalDied { id, .. } => Some(id.0),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(retired, [3, 4, 5]);
+    }
+
+    #[test]
+    fn train_generations() {
+        let config = Config {
+            population: 5,
+            generation_length: 10,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config.clone(), 0);
+        let history = sim.train_generations(3);
+        let generations: Vec<_> = history.iter().map(|stats| stats.generation).collect();
+
+        assert_eq!(generations, [0, 1, 2]);
+        assert_eq!(sim.stats(), history.last());
+        assert_eq!(sim.history(), history);
+
+        let csv = sim.export_csv();
+        let mut rows = csv.lines();
+
+        assert!(rows.next().unwrap().starts_with("generation,min_fitness,"));
+        assert!(rows.next().unwrap().starts_with("0,"));
+        assert_eq!(rows.count(), 2);
+
+        let json = Json::parse(&sim.export_json()).unwrap();
+        assert_eq!(json.as_array().map(|stats| stats.len()), Some(3));
+
+        let mut sim = Simulation::new(config, 0);
+        let until = sim.train_until(|stats| stats.generation == 2);
+
+        assert_eq!(until, history);
+    }
+
+    #[test]
+    fn custom_fitness() {
+        let config = Config {
+            population: 5,
+            generation_length: 10,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        sim.set_fitness(Box::new(|animal: &AnimalSummary| animal.age as f32));
+
+        let stats = sim.train();
+
+        assert_eq!(stats.min_fitness, 10.0);
+        assert_eq!(stats.max_fitness, 10.0);
+    }
+
+    #[test]
+    fn events() {
+        use std::sync::{Arc, Mutex};
+
+        let config = Config {
+            population: 3,
+            foods: 2000,
+            generation_length: 30,
+            lifespan: Some(20),
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        let events = Arc::new(Mutex::new(Vec::new()));
+
+        sim.subscribe(Box::new({
+            let events = events.clone();
+            move |event| events.lock().unwrap().push(event.clone())
+        }));
+
+        let stats = sim.train();
+        let events = events.lock().unwrap();
+
+        let retired = events
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    Event::AnimalDied {
+                        cause: DeathCause::Retired,
+                        ..
+                    }
+                )
+            })
+            .count();
+
+        assert_eq!(retired, 3);
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, Event::FoodEaten { .. })));
+        assert_eq!(events.last(), Some(&Event::GenerationEnded { stats }));
+
+        // Only the last step's events are kept in here
+        assert_eq!(sim.events(), &events[events.len() - 1..]);
+    }
+
+    #[test]
+    fn senses() {
+        let config = Config {
+            population: 2,
+            generation_length: 5,
+            senses: Senses {
+                speed: true,
+                compass: true,
+                bias: true,
+                ..Senses::default()
+            },
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        let brain = &sim.world().animals()[0].brain.nn;
+
+        assert_eq!(brain.input_size(), 9 + 4);
+        assert_eq!(brain.input_index("compass_y"), Some(11));
+
+        sim.train();
+    }
+
+    #[test]
+    fn traits() {
+        let config = Config {
+            population: 4,
+            generation_length: 5,
+            traits: Traits {
+                speed_max: Some(Bounds::new(0.002, 0.01)),
+                eye_fov_range: Some(Bounds::new(0.1, 0.5)),
+                ..Traits::default()
+            },
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        sim.train();
+
+        for animal in sim.world().animals() {
+            let speed_max = animal.body().speed_max();
+
+            assert!((0.002..=0.01).contains(&speed_max));
+            assert!((0.1..=0.5).contains(&animal.eye().fov_range()));
+            assert!(animal.speed <= speed_max);
+            assert_eq!(animal.body().genes.len(), 2);
+        }
     }
 }
@@ -0,0 +1,121 @@
+use crate::*;
+
+/// Static shape that birds (and predators) can't fly through nor see
+/// through.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Obstacle {
+    Circle {
+        center: na::Point2<f32>,
+        radius: f32,
+    },
+
+    /// Axis-aligned rectangle spanning from `min` to `max`
+    Rect {
+        min: na::Point2<f32>,
+        max: na::Point2<f32>,
+    },
+}
+
+impl Obstacle {
+    pub fn contains(&self, point: na::Point2<f32>) -> bool {
+        match self {
+            Obstacle::Circle { center, radius } => na::distance(center, &point) <= *radius,
+            Obstacle::Rect { min, max } => {
+                (min.x..=max.x).contains(&point.x) && (min.y..=max.y).contains(&point.y)
+            }
+        }
+    }
+
+    /// Whether the segment from `from` to `to` crosses this obstacle.
+    pub fn blocks(&self, from: na::Point2<f32>, to: na::Point2<f32>) -> bool {
+        let dir = to - from;
+
+        match self {
+            Obstacle::Circle { center, radius } => {
+                let len2 = dir.norm_squared();
+
+                let t = if len2 > 0.0 {
+                    ((center - from).dot(&dir) / len2).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+
+                na::distance(center, &(from + dir * t)) <= *radius
+            }
+
+            // Liang-Barsky clipping
+            Obstacle::Rect { min, max } => {
+                let mut t0: f32 = 0.0;
+                let mut t1: f32 = 1.0;
+
+                for axis in 0..2 {
+                    if dir[axis] == 0.0 {
+                        if from[axis] < min[axis] || from[axis] > max[axis] {
+                            return false;
+                        }
+
+                        continue;
+                    }
+
+                    let a = (min[axis] - from[axis]) / dir[axis];
+                    let b = (max[axis] - from[axis]) / dir[axis];
+
+                    t0 = t0.max(a.min(b));
+                    t1 = t1.min(a.max(b));
+
+                    if t0 > t1 {
+                        return false;
+                    }
+                }
+
+                true
+            }
+        }
+    }
+}
+
+/// Whether something can move from `from` to `to` without bumping into
+/// any of the `obstacles`.
+pub(crate) fn passable(obstacles: &[Obstacle], from: na::Point2<f32>, to: na::Point2<f32>) -> bool {
+    !obstacles.iter().any(|obstacle| obstacle.blocks(from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: f32, y: f32) -> na::Point2<f32> {
+        na::Point2::new(x, y)
+    }
+
+    #[test]
+    fn circle() {
+        let circle = Obstacle::Circle {
+            center: p(0.5, 0.5),
+            radius: 0.1,
+        };
+
+        assert!(circle.contains(p(0.55, 0.5)));
+        assert!(!circle.contains(p(0.7, 0.5)));
+
+        assert!(circle.blocks(p(0.2, 0.5), p(0.8, 0.55)));
+        assert!(!circle.blocks(p(0.2, 0.7), p(0.8, 0.7)));
+        assert!(!circle.blocks(p(0.2, 0.5), p(0.3, 0.5)));
+    }
+
+    #[test]
+    fn rect() {
+        let rect = Obstacle::Rect {
+            min: p(0.4, 0.4),
+            max: p(0.6, 0.5),
+        };
+
+        assert!(rect.contains(p(0.5, 0.45)));
+        assert!(!rect.contains(p(0.5, 0.55)));
+
+        assert!(rect.blocks(p(0.5, 0.0), p(0.5, 1.0)));
+        assert!(rect.blocks(p(0.0, 0.0), p(1.0, 1.0)));
+        assert!(!rect.blocks(p(0.0, 0.6), p(1.0, 0.6)));
+        assert!(!rect.blocks(p(0.5, 0.0), p(0.5, 0.3)));
+    }
+}
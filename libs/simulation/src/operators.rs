@@ -0,0 +1,115 @@
+use crate::*;
+
+/// How parents of the next generation get picked.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Selection {
+    /// Chance of being picked is proportional to fitness
+    #[default]
+    RouletteWheel,
+
+    /// Fittest out of `size` birds drawn at random gets picked
+    Tournament { size: usize },
+}
+
+impl ga::SelectionMethod for Selection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: ga::Individual,
+    {
+        match *self {
+            Selection::RouletteWheel => ga::RouletteWheelSelection.select(rng, population),
+
+            Selection::Tournament { size } => {
+                ga::TournamentSelection::new(size).select(rng, population)
+            }
+        }
+    }
+
+    /// Keeps the roulette wheel's precomputed weights around.
+    fn selector<'a, I>(&'a self, population: &'a [I]) -> impl FnMut(&mut dyn RngCore) -> &'a I + 'a
+    where
+        I: ga::Individual,
+    {
+        let mut roulette_wheel = (*self == Selection::RouletteWheel)
+            .then(|| ga::RouletteWheelSelection.selector(population));
+
+        move |rng| match &mut roulette_wheel {
+            Some(select) => select(rng),
+            None => self.select(rng, population),
+        }
+    }
+}
+
+/// How parents' genes get mixed into their child's.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Crossover {
+    /// Each gene comes from either of the parents, see
+    /// `ga::UniformCrossover`
+    #[default]
+    Uniform,
+
+    /// Genes lie in between the parents', see `ga::BlendCrossover`
+    Blend,
+}
+
+impl ga::CrossoverMethod for Crossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &ga::Chromosome,
+        parent_b: &ga::Chromosome,
+    ) -> ga::Chromosome {
+        match self {
+            Crossover::Uniform => ga::UniformCrossover.crossover(rng, parent_a, parent_b),
+            Crossover::Blend => ga::BlendCrossover.crossover(rng, parent_a, parent_b),
+        }
+    }
+
+    fn crossover_into(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &ga::Chromosome,
+        parent_b: &ga::Chromosome,
+        child: &mut ga::Chromosome,
+    ) {
+        match self {
+            Crossover::Uniform => {
+                ga::UniformCrossover.crossover_into(rng, parent_a, parent_b, child)
+            }
+
+            Crossover::Blend => ga::BlendCrossover.crossover_into(rng, parent_a, parent_b, child),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators() {
+        let config = Config {
+            population: 4,
+            generation_length: 10,
+            selection: Selection::Tournament { size: 2 },
+            crossover: Crossover::Blend,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config.clone(), 0);
+        let stats = sim.train();
+
+        assert_eq!(stats.generation, 0);
+        assert_eq!(sim.world().animals().len(), 4);
+
+        assert_eq!(
+            Config {
+                selection: Selection::Tournament { size: 0 },
+                ..config
+            }
+            .validate()
+            .map_err(|err| err.field),
+            Err("selection")
+        );
+    }
+}
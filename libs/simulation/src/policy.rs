@@ -0,0 +1,124 @@
+use crate::*;
+
+/// Hand-coded way of flying, used instead of a brain by baseline birds.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Policy {
+    /// Changes speed and direction at random
+    #[default]
+    RandomWalk,
+
+    /// Speeds up and turns towards the nearest available food, seen or not
+    GreedyFood,
+}
+
+/// Group of birds following the same policy, mixed into the world to
+/// compare evolved brains against.
+///
+/// Baseline birds don't evolve - each generation starts with a fresh
+/// batch of them - and they are left out of the simulation's statistics
+/// (see `Simulation::baseline_stats()`).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Baseline {
+    pub policy: Policy,
+    pub count: usize,
+}
+
+impl Policy {
+    /// Returns change of speed and rotation - just like a brain would.
+    pub(crate) fn decide(
+        &self,
+        animal: &Animal,
+        config: &Config,
+        world: &WorldView,
+        rng: &mut dyn RngCore,
+    ) -> [f32; 2] {
+        match self {
+            Policy::RandomWalk => [
+                rng.gen_range(-config.speed_accel..=config.speed_accel),
+                rng.gen_range(-animal.body.rotation_accel..=animal.body.rotation_accel),
+            ],
+
+            Policy::GreedyFood => {
+                let nearest = world
+                    .foods
+                    .iter()
+                    .filter(|food| food.available)
+                    .map(|food| food.position - animal.position)
+                    .min_by(|a, b| a.norm_squared().total_cmp(&b.norm_squared()));
+
+                let Some(offset) = nearest else {
+                    return [config.speed_accel, 0.0];
+                };
+
+                // Birds fly along their local y axis
+                let angle = f32::atan2(-offset.x, offset.y);
+                let turn = na::wrap(angle - animal.rotation.angle(), -PI, PI);
+
+                [config.speed_accel, turn]
+            }
+        }
+    }
+}
+
+/// Spawns a fresh batch of birds for each of `Config::baselines`.
+pub(crate) fn spawn_baselines(config: &Config, rng: &mut dyn RngCore) -> Vec<Animal> {
+    let mut animals = Vec::new();
+
+    for (idx, baseline) in config.baselines.iter().enumerate() {
+        for _ in 0..baseline.count {
+            let mut animal = Animal::random(config, rng);
+            animal.baseline = Some(idx);
+            animals.push(animal);
+        }
+    }
+
+    animals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn greedy_food() {
+        let config = Config::default();
+
+        let mut animal = Animal::random(&config, &mut StepRng::new(0, 1));
+        animal.position = na::Point2::new(0.5, 0.5);
+        animal.rotation = na::Rotation2::new(0.0);
+
+        let foods = [
+            Food {
+                position: na::Point2::new(0.6, 0.5),
+                kind: 0,
+                available: true,
+                value: None,
+            },
+            Food {
+                position: na::Point2::new(0.45, 0.5),
+                kind: 0,
+                available: false,
+                value: None,
+            },
+        ];
+
+        let world = WorldView {
+            foods: &foods,
+            food_grid: &Grid::new(1.0),
+            predators: &[],
+            obstacles: &[],
+            scent: None,
+            light: 1.0,
+            vision: 1.0,
+        };
+
+        let [speed, turn] =
+            Policy::GreedyFood.decide(&animal, &config, &world, &mut StepRng::new(0, 1));
+
+        // Eaten food gets ignored, so it's right, not left
+        assert_relative_eq!(speed, config.speed_accel);
+        assert_relative_eq!(turn, -FRAC_PI_2);
+    }
+}
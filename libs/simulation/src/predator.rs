@@ -0,0 +1,74 @@
+use crate::*;
+
+/// Scripted hunter: flies towards the nearest bird it can see and
+/// catches it on contact.
+#[derive(Debug)]
+pub struct Predator {
+    pub(crate) position: na::Point2<f32>,
+    pub(crate) rotation: na::Rotation2<f32>,
+}
+
+impl Predator {
+    pub fn random(rng: &mut dyn RngCore) -> Self {
+        Self {
+            position: rng.gen(),
+            rotation: rng.gen(),
+        }
+    }
+
+    pub fn position(&self) -> na::Point2<f32> {
+        self.position
+    }
+
+    pub fn rotation(&self) -> na::Rotation2<f32> {
+        self.rotation
+    }
+
+    /// Turns towards the nearest living bird within `config.sight`.
+    pub(crate) fn hunt(&mut self, config: &PredatorConfig, animals: &[Animal]) {
+        let target = animals
+            .iter()
+            .filter(|animal| animal.alive)
+            .map(|animal| animal.position - self.position)
+            .filter(|vec| vec.norm() <= config.sight)
+            .min_by(|a, b| a.norm().total_cmp(&b.norm()));
+
+        let Some(target) = target else {
+            return;
+        };
+
+        let angle = na::Rotation2::rotation_between(&na::Vector2::y(), &target).angle();
+        let turn = na::wrap(angle - self.rotation.angle(), -PI, PI);
+        let turn = turn.clamp(-config.rotation_accel, config.rotation_accel);
+
+        self.rotation = na::Rotation2::new(self.rotation.angle() + turn);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn hunt() {
+        let config = PredatorConfig {
+            rotation_accel: PI,
+            ..PredatorConfig::default()
+        };
+
+        let mut animal = Animal::random(&Config::default(), &mut StepRng::new(0, 1));
+        animal.position = na::Point2::new(0.6, 0.5);
+
+        let mut predator = Predator {
+            position: na::Point2::new(0.5, 0.5),
+            rotation: na::Rotation2::new(0.0),
+        };
+
+        predator.hunt(&config, &[animal]);
+
+        // Rotation of zero faces +y, so +x lies a quarter turn clockwise
+        assert_relative_eq!(predator.rotation.angle(), -FRAC_PI_2, epsilon = 1e-6);
+    }
+}
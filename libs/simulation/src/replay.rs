@@ -0,0 +1,481 @@
+use crate::snapshot::{array, number, point_to_json};
+use crate::*;
+
+const VERSION: u64 = 1;
+
+/// Where something was at a given step.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pose {
+    pub position: na::Point2<f32>,
+    pub rotation: f32,
+}
+
+impl Pose {
+    fn lerp(&self, other: &Pose, t: f32) -> Pose {
+        // If something has moved by more than half of the world, it must've
+        // wrapped around - and we don't want to draw it flying across
+        let offset = other.position - self.position;
+
+        if offset.x.abs() > 0.5 || offset.y.abs() > 0.5 {
+            return *self;
+        }
+
+        let turn = na::wrap(other.rotation - self.rotation, -PI, PI);
+
+        Pose {
+            position: self.position + offset * t,
+            rotation: self.rotation + turn * t,
+        }
+    }
+}
+
+/// Recorded state of the world.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Frame {
+    /// `None` for dead birds
+    pub animals: Vec<Option<Pose>>,
+
+    /// `None` for eaten foods
+    pub foods: Vec<Option<na::Point2<f32>>>,
+
+    pub predators: Vec<Pose>,
+}
+
+impl Frame {
+    pub fn from_world(world: &World) -> Self {
+        Self {
+            animals: world
+                .animals
+                .iter()
+                .map(|animal| {
+                    animal.alive.then(|| Pose {
+                        position: animal.position,
+                        rotation: animal.rotation.angle(),
+                    })
+                })
+                .collect(),
+            foods: world
+                .foods
+                .iter()
+                .map(|food| food.available.then_some(food.position))
+                .collect(),
+            predators: world
+                .predators
+                .iter()
+                .map(|predator| Pose {
+                    position: predator.position,
+                    rotation: predator.rotation.angle(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Blends this frame with `other`; things that have appeared,
+    /// disappeared or teleported in between are taken from this frame.
+    fn lerp(&self, other: &Frame, t: f32) -> Frame {
+        fn lerp_all<T: Clone>(a: &[T], b: &[T], f: impl Fn(&T, &T) -> T) -> Vec<T> {
+            if a.len() == b.len() {
+                a.iter().zip(b).map(|(a, b)| f(a, b)).collect()
+            } else {
+                a.to_vec()
+            }
+        }
+
+        Frame {
+            animals: lerp_all(&self.animals, &other.animals, |a, b| match (a, b) {
+                (Some(a), Some(b)) => Some(a.lerp(b, t)),
+                _ => *a,
+            }),
+            foods: self.foods.clone(),
+            predators: lerp_all(&self.predators, &other.predators, |a, b| a.lerp(b, t)),
+        }
+    }
+}
+
+/// Captures frames of a running simulation, see `Recorder::record()`.
+#[derive(Clone, Debug)]
+pub struct Recorder {
+    interval: usize,
+    recorded: usize,
+    keyframes: Vec<Frame>,
+}
+
+impl Recorder {
+    /// Creates a recorder keeping every frame.
+    pub fn new() -> Self {
+        Self::keyframed(1)
+    }
+
+    /// Creates a recorder keeping only every `interval`-th frame - the
+    /// ones in between get interpolated during playback.
+    pub fn keyframed(interval: usize) -> Self {
+        assert!(interval > 0);
+
+        Self {
+            interval,
+            recorded: 0,
+            keyframes: Vec::new(),
+        }
+    }
+
+    /// Captures the current state of the world; supposed to be called
+    /// after each step.
+    pub fn record(&mut self, world: &World) {
+        if self.recorded.is_multiple_of(self.interval) {
+            self.keyframes.push(Frame::from_world(world));
+        }
+
+        self.recorded += 1;
+    }
+
+    pub fn finish(self) -> Replay {
+        Replay {
+            interval: self.interval,
+            keyframes: self.keyframes,
+        }
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Recorded run, ready to be played back.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    interval: usize,
+    keyframes: Vec<Frame>,
+}
+
+impl Replay {
+    /// Returns the number of frames that can be played back.
+    pub fn len(&self) -> usize {
+        self.keyframes
+            .len()
+            .saturating_sub(1)
+            .saturating_mul(self.interval)
+            + usize::from(!self.keyframes.is_empty())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keyframes.is_empty()
+    }
+
+    /// Returns the frame recorded at given step (counted since the
+    /// recording has started).
+    pub fn frame(&self, step: usize) -> Option<Frame> {
+        let keyframe = step / self.interval;
+        let offset = step % self.interval;
+
+        if offset == 0 {
+            return self.keyframes.get(keyframe).cloned();
+        }
+
+        let a = self.keyframes.get(keyframe)?;
+        let b = self.keyframes.get(keyframe + 1)?;
+
+        Some(a.lerp(b, offset as f32 / self.interval as f32))
+    }
+
+    /// Plays the replay back, frame by frame.
+    pub fn frames(&self) -> impl Iterator<Item = Frame> + '_ {
+        (0..self.len()).map(|step| self.frame(step).unwrap())
+    }
+
+    pub fn save(&self) -> String {
+        self.to_json().to_string()
+    }
+
+    pub fn load(replay: &str) -> Result<Self, SnapshotError> {
+        let json = Json::parse(replay).map_err(|offset| SnapshotError::Syntax { offset })?;
+
+        Self::from_json(&json)
+    }
+
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("version", Json::number(VERSION)),
+            ("interval", Json::number(self.interval)),
+            (
+                "frames",
+                Json::Array(self.keyframes.iter().map(frame_to_json).collect()),
+            ),
+        ])
+    }
+
+    fn from_json(json: &Json) -> Result<Self, SnapshotError> {
+        let version: u64 = number(json, "version")?;
+
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion { version });
+        }
+
+        let interval = number(json, "interval")?;
+
+        if interval == 0 {
+            return Err(SnapshotError::Malformed { field: "interval" });
+        }
+
+        Ok(Self {
+            interval,
+            keyframes: array(json, "frames")?
+                .iter()
+                .map(frame_from_json)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// Replays of a couple of generations picked out of a long training - e.g.
+/// its first, best and last one - to be watched afterwards.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Highlights {
+    pub clips: Vec<Clip>,
+}
+
+/// Replay of a single generation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clip {
+    /// Why the generation got picked - e.g. `["first", "best"]`
+    pub labels: Vec<String>,
+
+    pub generation: usize,
+    pub replay: Replay,
+}
+
+impl Highlights {
+    pub fn save(&self) -> String {
+        let clips = self.clips.iter().map(|clip| {
+            Json::object([
+                (
+                    "labels",
+                    Json::Array(clip.labels.iter().cloned().map(Json::String).collect()),
+                ),
+                ("generation", Json::number(clip.generation)),
+                ("replay", clip.replay.to_json()),
+            ])
+        });
+
+        Json::object([
+            ("version", Json::number(VERSION)),
+            ("clips", Json::Array(clips.collect())),
+        ])
+        .to_string()
+    }
+
+    pub fn load(highlights: &str) -> Result<Self, SnapshotError> {
+        let json = Json::parse(highlights).map_err(|offset| SnapshotError::Syntax { offset })?;
+
+        let version: u64 = number(&json, "version")?;
+
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion { version });
+        }
+
+        let clips = array(&json, "clips")?
+            .iter()
+            .map(|clip| {
+                let labels = array(clip, "labels")?
+                    .iter()
+                    .map(|label| {
+                        label
+                            .as_str()
+                            .map(String::from)
+                            .ok_or(SnapshotError::Malformed { field: "labels" })
+                    })
+                    .collect::<Result<_, _>>()?;
+
+                let replay = clip
+                    .get("replay")
+                    .ok_or(SnapshotError::Malformed { field: "replay" })?;
+
+                Ok(Clip {
+                    labels,
+                    generation: number(clip, "generation")?,
+                    replay: Replay::from_json(replay)?,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { clips })
+    }
+}
+
+fn frame_to_json(frame: &Frame) -> Json {
+    let or_null = |json: Option<Json>| json.unwrap_or(Json::Null);
+
+    Json::object([
+        (
+            "animals",
+            Json::Array(
+                frame
+                    .animals
+                    .iter()
+                    .map(|pose| or_null(pose.as_ref().map(pose_to_json)))
+                    .collect(),
+            ),
+        ),
+        (
+            "foods",
+            Json::Array(
+                frame
+                    .foods
+                    .iter()
+                    .map(|&food| or_null(food.map(point_to_json)))
+                    .collect(),
+            ),
+        ),
+        (
+            "predators",
+            Json::Array(frame.predators.iter().map(pose_to_json).collect()),
+        ),
+    ])
+}
+
+fn frame_from_json(json: &Json) -> Result<Frame, SnapshotError> {
+    Ok(Frame {
+        animals: array(json, "animals")?
+            .iter()
+            .map(|pose| match pose {
+                Json::Null => Ok(None),
+                pose => pose_from_json(pose).map(Some),
+            })
+            .collect::<Result<_, _>>()?,
+        foods: array(json, "foods")?
+            .iter()
+            .map(|food| match food {
+                Json::Null => Ok(None),
+                food => point_from_json(food).map(Some),
+            })
+            .collect::<Result<_, _>>()?,
+        predators: array(json, "predators")?
+            .iter()
+            .map(pose_from_json)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn pose_to_json(pose: &Pose) -> Json {
+    Json::Array(vec![
+        Json::number(pose.position.x),
+        Json::number(pose.position.y),
+        Json::number(pose.rotation),
+    ])
+}
+
+fn pose_from_json(json: &Json) -> Result<Pose, SnapshotError> {
+    let number = |json: &Json| {
+        json.as_number()
+            .ok_or(SnapshotError::Malformed { field: "pose" })
+    };
+
+    match json.as_array() {
+        Some([x, y, rotation]) => Ok(Pose {
+            position: na::Point2::new(number(x)?, number(y)?),
+            rotation: number(rotation)?,
+        }),
+        _ => Err(SnapshotError::Malformed { field: "pose" }),
+    }
+}
+
+fn point_from_json(json: &Json) -> Result<na::Point2<f32>, SnapshotError> {
+    let number = |json: &Json| {
+        json.as_number()
+            .ok_or(SnapshotError::Malformed { field: "food" })
+    };
+
+    match json.as_array() {
+        Some([x, y]) => Ok(na::Point2::new(number(x)?, number(y)?)),
+        _ => Err(SnapshotError::Malformed { field: "food" }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn record(interval: usize) -> Replay {
+        let config = Config {
+            population: 3,
+            foods: 5,
+            generation_length: 4,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation::new(config, 0);
+        let mut recorder = Recorder::keyframed(interval);
+
+        for _ in 0..9 {
+            sim.step();
+            recorder.record(sim.world());
+        }
+
+        recorder.finish()
+    }
+
+    #[test]
+    fn full() {
+        let replay = record(1);
+
+        assert_eq!(replay.len(), 9);
+        assert_eq!(replay.frames().count(), 9);
+        assert_eq!(Replay::load(&replay.save()), Ok(replay));
+    }
+
+    #[test]
+    fn keyframed() {
+        let full = record(1);
+        let replay = record(2);
+
+        assert_eq!(replay.len(), 9);
+
+        for step in (0..9).step_by(2) {
+            assert_eq!(replay.frame(step), full.frame(step));
+        }
+
+        // Birds don't wrap around here, so they should be in between
+        let (a, b, c) = (
+            full.frame(0).unwrap(),
+            replay.frame(1).unwrap(),
+            full.frame(2).unwrap(),
+        );
+
+        let (a, b, c) = (
+            a.animals[0].unwrap().position,
+            b.animals[0].unwrap().position,
+            c.animals[0].unwrap().position,
+        );
+
+        assert_relative_eq!(b, a + (c - a) / 2.0);
+        assert_eq!(replay.frame(9), None);
+        assert_eq!(Replay::load(&replay.save()), Ok(replay));
+    }
+
+    #[test]
+    fn highlights() {
+        let highlights = Highlights {
+            clips: vec![
+                Clip {
+                    labels: vec!["first".into(), "best".into()],
+                    generation: 0,
+                    replay: record(1),
+                },
+                Clip {
+                    labels: vec!["last".into()],
+                    generation: 4,
+                    replay: record(2),
+                },
+            ],
+        };
+
+        assert_eq!(Highlights::load(&highlights.save()), Ok(highlights));
+
+        assert_eq!(
+            Highlights::load("{\"version\":1,\"clips\":[{\"labels\":[1]}]}"),
+            Err(SnapshotError::Malformed { field: "labels" })
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::*;
+
+/// Scent deposited by foods (and optionally birds), spreading out and
+/// fading away over time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScentConfig {
+    /// Number of the field's cells along each axis
+    pub resolution: usize,
+
+    /// Scent deposited by each available food per step
+    pub food_deposit: f32,
+
+    /// Scent deposited by each living bird per step
+    pub bird_deposit: f32,
+
+    /// Fraction of each cell's scent exchanged with its neighbours per
+    /// step
+    pub diffusion: f32,
+
+    /// Fraction of scent evaporating per step
+    pub decay: f32,
+}
+
+impl Default for ScentConfig {
+    fn default() -> Self {
+        Self {
+            resolution: 32,
+            food_deposit: 0.05,
+            bird_deposit: 0.0,
+            diffusion: 0.2,
+            decay: 0.05,
+        }
+    }
+}
+
+/// Grid of scent intensities covering the world.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScentField {
+    size: usize,
+    pub(crate) cells: Vec<f32>,
+}
+
+impl ScentField {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+
+        Self {
+            size,
+            cells: vec![0.0; size * size],
+        }
+    }
+
+    /// Number of cells along each axis.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns intensities, row by row (starting at `y = 0`).
+    pub fn cells(&self) -> &[f32] {
+        &self.cells
+    }
+
+    /// Returns intensity at given point; points outside the world get
+    /// the nearest border cell.
+    pub fn at(&self, point: na::Point2<f32>) -> f32 {
+        self.cells[self.cell_of(point)]
+    }
+
+    pub(crate) fn deposit(&mut self, point: na::Point2<f32>, amount: f32) {
+        let cell = self.cell_of(point);
+        self.cells[cell] += amount;
+    }
+
+    /// Spreads the scent towards neighbouring cells and lets it evaporate
+    /// a bit; the field's edges don't let any through.
+    pub(crate) fn diffuse(&mut self, diffusion: f32, decay: f32) {
+        let size = self.size;
+        let at = |x: usize, y: usize| self.cells[y * size + x];
+
+        let cells = (0..size)
+            .flat_map(|y| (0..size).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let neighbours = at(x.saturating_sub(1), y)
+                    + at((x + 1).min(size - 1), y)
+                    + at(x, y.saturating_sub(1))
+                    + at(x, (y + 1).min(size - 1));
+
+                let value = (1.0 - diffusion) * at(x, y) + diffusion * neighbours / 4.0;
+
+                value * (1.0 - decay)
+            })
+            .collect();
+
+        self.cells = cells;
+    }
+
+    fn cell_of(&self, point: na::Point2<f32>) -> usize {
+        // Negative values (and NaNs) saturate to zero
+        let coord = |value: f32| ((value * self.size as f32) as usize).min(self.size - 1);
+
+        coord(point.y) * self.size + coord(point.x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn diffuse() {
+        let mut field = ScentField::new(3);
+        field.deposit(na::Point2::new(0.5, 0.5), 1.0);
+        field.diffuse(0.4, 0.0);
+
+        assert_relative_eq!(field.at(na::Point2::new(0.5, 0.5)), 0.6);
+        assert_relative_eq!(field.at(na::Point2::new(0.5, 0.9)), 0.1);
+        assert_relative_eq!(field.at(na::Point2::new(0.1, 0.1)), 0.0);
+
+        // Without decay nothing gets lost
+        field.diffuse(0.4, 0.0);
+        assert_relative_eq!(field.cells().iter().sum::<f32>(), 1.0, epsilon = 1e-6);
+
+        field.diffuse(0.0, 0.5);
+        assert_relative_eq!(field.cells().iter().sum::<f32>(), 0.5, epsilon = 1e-6);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::*;
+
+/// Brain inputs besides vision; each enabled sense adds inputs that come
+/// after all the eye's channels (in the order of fields below).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Senses {
+    /// Current speed, relative to bird's maximum speed (`speed`)
+    pub speed: bool,
+
+    /// Last turn, relative to how much the bird can turn (`rotation_rate`)
+    pub rotation: bool,
+
+    /// Energy left, relative to `EnergyConfig::max` (`energy`); always
+    /// zero if `Config::energy` is disabled
+    pub energy: bool,
+
+    /// Direction the bird is facing, as a unit vector (`compass_x`,
+    /// `compass_y`)
+    pub compass: bool,
+
+    /// Constant one (`bias`)
+    pub bias: bool,
+
+    /// Scent a bit ahead to the left and to the right of the bird
+    /// (`scent_left`, `scent_right`); always zero if `Config::scent` is
+    /// disabled
+    pub scent: bool,
+
+    /// Wind at the bird's position, relative to its heading and maximum
+    /// speed (`wind_forward`, `wind_side` - positive when blowing to the
+    /// right)
+    pub wind: bool,
+
+    /// Current daylight, from zero (midnight) to one (noon) (`daylight`);
+    /// always one if `Config::day_night` is disabled
+    pub daylight: bool,
+
+    /// Speed multiplier of the terrain underneath the bird (`terrain`);
+    /// always one if `Config::terrain` is disabled
+    pub terrain: bool,
+
+    /// Loudness of other birds flapping and of predators nearby - from
+    /// all around, unlike vision (`sound_birds`, `sound_predators`)
+    pub hearing: bool,
+}
+
+impl Senses {
+    /// Returns names of the inputs enabled senses feed.
+    pub(crate) fn names(&self) -> Vec<&'static str> {
+        let mut names = Vec::new();
+
+        if self.speed {
+            names.push("speed");
+        }
+
+        if self.rotation {
+            names.push("rotation_rate");
+        }
+
+        if self.energy {
+            names.push("energy");
+        }
+
+        if self.compass {
+            names.extend(["compass_x", "compass_y"]);
+        }
+
+        if self.bias {
+            names.push("bias");
+        }
+
+        if self.scent {
+            names.extend(["scent_left", "scent_right"]);
+        }
+
+        if self.wind {
+            names.extend(["wind_forward", "wind_side"]);
+        }
+
+        if self.daylight {
+            names.push("daylight");
+        }
+
+        if self.terrain {
+            names.push("terrain");
+        }
+
+        if self.hearing {
+            names.extend(["sound_birds", "sound_predators"]);
+        }
+
+        names
+    }
+
+    /// Appends what `animal` feels to `inputs`, in the order of `names()`.
+    pub(crate) fn feel(
+        &self,
+        animal: &Animal,
+        config: &Config,
+        world: &WorldView,
+        inputs: &mut Vec<f32>,
+    ) {
+        if self.speed {
+            inputs.push(animal.speed / animal.body.speed_max);
+        }
+
+        if self.rotation {
+            inputs.push(animal.turn / animal.body.rotation_accel);
+        }
+
+        if self.energy {
+            inputs.push(
+                config
+                    .energy
+                    .as_ref()
+                    .map_or(0.0, |energy| animal.energy / energy.max),
+            );
+        }
+
+        if self.compass {
+            let heading = animal.rotation * na::Vector2::y();
+            inputs.extend([heading.x, heading.y]);
+        }
+
+        if self.bias {
+            inputs.push(1.0);
+        }
+
+        if self.scent {
+            let smell = |side: f32| {
+                world.scent.map_or(0.0, |scent| {
+                    // One cell away, so that the sides differ
+                    let offset = 1.0 / scent.size() as f32;
+                    let offset = animal.rotation * na::Vector2::new(side * offset, offset);
+
+                    scent.at(animal.position + offset)
+                })
+            };
+
+            inputs.extend([smell(-1.0), smell(1.0)]);
+        }
+
+        if self.wind {
+            let wind = animal.rotation.inverse() * config.wind.at(animal.position);
+            let wind = wind / animal.body.speed_max;
+
+            // Bird faces the local y axis, with x pointing to its right
+            inputs.extend([wind.y, wind.x]);
+        }
+
+        if self.daylight {
+            inputs.push(world.light);
+        }
+
+        if self.terrain {
+            inputs.push(
+                config
+                    .terrain
+                    .as_ref()
+                    .map_or(1.0, |terrain| terrain.at(animal.position)),
+            );
+        }
+
+        if self.hearing {
+            inputs.extend(animal.sound);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    #[test]
+    fn feel() {
+        let senses = Senses {
+            speed: true,
+            rotation: true,
+            energy: true,
+            compass: true,
+            bias: true,
+            scent: true,
+            wind: true,
+            daylight: true,
+            terrain: true,
+            hearing: true,
+        };
+
+        let config = Config {
+            energy: Some(EnergyConfig::default()),
+            wind: Wind::Constant {
+                velocity: na::Vector2::new(-0.005, 0.0),
+            },
+            terrain: Some(Terrain::uniform(1, 0.25)),
+            ..Config::default()
+        };
+
+        let mut animal = Animal::random(&config, &mut StepRng::new(0, 1));
+        animal.position = na::Point2::new(0.5, 0.5);
+        animal.speed = config.speed_max / 2.0;
+        animal.turn = -config.rotation_accel;
+        animal.energy = 1.0;
+        animal.rotation = na::Rotation2::new(0.0);
+        animal.sound = [0.3, 0.0];
+
+        // Scent lies ahead to the right
+        let mut scent = ScentField::new(4);
+        scent.deposit(na::Point2::new(0.8, 0.8), 2.0);
+
+        let world = WorldView {
+            foods: &[],
+            food_grid: &Grid::new(1.0),
+            predators: &[],
+            obstacles: &[],
+            scent: Some(&scent),
+            light: 0.5,
+            vision: 1.0,
+        };
+
+        let mut inputs = Vec::new();
+        senses.feel(&animal, &config, &world, &mut inputs);
+
+        assert_eq!(inputs.len(), senses.names().len());
+        assert_eq!(
+            inputs,
+            [0.5, -1.0, 0.5, 0.0, 1.0, 1.0, 0.0, 2.0, 0.0, -1.0, 0.5, 0.25, 0.3, 0.0]
+        );
+    }
+}
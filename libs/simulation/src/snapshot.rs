@@ -0,0 +1,647 @@
+use crate::*;
+use std::fmt;
+use std::str::FromStr;
+
+const VERSION: u64 = 1;
+
+/// Reasons why a simulation (or a replay) can't be restored from a
+/// snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SnapshotError {
+    /// Snapshot isn't a valid JSON; `offset` points at the offending byte.
+    Syntax { offset: usize },
+
+    /// Snapshot has been written by an incompatible version of this crate.
+    UnsupportedVersion { version: u64 },
+
+    /// Snapshot lacks given field (or it's of a wrong type).
+    Malformed { field: &'static str },
+
+    /// Snapshot has been taken with a different config - e.g. one with a
+    /// different number of eye cells or food kinds.
+    ConfigMismatch,
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { offset } => write!(f, "snapshot is not a valid JSON (at byte {offset})"),
+            Self::UnsupportedVersion { version } => {
+                write!(f, "snapshot has unsupported version {version}")
+            }
+            Self::Malformed { field } => write!(f, "snapshot has missing or invalid `{field}`"),
+            Self::ConfigMismatch => write!(f, "snapshot doesn't match the config"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl Simulation {
+    /// Captures the entire state of the simulation - world, statistics and
+    /// the PRNG - as a JSON string.
+    ///
+    /// Config is not a part of the snapshot (neither is a spawner set
+    /// through `set_food_spawner()`), so it has to be provided again when
+    /// loading.
+    pub fn save(&self) -> String {
+        Json::object([
+            ("version", Json::number(VERSION)),
+            ("seed", Json::number(self.seed)),
+            ("rng", Json::number(self.rng.get_word_pos())),
+            ("age", Json::number(self.age)),
+            ("steps", Json::number(self.steps)),
+            ("best_fitness", Json::number(self.best_fitness)),
+            ("improved_at", Json::number(self.improved_at)),
+            (
+                "history",
+                Json::Array(self.history.iter().map(stats_to_json).collect()),
+            ),
+            (
+                "champion",
+                self.champion.as_ref().map_or(Json::Null, |champion| {
+                    Json::Array(champion.iter().map(Json::number).collect())
+                }),
+            ),
+            (
+                "baseline_stats",
+                Json::Array(self.baseline_stats.iter().map(stats_to_json).collect()),
+            ),
+            (
+                "species_stats",
+                Json::Array(self.species_stats.iter().map(ga_stats_to_json).collect()),
+            ),
+            ("behavior", tracker_to_json(&self.behavior)),
+            ("world", world_to_json(&self.world)),
+        ])
+        .to_string()
+    }
+
+    /// Restores a simulation saved with `save()`; given that `config` is
+    /// the same, the restored simulation continues exactly like the
+    /// original one would.
+    pub fn load(config: Config, snapshot: &str) -> Result<Self, SnapshotError> {
+        let json = Json::parse(snapshot).map_err(|offset| SnapshotError::Syntax { offset })?;
+
+        let version: u64 = number(&json, "version")?;
+
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion { version });
+        }
+
+        // Rebuilding the simulation from its seed recreates everything that
+        // isn't stored in the snapshot (e.g. food clusters)
+        let mut this = Self::new(config, number(&json, "seed")?);
+
+        this.rng.set_word_pos(number(&json, "rng")?);
+        this.age = number(&json, "age")?;
+        this.steps = number(&json, "steps")?;
+        this.best_fitness = number(&json, "best_fitness")?;
+        this.improved_at = number(&json, "improved_at")?;
+
+        this.history = array(&json, "history")?
+            .iter()
+            .map(stats_from_json)
+            .collect::<Result<_, _>>()?;
+
+        // The world gets loaded below as-is, so only the config has to
+        // catch up with the curriculum
+        for generation in 1..=this.history.len() {
+            advance_curriculum(&mut this.config, generation);
+        }
+
+        this.champion = match field(&json, "champion")? {
+            Json::Null => None,
+            _ => Some(
+                array(&json, "champion")?
+                    .iter()
+                    .map(|gene| {
+                        gene.as_number()
+                            .ok_or(SnapshotError::Malformed { field: "champion" })
+                    })
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+
+        this.baseline_stats = array(&json, "baseline_stats")?
+            .iter()
+            .map(stats_from_json)
+            .collect::<Result<_, _>>()?;
+
+        this.species_stats = array(&json, "species_stats")?
+            .iter()
+            .map(ga_stats_from_json)
+            .collect::<Result<_, _>>()?;
+
+        this.behavior = tracker_from_json(field(&json, "behavior")?)?;
+        this.world = world_from_json(&this.config, field(&json, "world")?)?;
+
+        Ok(this)
+    }
+}
+
+fn world_to_json(world: &World) -> Json {
+    Json::object([
+        (
+            "animals",
+            Json::Array(world.animals.iter().map(animal_to_json).collect()),
+        ),
+        (
+            "foods",
+            Json::Array(world.foods.iter().map(food_to_json).collect()),
+        ),
+        (
+            "predators",
+            Json::Array(world.predators.iter().map(predator_to_json).collect()),
+        ),
+        (
+            "obstacles",
+            Json::Array(world.obstacles.iter().map(obstacle_to_json).collect()),
+        ),
+        (
+            "scent",
+            match &world.scent {
+                Some(scent) => Json::Array(scent.cells.iter().map(Json::number).collect()),
+                None => Json::Null,
+            },
+        ),
+        ("next_id", Json::number(world.next_id)),
+    ])
+}
+
+fn world_from_json(config: &Config, json: &Json) -> Result<World, SnapshotError> {
+    let scent = match (&config.scent, field(json, "scent")?) {
+        (None, Json::Null) => None,
+
+        (Some(scent), Json::Array(cells)) => {
+            let mut field = ScentField::new(scent.resolution);
+
+            if cells.len() != field.cells.len() {
+                return Err(SnapshotError::ConfigMismatch);
+            }
+
+            for (cell, value) in field.cells.iter_mut().zip(cells) {
+                *cell = value
+                    .as_number()
+                    .ok_or(SnapshotError::Malformed { field: "scent" })?;
+            }
+
+            Some(field)
+        }
+
+        _ => return Err(SnapshotError::ConfigMismatch),
+    };
+
+    Ok(World {
+        animals: array(json, "animals")?
+            .iter()
+            .map(|animal| animal_from_json(config, animal))
+            .collect::<Result<_, _>>()?,
+        foods: array(json, "foods")?
+            .iter()
+            .map(|food| food_from_json(config, food))
+            .collect::<Result<_, _>>()?,
+        predators: array(json, "predators")?
+            .iter()
+            .map(predator_from_json)
+            .collect::<Result<_, _>>()?,
+        obstacles: array(json, "obstacles")?
+            .iter()
+            .map(obstacle_from_json)
+            .collect::<Result<_, _>>()?,
+        scent,
+        next_id: number(json, "next_id")?,
+    })
+}
+
+fn animal_to_json(animal: &Animal) -> Json {
+    Json::object([
+        ("id", Json::number(animal.id.0)),
+        ("position", point_to_json(animal.position)),
+        ("rotation", rotation_to_json(animal.rotation)),
+        ("speed", Json::number(animal.speed)),
+        ("turn", Json::number(animal.turn)),
+        (
+            "brain",
+            Json::Array(
+                animal
+                    .brain
+                    .as_chromosome()
+                    .iter()
+                    .map(Json::number)
+                    .collect(),
+            ),
+        ),
+        (
+            "body",
+            Json::Array(animal.body.genes.iter().map(Json::number).collect()),
+        ),
+        ("sound", point_to_json(animal.sound.into())),
+        ("species", Json::number(animal.species)),
+        ("injected", Json::Bool(animal.injected)),
+        ("baseline", animal.baseline.map_or(Json::Null, Json::number)),
+        ("satiation", Json::number(animal.satiation)),
+        (
+            "first_food",
+            animal.first_food.map_or(Json::Null, Json::number),
+        ),
+        ("distance", Json::number(animal.distance)),
+        ("displacement", point_to_json(animal.displacement.into())),
+        ("energy", Json::number(animal.energy)),
+        ("energy_spent", Json::number(animal.energy_spent)),
+        ("age", Json::number(animal.age)),
+        ("survived", Json::number(animal.survived)),
+        ("alive", Json::Bool(animal.alive)),
+    ])
+}
+
+fn animal_from_json(config: &Config, json: &Json) -> Result<Animal, SnapshotError> {
+    let channels = config.channels();
+
+    let body: Vec<_> = array(json, "body")?
+        .iter()
+        .map(|gene| {
+            gene.as_number()
+                .ok_or(SnapshotError::Malformed { field: "body" })
+        })
+        .collect::<Result<_, _>>()?;
+
+    if body.len() != config.traits.gene_count() {
+        return Err(SnapshotError::ConfigMismatch);
+    }
+
+    let body = Body::from_genes(config, body);
+    let eye = body.eye(config);
+
+    let chromosome: ga::Chromosome = array(json, "brain")?
+        .iter()
+        .map(|gene| {
+            gene.as_number()
+                .ok_or(SnapshotError::Malformed { field: "brain" })
+        })
+        .collect::<Result<_, _>>()?;
+
+    if chromosome.len() != Brain::segment_layout(&eye, &channels, &config.senses).gene_count() {
+        return Err(SnapshotError::ConfigMismatch);
+    }
+
+    let species = number(json, "species")?;
+
+    if species >= config.species {
+        return Err(SnapshotError::ConfigMismatch);
+    }
+
+    let baseline = optional_number(json, "baseline")?;
+
+    if baseline.is_some_and(|baseline| baseline >= config.baselines.len()) {
+        return Err(SnapshotError::ConfigMismatch);
+    }
+
+    Ok(Animal {
+        id: AnimalId(number(json, "id")?),
+        position: point_from_json(json, "position")?,
+        rotation: rotation_from_json(json, "rotation")?,
+        speed: number(json, "speed")?,
+        turn: number(json, "turn")?,
+        brain: Brain::from_chromosome(chromosome, &eye, &channels, &config.senses),
+        eye,
+        body,
+        sound: point_from_json(json, "sound")?.coords.into(),
+        species,
+        baseline,
+        injected: boolean(json, "injected")?,
+        satiation: number(json, "satiation")?,
+        first_food: optional_number(json, "first_food")?,
+        distance: number(json, "distance")?,
+        displacement: point_from_json(json, "displacement")?.coords,
+        energy: number(json, "energy")?,
+        energy_spent: number(json, "energy_spent")?,
+        age: number(json, "age")?,
+        survived: number(json, "survived")?,
+        alive: boolean(json, "alive")?,
+    })
+}
+
+fn food_to_json(food: &Food) -> Json {
+    Json::object([
+        ("position", point_to_json(food.position)),
+        ("kind", Json::number(food.kind)),
+        ("available", Json::Bool(food.available)),
+        ("value", food.value.map_or(Json::Null, Json::number)),
+    ])
+}
+
+fn food_from_json(config: &Config, json: &Json) -> Result<Food, SnapshotError> {
+    let kind = number(json, "kind")?;
+
+    if kind >= config.food_kinds.len() {
+        return Err(SnapshotError::ConfigMismatch);
+    }
+
+    Ok(Food {
+        position: point_from_json(json, "position")?,
+        kind,
+        available: boolean(json, "available")?,
+        value: optional_number(json, "value")?,
+    })
+}
+
+fn predator_to_json(predator: &Predator) -> Json {
+    Json::object([
+        ("position", point_to_json(predator.position)),
+        ("rotation", rotation_to_json(predator.rotation)),
+    ])
+}
+
+fn predator_from_json(json: &Json) -> Result<Predator, SnapshotError> {
+    Ok(Predator {
+        position: point_from_json(json, "position")?,
+        rotation: rotation_from_json(json, "rotation")?,
+    })
+}
+
+fn obstacle_to_json(obstacle: &Obstacle) -> Json {
+    match obstacle {
+        Obstacle::Circle { center, radius } => Json::object([
+            ("shape", Json::String("circle".into())),
+            ("center", point_to_json(*center)),
+            ("radius", Json::number(radius)),
+        ]),
+
+        Obstacle::Rect { min, max } => Json::object([
+            ("shape", Json::String("rect".into())),
+            ("min", point_to_json(*min)),
+            ("max", point_to_json(*max)),
+        ]),
+    }
+}
+
+fn obstacle_from_json(json: &Json) -> Result<Obstacle, SnapshotError> {
+    match field(json, "shape")?.as_str() {
+        Some("circle") => Ok(Obstacle::Circle {
+            center: point_from_json(json, "center")?,
+            radius: number(json, "radius")?,
+        }),
+
+        Some("rect") => Ok(Obstacle::Rect {
+            min: point_from_json(json, "min")?,
+            max: point_from_json(json, "max")?,
+        }),
+
+        _ => Err(SnapshotError::Malformed { field: "shape" }),
+    }
+}
+
+pub(crate) fn stats_to_json(stats: &Statistics) -> Json {
+    Json::object([
+        ("generation", Json::number(stats.generation)),
+        ("min_fitness", Json::number(stats.min_fitness)),
+        ("max_fitness", Json::number(stats.max_fitness)),
+        ("avg_fitness", Json::number(stats.avg_fitness)),
+        ("median_fitness", Json::number(stats.median_fitness)),
+        ("avg_age", Json::number(stats.avg_age)),
+        ("behavior", behavior_to_json(&stats.behavior)),
+    ])
+}
+
+fn stats_from_json(json: &Json) -> Result<Statistics, SnapshotError> {
+    Ok(Statistics {
+        generation: number(json, "generation")?,
+        min_fitness: number(json, "min_fitness")?,
+        max_fitness: number(json, "max_fitness")?,
+        avg_fitness: number(json, "avg_fitness")?,
+        median_fitness: number(json, "median_fitness")?,
+        avg_age: number(json, "avg_age")?,
+        behavior: behavior_from_json(field(json, "behavior")?)?,
+    })
+}
+
+fn behavior_to_json(behavior: &Behavior) -> Json {
+    Json::object([
+        ("nearest_neighbor", Json::number(behavior.nearest_neighbor)),
+        ("alignment", Json::number(behavior.alignment)),
+        ("coverage", Json::number(behavior.coverage)),
+        ("tortuosity", Json::number(behavior.tortuosity)),
+    ])
+}
+
+fn behavior_from_json(json: &Json) -> Result<Behavior, SnapshotError> {
+    Ok(Behavior {
+        nearest_neighbor: number(json, "nearest_neighbor")?,
+        alignment: number(json, "alignment")?,
+        coverage: number(json, "coverage")?,
+        tortuosity: number(json, "tortuosity")?,
+    })
+}
+
+fn tracker_to_json(tracker: &BehaviorTracker) -> Json {
+    Json::object([
+        ("steps", Json::number(tracker.steps)),
+        ("samples", Json::number(tracker.samples)),
+        ("nearest_neighbor", Json::number(tracker.nearest_neighbor)),
+        ("alignment", Json::number(tracker.alignment)),
+        (
+            "visited",
+            Json::Array(
+                tracker
+                    .visited
+                    .iter()
+                    .map(|&cell| Json::Bool(cell))
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+fn tracker_from_json(json: &Json) -> Result<BehaviorTracker, SnapshotError> {
+    let mut tracker = BehaviorTracker::new();
+    let visited = array(json, "visited")?;
+
+    if visited.len() != tracker.visited.len() {
+        return Err(SnapshotError::Malformed { field: "visited" });
+    }
+
+    for (cell, value) in tracker.visited.iter_mut().zip(visited) {
+        *cell = value
+            .as_bool()
+            .ok_or(SnapshotError::Malformed { field: "visited" })?;
+    }
+
+    tracker.steps = number(json, "steps")?;
+    tracker.samples = number(json, "samples")?;
+    tracker.nearest_neighbor = number(json, "nearest_neighbor")?;
+    tracker.alignment = number(json, "alignment")?;
+
+    Ok(tracker)
+}
+
+fn ga_stats_to_json(stats: &ga::Statistics) -> Json {
+    Json::object([
+        ("min_fitness", Json::number(stats.min_fitness)),
+        ("max_fitness", Json::number(stats.max_fitness)),
+        ("avg_fitness", Json::number(stats.avg_fitness)),
+    ])
+}
+
+fn ga_stats_from_json(json: &Json) -> Result<ga::Statistics, SnapshotError> {
+    Ok(ga::Statistics {
+        min_fitness: number(json, "min_fitness")?,
+        max_fitness: number(json, "max_fitness")?,
+        avg_fitness: number(json, "avg_fitness")?,
+    })
+}
+
+pub(crate) fn point_to_json(point: na::Point2<f32>) -> Json {
+    Json::Array(vec![Json::number(point.x), Json::number(point.y)])
+}
+
+fn point_from_json(json: &Json, name: &'static str) -> Result<na::Point2<f32>, SnapshotError> {
+    match array(json, name)? {
+        [x, y] => Ok(na::Point2::new(
+            x.as_number()
+                .ok_or(SnapshotError::Malformed { field: name })?,
+            y.as_number()
+                .ok_or(SnapshotError::Malformed { field: name })?,
+        )),
+        _ => Err(SnapshotError::Malformed { field: name }),
+    }
+}
+
+/// Rotations are stored as their cosine and sine (instead of the angle), so
+/// that they round-trip exactly.
+fn rotation_to_json(rotation: na::Rotation2<f32>) -> Json {
+    let matrix = rotation.matrix();
+
+    Json::Array(vec![
+        Json::number(matrix[(0, 0)]),
+        Json::number(matrix[(1, 0)]),
+    ])
+}
+
+fn rotation_from_json(
+    json: &Json,
+    name: &'static str,
+) -> Result<na::Rotation2<f32>, SnapshotError> {
+    let [cos, sin] = point_from_json(json, name)?.coords.into();
+
+    Ok(na::Rotation2::from_matrix_unchecked(na::Matrix2::new(
+        cos, -sin, sin, cos,
+    )))
+}
+
+fn field<'a>(json: &'a Json, name: &'static str) -> Result<&'a Json, SnapshotError> {
+    json.get(name)
+        .ok_or(SnapshotError::Malformed { field: name })
+}
+
+pub(crate) fn number<T: FromStr>(json: &Json, name: &'static str) -> Result<T, SnapshotError> {
+    field(json, name)?
+        .as_number()
+        .ok_or(SnapshotError::Malformed { field: name })
+}
+
+/// Like `number()`, but `null` gives `None`.
+fn optional_number<T: FromStr>(
+    json: &Json,
+    name: &'static str,
+) -> Result<Option<T>, SnapshotError> {
+    match field(json, name)? {
+        Json::Null => Ok(None),
+        _ => number(json, name).map(Some),
+    }
+}
+
+fn boolean(json: &Json, name: &'static str) -> Result<bool, SnapshotError> {
+    field(json, name)?
+        .as_bool()
+        .ok_or(SnapshotError::Malformed { field: name })
+}
+
+pub(crate) fn array<'a>(json: &'a Json, name: &'static str) -> Result<&'a [Json], SnapshotError> {
+    field(json, name)?
+        .as_array()
+        .ok_or(SnapshotError::Malformed { field: name })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            population: 5,
+            generation_length: 20,
+            food_kinds: vec![FoodKind::default(), FoodKind::default()],
+            food_spawn: FoodSpawn::Clusters {
+                count: 2,
+                radius: 0.1,
+            },
+            predators: Some(PredatorConfig::default()),
+            scent: Some(ScentConfig::default()),
+            traits: Traits {
+                eye_fov_angle: Some(Bounds::new(1.0, 4.0)),
+                ..Traits::default()
+            },
+            obstacles: vec![Obstacle::Rect {
+                min: na::Point2::new(0.4, 0.4),
+                max: na::Point2::new(0.6, 0.6),
+            }],
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut sim = Simulation::new(config(), 42);
+
+        sim.add_obstacle(Obstacle::Circle {
+            center: na::Point2::new(0.1, 0.2),
+            radius: 0.05,
+        });
+
+        sim.train();
+
+        for _ in 0..7 {
+            sim.step();
+        }
+
+        let snapshot = sim.save();
+        let mut restored = Simulation::load(config(), &snapshot).unwrap();
+
+        assert_eq!(restored.save(), snapshot);
+        assert_eq!(restored.stats(), sim.stats());
+
+        assert_eq!(restored.train(), sim.train());
+        assert_eq!(restored.save(), sim.save());
+    }
+
+    #[test]
+    fn errors() {
+        let snapshot = Simulation::new(config(), 42).save();
+
+        assert_eq!(
+            Simulation::load(config(), "{").err(),
+            Some(SnapshotError::Syntax { offset: 1 })
+        );
+
+        assert_eq!(
+            Simulation::load(config(), "{\"version\":0}").err(),
+            Some(SnapshotError::UnsupportedVersion { version: 0 })
+        );
+
+        assert_eq!(
+            Simulation::load(config(), "{\"version\":1}").err(),
+            Some(SnapshotError::Malformed { field: "seed" })
+        );
+
+        let other_config = Config {
+            eye_cells: 3,
+            ..config()
+        };
+
+        assert_eq!(
+            Simulation::load(other_config, &snapshot).err(),
+            Some(SnapshotError::ConfigMismatch)
+        );
+    }
+}
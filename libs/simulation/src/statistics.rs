@@ -0,0 +1,129 @@
+use crate::*;
+
+/// Summary of a finished generation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Statistics {
+    /// Index of the generation (starting from zero)
+    pub generation: usize,
+
+    pub min_fitness: f32,
+    pub max_fitness: f32,
+    pub avg_fitness: f32,
+    pub median_fitness: f32,
+
+    /// Average number of steps birds have lived for
+    pub avg_age: f32,
+
+    pub behavior: Behavior,
+}
+
+impl Statistics {
+    /// Header of CSV rows produced by `to_csv_row()`
+    pub const CSV_HEADER: &'static str = "generation,min_fitness,max_fitness,avg_fitness,\
+        median_fitness,avg_age,nearest_neighbor,alignment,coverage,tortuosity";
+
+    pub fn to_csv_row(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{}",
+            self.generation,
+            self.min_fitness,
+            self.max_fitness,
+            self.avg_fitness,
+            self.median_fitness,
+            self.avg_age,
+            self.behavior.nearest_neighbor,
+            self.behavior.alignment,
+            self.behavior.coverage,
+            self.behavior.tortuosity,
+        )
+    }
+
+    /// Returns statistics in the same shape snapshots store them in.
+    pub fn to_json(&self) -> Json {
+        stats_to_json(self)
+    }
+
+    pub(crate) fn new(
+        generation: usize,
+        animals: &[&Animal],
+        fitness: &dyn FitnessFn,
+        behavior: Behavior,
+    ) -> Self {
+        assert!(!animals.is_empty());
+
+        let mut scores: Vec<_> = animals
+            .iter()
+            .map(|animal| score(fitness, animal))
+            .collect();
+        scores.sort_by(f32::total_cmp);
+
+        let len = scores.len();
+
+        let median_fitness = if len % 2 == 0 {
+            (scores[len / 2 - 1] + scores[len / 2]) / 2.0
+        } else {
+            scores[len / 2]
+        };
+
+        let ages: usize = animals.iter().map(|animal| animal.age).sum();
+
+        Self {
+            generation,
+            min_fitness: scores[0],
+            max_fitness: scores[len - 1],
+            avg_fitness: scores.iter().sum::<f32>() / len as f32,
+            median_fitness,
+            avg_age: ages as f32 / len as f32,
+            behavior,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use rand::rngs::mock::StepRng;
+
+    fn stats(generation: usize, satiations: &[f32]) -> Statistics {
+        let animals: Vec<_> = satiations
+            .iter()
+            .zip(1..)
+            .map(|(&satiation, age)| {
+                let mut animal = Animal::random(&Config::default(), &mut StepRng::new(0, 1));
+                animal.satiation = satiation;
+                animal.age = age;
+                animal
+            })
+            .collect();
+
+        let animals: Vec<_> = animals.iter().collect();
+
+        Statistics::new(
+            generation,
+            &animals,
+            &Fitness::Satiation,
+            Behavior::default(),
+        )
+    }
+
+    #[test]
+    fn odd() {
+        let stats = stats(3, &[4.0, 1.0, 2.0]);
+
+        assert_eq!(stats.generation, 3);
+        assert_relative_eq!(stats.min_fitness, 1.0);
+        assert_relative_eq!(stats.max_fitness, 4.0);
+        assert_relative_eq!(stats.avg_fitness, 7.0 / 3.0);
+        assert_relative_eq!(stats.median_fitness, 2.0);
+        assert_relative_eq!(stats.avg_age, 2.0);
+    }
+
+    #[test]
+    fn even() {
+        let stats = stats(0, &[4.0, 1.0, 2.0, 8.0]);
+
+        assert_relative_eq!(stats.median_fitness, 3.0);
+        assert_relative_eq!(stats.avg_age, 2.5);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::snapshot::{array, number};
+use crate::*;
+
+const VERSION: u64 = 1;
+
+/// Grid of speed multipliers covering the world - e.g. with `1.0` for open
+/// air, `0.5` for a dense forest and `0.0` for places birds can't
+/// fly through at all.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Terrain {
+    size: usize,
+    cells: Vec<f32>,
+}
+
+impl Terrain {
+    /// Creates terrain of `size` x `size` cells, given row by row (starting
+    /// at `y = 0`).
+    pub fn new(size: usize, cells: Vec<f32>) -> Self {
+        assert!(size > 0);
+        assert_eq!(cells.len(), size * size);
+        assert!(cells.iter().all(|&cell| cell >= 0.0));
+
+        Self { size, cells }
+    }
+
+    /// Creates terrain with the same multiplier everywhere.
+    pub fn uniform(size: usize, multiplier: f32) -> Self {
+        Self::new(size, vec![multiplier; size * size])
+    }
+
+    /// Number of cells along each axis.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns multipliers, row by row (starting at `y = 0`).
+    pub fn cells(&self) -> &[f32] {
+        &self.cells
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, multiplier: f32) {
+        assert!(x < self.size && y < self.size);
+        assert!(multiplier >= 0.0);
+
+        self.cells[y * self.size + x] = multiplier;
+    }
+
+    /// Returns multiplier at given point; points outside the world get the
+    /// nearest border cell.
+    pub fn at(&self, point: na::Point2<f32>) -> f32 {
+        // Negative values (and NaNs) saturate to zero
+        let coord = |value: f32| ((value * self.size as f32) as usize).min(self.size - 1);
+
+        self.cells[coord(point.y) * self.size + coord(point.x)]
+    }
+
+    pub fn save(&self) -> String {
+        Json::object([
+            ("version", Json::number(VERSION)),
+            ("size", Json::number(self.size)),
+            (
+                "cells",
+                Json::Array(self.cells.iter().map(Json::number).collect()),
+            ),
+        ])
+        .to_string()
+    }
+
+    pub fn load(terrain: &str) -> Result<Self, SnapshotError> {
+        let json = Json::parse(terrain).map_err(|offset| SnapshotError::Syntax { offset })?;
+
+        let version: u64 = number(&json, "version")?;
+
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion { version });
+        }
+
+        let size: usize = number(&json, "size")?;
+
+        let cells: Vec<f32> = array(&json, "cells")?
+            .iter()
+            .map(|cell| {
+                cell.as_number()
+                    .filter(|&cell: &f32| cell >= 0.0)
+                    .ok_or(SnapshotError::Malformed { field: "cells" })
+            })
+            .collect::<Result<_, _>>()?;
+
+        if size == 0 || cells.len() != size * size {
+            return Err(SnapshotError::Malformed { field: "cells" });
+        }
+
+        Ok(Self { size, cells })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn at() {
+        let mut terrain = Terrain::uniform(2, 1.0);
+        terrain.set(1, 0, 0.5);
+
+        assert_eq!(terrain.at(na::Point2::new(0.75, 0.25)), 0.5);
+        assert_eq!(terrain.at(na::Point2::new(0.25, 0.25)), 1.0);
+        assert_eq!(terrain.at(na::Point2::new(1.5, -1.0)), 0.5);
+    }
+
+    #[test]
+    fn save_load() {
+        let terrain = Terrain::new(2, vec![1.0, 0.5, 0.0, 2.0]);
+
+        assert_eq!(Terrain::load(&terrain.save()), Ok(terrain));
+
+        assert_eq!(
+            Terrain::load("{\"version\":1,\"size\":2,\"cells\":[1]}"),
+            Err(SnapshotError::Malformed { field: "cells" })
+        );
+    }
+}
@@ -0,0 +1,166 @@
+use crate::*;
+
+/// Spawning threads isn't free, so each one gets at least this many birds.
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+const MIN_ANIMALS_PER_THREAD: usize = 64;
+
+/// Runs brains of all the living, brain-driven birds, returning their
+/// decisions (`None` for the rest).
+///
+/// Brains don't affect each other, so with the `parallel` feature they
+/// think on multiple threads - yielding the same decisions, no matter how
+/// many threads there are.
+pub(crate) fn think(
+    animals: &[Animal],
+    config: &Config,
+    world: &WorldView,
+    scratch: &mut nn::PropagationScratch,
+) -> Vec<Option<[f32; 2]>> {
+    let channels = config.channels();
+
+    #[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+    {
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk = animals.len().div_ceil(threads).max(MIN_ANIMALS_PER_THREAD);
+
+        if chunk < animals.len() {
+            let mut decisions = vec![None; animals.len()];
+
+            std::thread::scope(|scope| {
+                for (animals, decisions) in animals.chunks(chunk).zip(decisions.chunks_mut(chunk)) {
+                    let channels = &channels;
+
+                    scope.spawn(move || {
+                        let mut scratch = nn::PropagationScratch::new();
+
+                        for (animal, decision) in animals.iter().zip(decisions) {
+                            *decision = think_one(animal, config, channels, world, &mut scratch);
+                        }
+                    });
+                }
+            });
+
+            return decisions;
+        }
+    }
+
+    animals
+        .iter()
+        .map(|animal| think_one(animal, config, &channels, world, scratch))
+        .collect()
+}
+
+fn think_one(
+    animal: &Animal,
+    config: &Config,
+    channels: &[Channel],
+    world: &WorldView,
+    scratch: &mut nn::PropagationScratch,
+) -> Option<[f32; 2]> {
+    if !animal.alive || animal.baseline.is_some() {
+        return None;
+    }
+
+    let inputs = observe(animal, config, channels, world);
+
+    Some(animal.brain.propagate(&inputs, scratch))
+}
+
+/// Returns what the bird sees, followed by what it feels - i.e. its brain's
+/// inputs.
+pub(crate) fn observe(
+    animal: &Animal,
+    config: &Config,
+    channels: &[Channel],
+    world: &WorldView,
+) -> Vec<f32> {
+    let mut inputs = look(animal, channels, world);
+    config.senses.feel(animal, config, world, &mut inputs);
+    inputs
+}
+
+/// What's going on inside a bird's head, see `Simulation::inspect()`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Inspection {
+    /// Eye cells of all the channels, one after another (in the order
+    /// returned by `Config::channels()`)
+    pub vision: Vec<f32>,
+
+    /// Outputs of the brain's layers, starting with its inputs - i.e.
+    /// `vision` followed by the senses
+    pub activations: Vec<Vec<f32>>,
+
+    /// Change of speed and rotation the brain asks for (before getting
+    /// clamped to what the bird can do)
+    pub outputs: [f32; 2],
+}
+
+/// Like `think_one()`, but recording the details; `None` for dead and
+/// baseline birds.
+pub(crate) fn inspect(animal: &Animal, config: &Config, world: &WorldView) -> Option<Inspection> {
+    if !animal.alive || animal.baseline.is_some() {
+        return None;
+    }
+
+    let vision = look(animal, &config.channels(), world);
+    let mut inputs = vision.clone();
+    config.senses.feel(animal, config, world, &mut inputs);
+
+    let (activations, outputs) = animal.brain.propagate_traced(&inputs);
+
+    Some(Inspection {
+        vision,
+        activations,
+        outputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_as_one_by_one() {
+        let config = Config {
+            population: 500,
+            baselines: vec![Baseline {
+                policy: Policy::RandomWalk,
+                count: 1,
+            }],
+            ..Config::default()
+        };
+
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let world = World::random(&config, &mut rng);
+        let foods = [Food::random(&mut rng)];
+
+        let view = WorldView {
+            foods: &foods,
+            food_grid: &Grid::new(1.0),
+            predators: &[],
+            obstacles: &[],
+            scent: None,
+            light: 1.0,
+            vision: 1.0,
+        };
+
+        let mut scratch = nn::PropagationScratch::new();
+        let decisions = think(&world.animals, &config, &view, &mut scratch);
+
+        let expected: Vec<_> = world
+            .animals
+            .iter()
+            .map(|animal| think_one(animal, &config, &config.channels(), &view, &mut scratch))
+            .collect();
+
+        assert_eq!(decisions, expected);
+        assert_eq!(decisions.last(), Some(&None));
+
+        let inspection = inspect(&world.animals[0], &config, &view).unwrap();
+
+        assert_eq!(Some(inspection.outputs), decisions[0]);
+        assert_eq!(inspection.activations.len(), 3);
+        assert!(inspection.activations[0].starts_with(&inspection.vision));
+        assert_eq!(inspect(world.animals.last().unwrap(), &config, &view), None);
+    }
+}
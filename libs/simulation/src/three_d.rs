@@ -0,0 +1,441 @@
+//! Birds flying in a unit cube instead of a unit square (enabled by the
+//! `3d` feature).
+//!
+//! It's a leaner sibling of `Simulation` rather than a dimension-generic
+//! version of it - most of the 2D world (obstacles, predators, scent, wind,
+//! terrain, ...) is planar through and through, so in here birds only chase
+//! foods. What doesn't depend on the dimension - `Config`, the genetic
+//! algorithm, neural networks and `Config::fitness` - is shared with 2D.
+
+use crate::*;
+
+/// Number of eye cells from the bottom to the top of the field of view
+/// (`Config::eye_cells` says how many there are from left to right).
+pub const ELEVATION_CELLS: usize = 3;
+
+/// Distance at which a bird eats food.
+const EAT_DISTANCE: f32 = 0.02;
+
+/// Eye whose cells cover a patch of a sphere - `Config::eye_fov_angle`
+/// horizontally and the entire half-sphere in front of the bird
+/// vertically.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Eye3d {
+    fov_range: f32,
+    fov_angle: f32,
+    azimuth_cells: usize,
+}
+
+impl Eye3d {
+    pub fn new(fov_range: f32, fov_angle: f32, azimuth_cells: usize) -> Self {
+        assert!(fov_range > 0.0);
+        assert!(fov_angle > 0.0);
+        assert!(azimuth_cells > 0);
+
+        Self {
+            fov_range,
+            fov_angle,
+            azimuth_cells,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.eye_fov_range, config.eye_fov_angle, config.eye_cells)
+    }
+
+    /// Total number of cells, row by row (starting at the bottom).
+    pub fn cells(&self) -> usize {
+        self.azimuth_cells * ELEVATION_CELLS
+    }
+
+    /// Birds look along their local y axis, with the z axis pointing up.
+    pub fn see(
+        &self,
+        position: na::Point3<f32>,
+        orientation: na::UnitQuaternion<f32>,
+        objects: impl IntoIterator<Item = na::Point3<f32>>,
+    ) -> Vec<f32> {
+        let mut cells = vec![0.0; self.cells()];
+
+        for object in objects {
+            let vec = orientation.inverse_transform_vector(&(object - position));
+            let dist = vec.norm();
+
+            if dist > self.fov_range {
+                continue;
+            }
+
+            let azimuth = f32::atan2(-vec.x, vec.y);
+            let elevation = f32::atan2(vec.z, vec.xy().norm());
+
+            if azimuth.abs() > self.fov_angle / 2.0 {
+                continue;
+            }
+
+            let column = (azimuth + self.fov_angle / 2.0) / self.fov_angle;
+            let column =
+                ((column * self.azimuth_cells as f32) as usize).min(self.azimuth_cells - 1);
+
+            let row = (elevation + FRAC_PI_2) / PI;
+            let row = ((row * ELEVATION_CELLS as f32) as usize).min(ELEVATION_CELLS - 1);
+
+            cells[row * self.azimuth_cells + column] += (self.fov_range - dist) / self.fov_range;
+        }
+
+        cells
+    }
+}
+
+#[derive(Debug)]
+pub struct Animal3d {
+    pub(crate) position: na::Point3<f32>,
+    pub(crate) orientation: na::UnitQuaternion<f32>,
+    pub(crate) speed: f32,
+    pub(crate) eye: Eye3d,
+    pub(crate) brain: nn::Network,
+
+    /// Total value of foods eaten
+    pub(crate) satiation: f32,
+
+    /// Total distance travelled
+    pub(crate) distance: f32,
+
+    /// Age at which the first food has been eaten
+    pub(crate) first_food: Option<usize>,
+
+    pub(crate) age: usize,
+}
+
+impl Animal3d {
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let eye = Eye3d::from_config(config);
+        let brain = Self::builder(&eye).build(rng);
+
+        Self::new(eye, brain, rng)
+    }
+
+    fn from_chromosome(config: &Config, chromosome: ga::Chromosome, rng: &mut dyn RngCore) -> Self {
+        let eye = Eye3d::from_config(config);
+
+        // Genes and weights can be of different precisions, depending on
+        // the features enabled in both crates
+        let weights = chromosome.into_iter().map(|gene| gene as nn::Float);
+        let brain = nn::Network::from_weights(&Self::builder(&eye).topology(), weights);
+
+        Self::new(eye, brain, rng)
+    }
+
+    fn new(eye: Eye3d, brain: nn::Network, rng: &mut dyn RngCore) -> Self {
+        Self {
+            position: rng.gen(),
+            orientation: rng.gen(),
+            speed: 0.002,
+            eye,
+            brain,
+            satiation: 0.0,
+            distance: 0.0,
+            first_food: None,
+            age: 0,
+        }
+    }
+
+    pub fn as_chromosome(&self) -> ga::Chromosome {
+        self.brain
+            .weights()
+            .map(|weight| weight as ga::Float)
+            .collect()
+    }
+
+    pub fn position(&self) -> na::Point3<f32> {
+        self.position
+    }
+
+    pub fn orientation(&self) -> na::UnitQuaternion<f32> {
+        self.orientation
+    }
+
+    pub fn satiation(&self) -> f32 {
+        self.satiation
+    }
+
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    /// Outputs are the change in speed, yaw and pitch.
+    fn builder(eye: &Eye3d) -> nn::NetworkBuilder {
+        nn::Network::builder()
+            .input(eye.cells())
+            .hidden(2 * eye.cells(), nn::Activation::Relu)
+            .output(3, nn::Activation::Tanh)
+    }
+}
+
+impl From<&Animal3d> for AnimalSummary {
+    fn from(animal: &Animal3d) -> Self {
+        Self {
+            species: 0,
+            satiation: animal.satiation,
+            distance: animal.distance,
+            first_food: animal.first_food,
+            energy: 0.0,
+            energy_spent: 0.0,
+            age: animal.age,
+            survived: animal.age,
+            alive: true,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct World3d {
+    pub(crate) animals: Vec<Animal3d>,
+    pub(crate) foods: Vec<na::Point3<f32>>,
+}
+
+impl World3d {
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let animals = (0..config.population)
+            .map(|_| Animal3d::random(config, rng))
+            .collect();
+
+        let foods = (0..config.foods).map(|_| rng.gen()).collect();
+
+        Self { animals, foods }
+    }
+
+    pub fn animals(&self) -> &[Animal3d] {
+        &self.animals
+    }
+
+    pub fn foods(&self) -> &[na::Point3<f32>] {
+        &self.foods
+    }
+}
+
+/// 3D counterpart of `Simulation`; uses the population, foods, eyes, speeds,
+/// fitness and genetic operators of the `Config` - the rest is ignored.
+pub struct Simulation3d {
+    config: Config,
+    world: World3d,
+    ga: GeneticAlgorithm,
+
+    /// Genes of the last generation, which the next one gets built in
+    recycled: Vec<ga::Chromosome>,
+
+    age: usize,
+    rng: ChaCha8Rng,
+}
+
+impl Simulation3d {
+    pub fn new(config: Config, seed: u64) -> Self {
+        assert!(config.population > 0);
+        assert!(config.speed_min <= config.speed_max);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let world = World3d::random(&config, &mut rng);
+        let ga = genetic_algorithm(&config);
+
+        Self {
+            config,
+            world,
+            ga,
+            recycled: Vec::new(),
+            age: 0,
+            rng,
+        }
+    }
+
+    pub fn world(&self) -> &World3d {
+        &self.world
+    }
+
+    /// Performs a single step, returning statistics if it has ended the
+    /// generation.
+    pub fn step(&mut self) -> Option<ga::Statistics> {
+        self.process_collisions();
+        self.process_brains();
+        self.process_movements();
+
+        self.age += 1;
+
+        if self.age >= self.config.generation_length {
+            Some(self.evolve())
+        } else {
+            None
+        }
+    }
+
+    /// Fast-forwards 'till the end of the current generation.
+    pub fn train(&mut self) -> ga::Statistics {
+        loop {
+            if let Some(stats) = self.step() {
+                return stats;
+            }
+        }
+    }
+
+    fn process_collisions(&mut self) {
+        for animal in &mut self.world.animals {
+            for food in &mut self.world.foods {
+                if na::distance(&animal.position, food) <= EAT_DISTANCE {
+                    animal.satiation += self.config.food_kinds[0].value;
+                    animal.first_food.get_or_insert(animal.age);
+                    *food = self.rng.gen();
+                }
+            }
+        }
+    }
+
+    fn process_brains(&mut self) {
+        for animal in &mut self.world.animals {
+            let vision = animal.eye.see(
+                animal.position,
+                animal.orientation,
+                self.world.foods.iter().copied(),
+            );
+
+            let outputs = animal
+                .brain
+                .propagate(vision.into_iter().map(|cell| cell as nn::Float).collect());
+
+            // No-op for the default precision
+            #[allow(clippy::unnecessary_cast)]
+            let [speed, yaw, pitch] = [0, 1, 2].map(|idx| outputs[idx] as f32);
+
+            let speed = speed * self.config.speed_accel;
+            let yaw = yaw * self.config.rotation_accel;
+            let pitch = pitch * self.config.rotation_accel;
+
+            animal.speed =
+                (animal.speed + speed).clamp(self.config.speed_min, self.config.speed_max);
+
+            animal.orientation *= na::UnitQuaternion::from_euler_angles(pitch, 0.0, yaw);
+        }
+    }
+
+    fn process_movements(&mut self) {
+        for animal in &mut self.world.animals {
+            animal.position += animal.orientation * na::Vector3::new(0.0, animal.speed, 0.0);
+            animal.position = animal.position.map(|coord| coord.rem_euclid(1.0));
+            animal.distance += animal.speed;
+            animal.age += 1;
+        }
+    }
+
+    fn evolve(&mut self) -> ga::Statistics {
+        self.age = 0;
+
+        let population: Vec<_> = self
+            .world
+            .animals
+            .iter()
+            .map(|animal| {
+                // Negative scores are treated as zero, just like in 2D
+                let fitness = self.config.fitness.score(&animal.into()).max(0.0);
+
+                AnimalIndividual::new(fitness as ga::Float, animal.as_chromosome())
+            })
+            .collect();
+
+        let (evolved, stats) = self
+            .ga
+            .evolve_into(&mut self.rng, &population, &mut self.recycled);
+
+        self.recycled
+            .extend(population.into_iter().map(Individual::into_chromosome));
+
+        self.world.animals = evolved
+            .into_iter()
+            .map(|individual| {
+                Animal3d::from_chromosome(&self.config, individual.into_chromosome(), &mut self.rng)
+            })
+            .collect();
+
+        for food in &mut self.world.foods {
+            *food = self.rng.gen();
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn see() {
+        let eye = Eye3d::new(1.0, FRAC_PI_2, 3);
+        let position = na::Point3::new(0.5, 0.5, 0.5);
+
+        // Straight ahead, above, and behind the bird
+        let foods = [
+            na::Point3::new(0.5, 1.0, 0.5),
+            na::Point3::new(0.5, 0.6, 1.0),
+            na::Point3::new(0.5, 0.0, 0.5),
+        ];
+
+        let cells = eye.see(position, na::UnitQuaternion::identity(), foods);
+
+        assert_relative_eq!(
+            cells.as_slice(),
+            [
+                0.0,
+                0.0,
+                0.0,
+                0.0,
+                0.5,
+                0.0,
+                0.0,
+                1.0 - 0.26_f32.sqrt(),
+                0.0
+            ]
+            .as_ref(),
+            epsilon = 1e-6
+        );
+
+        // Pitching up by 90 degrees puts the food above straight ahead
+        let up = na::UnitQuaternion::from_euler_angles(FRAC_PI_2, 0.0, 0.0);
+        let cells = eye.see(position, up, [na::Point3::new(0.5, 0.5, 0.9)]);
+
+        assert_relative_eq!(cells[4], 0.6, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn train() {
+        let config = Config {
+            population: 4,
+            foods: 10,
+            generation_length: 20,
+            ..Config::default()
+        };
+
+        let mut sim = Simulation3d::new(config, 0);
+        let stats = sim.train();
+
+        assert!(stats.max_fitness >= stats.avg_fitness);
+        assert_eq!(sim.world().animals().len(), 4);
+    }
+
+    #[test]
+    fn fitness() {
+        let config = Config {
+            population: 4,
+            generation_length: 20,
+            fitness: Fitness::Distance,
+            ..Config::default()
+        };
+
+        let stats = Simulation3d::new(config.clone(), 0).train();
+
+        // No-op for the default precision
+        #[allow(clippy::unnecessary_cast)]
+        let (min_fitness, max_fitness) = (stats.min_fitness as f32, stats.max_fitness as f32);
+
+        // Birds always fly between the minimum and maximum speed
+        assert!(min_fitness >= 20.0 * config.speed_min - 1e-6);
+        assert!(max_fitness <= 20.0 * config.speed_max + 1e-6);
+    }
+}
@@ -0,0 +1,293 @@
+use crate::snapshot::{array, number};
+use crate::*;
+use rand::seq::SliceRandom;
+
+const VERSION: u64 = 1;
+
+/// Trains a couple of independent simulations (sharing the same config)
+/// side by side, each on its own thread, every now and then mixing their
+/// birds together - so that good brains found in one world spread to the
+/// others.
+pub struct ParallelTrainer {
+    simulations: Vec<Simulation>,
+    merge_every: usize,
+    generations: usize,
+    seed: u64,
+
+    /// Drives the merging; simulations have PRNGs of their own
+    rng: ChaCha8Rng,
+}
+
+impl ParallelTrainer {
+    /// Creates `worlds` simulations, with seeds derived from `seed`, that
+    /// get their birds merged after every `merge_every` generations.
+    pub fn new(config: Config, seed: u64, worlds: usize, merge_every: usize) -> Self {
+        assert!(worlds > 0);
+        assert!(merge_every > 0);
+
+        assert_eq!(
+            config.reproduction,
+            Reproduction::Generational,
+            "parallel training requires generations"
+        );
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+        let simulations = (0..worlds)
+            .map(|_| Simulation::new(config.clone(), rng.next_u64()))
+            .collect();
+
+        Self {
+            simulations,
+            merge_every,
+            generations: 0,
+            seed,
+            rng,
+        }
+    }
+
+    pub fn simulations(&self) -> &[Simulation] {
+        &self.simulations
+    }
+
+    /// Number of generations trained so far.
+    pub fn generations(&self) -> usize {
+        self.generations
+    }
+
+    /// Captures all the simulations, along with the state of merging, as a
+    /// JSON string - see `Simulation::save()`.
+    pub fn save(&self) -> String {
+        let simulations = self
+            .simulations
+            .iter()
+            .map(|sim| Json::parse(&sim.save()).expect("snapshots are valid JSONs"))
+            .collect();
+
+        Json::object([
+            ("version", Json::number(VERSION)),
+            ("seed", Json::number(self.seed)),
+            ("rng", Json::number(self.rng.get_word_pos())),
+            ("merge_every", Json::number(self.merge_every)),
+            ("generations", Json::number(self.generations)),
+            ("simulations", Json::Array(simulations)),
+        ])
+        .to_string()
+    }
+
+    /// Restores a trainer saved with `save()`; given that `config` is the
+    /// same, it continues exactly like the original one would.
+    pub fn load(config: Config, snapshot: &str) -> Result<Self, SnapshotError> {
+        let json = Json::parse(snapshot).map_err(|offset| SnapshotError::Syntax { offset })?;
+
+        let version: u64 = number(&json, "version")?;
+
+        if version != VERSION {
+            return Err(SnapshotError::UnsupportedVersion { version });
+        }
+
+        let seed = number(&json, "seed")?;
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        rng.set_word_pos(number(&json, "rng")?);
+
+        let simulations: Vec<_> = array(&json, "simulations")?
+            .iter()
+            .map(|sim| Simulation::load(config.clone(), &sim.to_string()))
+            .collect::<Result<_, _>>()?;
+
+        let merge_every = number(&json, "merge_every")?;
+
+        if simulations.is_empty() || merge_every == 0 {
+            return Err(SnapshotError::Malformed {
+                field: "simulations",
+            });
+        }
+
+        Ok(Self {
+            simulations,
+            merge_every,
+            generations: number(&json, "generations")?,
+            seed,
+            rng,
+        })
+    }
+
+    /// Fast-forwards each simulation through a generation, returning their
+    /// statistics (in the order of `simulations()`).
+    pub fn train(&mut self) -> Vec<Statistics> {
+        self.train_inner(None)
+    }
+
+    /// Like `train()`, but also records the first simulation's generation
+    /// - frame by frame, from its very first step up to the last one.
+    pub fn train_recorded(&mut self, recorder: &mut Recorder) -> Vec<Statistics> {
+        self.train_inner(Some(recorder))
+    }
+
+    fn train_inner(&mut self, mut recorder: Option<&mut Recorder>) -> Vec<Statistics> {
+        let stats = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .simulations
+                .iter_mut()
+                .map(|sim| {
+                    let recorder = recorder.take();
+
+                    scope.spawn(move || match recorder {
+                        Some(recorder) => loop {
+                            recorder.record(sim.world());
+
+                            if let Some(stats) = sim.step() {
+                                break stats;
+                            }
+                        },
+
+                        None => sim.train(),
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        self.generations += 1;
+
+        if self.generations.is_multiple_of(self.merge_every) {
+            self.merge();
+        }
+
+        stats
+    }
+
+    /// Fast-forwards through `n` generations, returning their statistics.
+    pub fn train_generations(&mut self, n: usize) -> Vec<Vec<Statistics>> {
+        (0..n).map(|_| self.train()).collect()
+    }
+
+    /// Shuffles evolved birds of each species between the simulations;
+    /// every simulation keeps its number of birds (and its baselines and
+    /// injected birds).
+    fn merge(&mut self) {
+        let species = self.simulations[0].config.species;
+        let mut pools: Vec<Vec<Animal>> = (0..species).map(|_| Vec::new()).collect();
+        let mut counts = Vec::new();
+        let mut baselines = Vec::new();
+
+        for sim in &mut self.simulations {
+            let mut count = vec![0; species];
+            let mut rest = Vec::new();
+
+            for animal in std::mem::take(&mut sim.world.animals) {
+                if !animal.is_evolved() {
+                    rest.push(animal);
+                } else {
+                    count[animal.species] += 1;
+                    pools[animal.species].push(animal);
+                }
+            }
+
+            counts.push(count);
+            baselines.push(rest);
+        }
+
+        for pool in &mut pools {
+            pool.shuffle(&mut self.rng);
+        }
+
+        for ((sim, count), baselines) in self.simulations.iter_mut().zip(counts).zip(baselines) {
+            for (pool, count) in pools.iter_mut().zip(count) {
+                sim.world.animals.extend(pool.drain(..count));
+            }
+
+            sim.world.animals.extend(baselines);
+
+            // Birds coming from other worlds could clash with the local ids
+            sim.world.number_animals(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            population: 4,
+            species: 2,
+            generation_length: 10,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn train() {
+        let mut trainer = ParallelTrainer::new(config(), 0, 3, 2);
+        let history = trainer.train_generations(3);
+
+        assert_eq!(history.len(), 3);
+        assert!(history.iter().all(|stats| stats.len() == 3));
+
+        for sim in trainer.simulations() {
+            let species: Vec<_> = sim.world().animals().iter().map(Animal::species).collect();
+            assert_eq!(species, [0, 0, 0, 0, 1, 1, 1, 1]);
+        }
+
+        // Threads don't make it any less deterministic
+        let mut other = ParallelTrainer::new(config(), 0, 3, 2);
+        assert_eq!(other.train_generations(3), history);
+    }
+
+    #[test]
+    fn train_recorded() {
+        let mut trainer = ParallelTrainer::new(config(), 0, 2, 2);
+        let mut recorder = Recorder::new();
+        let stats = trainer.train_recorded(&mut recorder);
+
+        assert_eq!(recorder.finish().len(), 10);
+
+        // Recording doesn't affect the training
+        let mut other = ParallelTrainer::new(config(), 0, 2, 2);
+        assert_eq!(other.train(), stats);
+    }
+
+    #[test]
+    fn save_load() {
+        let mut trainer = ParallelTrainer::new(config(), 0, 2, 2);
+        trainer.train();
+
+        let mut loaded = ParallelTrainer::load(config(), &trainer.save()).unwrap();
+
+        assert_eq!(loaded.generations(), 1);
+
+        // Second generation ends with a merge, which has to go the same way
+        assert_eq!(loaded.train_generations(2), trainer.train_generations(2));
+        assert_eq!(loaded.save(), trainer.save());
+    }
+
+    #[test]
+    fn merge() {
+        let mut trainer = ParallelTrainer::new(config(), 0, 2, 1);
+        let before: Vec<_> = trainer.simulations[0]
+            .world
+            .animals
+            .iter()
+            .map(|animal| animal.as_chromosome().iter().copied().collect::<Vec<_>>())
+            .collect();
+
+        trainer.merge();
+
+        let after: Vec<_> = trainer.simulations[0]
+            .world
+            .animals
+            .iter()
+            .map(|animal| animal.as_chromosome().iter().copied().collect::<Vec<_>>())
+            .collect();
+
+        // Some birds have come from the other world
+        assert_eq!(after.len(), before.len());
+        assert!(after.iter().any(|chromosome| !before.contains(chromosome)));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::*;
+
+/// Physical parameters that get evolved alongside the brain; each enabled
+/// trait adds a gene at the end of the chromosome (in the order of fields
+/// below), disabled ones are taken from the `Config`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Traits {
+    pub speed_max: Option<Bounds>,
+    pub rotation_accel: Option<Bounds>,
+    pub eye_fov_range: Option<Bounds>,
+    pub eye_fov_angle: Option<Bounds>,
+}
+
+/// Range a trait can evolve within; see `Bounds::new()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Bounds {
+    min: f32,
+    max: f32,
+}
+
+impl Bounds {
+    /// Panics unless `min <= max` (and both are finite).
+    pub fn new(min: f32, max: f32) -> Self {
+        assert!(min.is_finite() && max.is_finite());
+        assert!(min <= max);
+
+        Self { min, max }
+    }
+
+    pub fn min(&self) -> f32 {
+        self.min
+    }
+
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+
+    /// Squashes an (unbounded) gene into the range.
+    fn express(&self, gene: ga::Float) -> f32 {
+        // No-op for the default precision
+        #[allow(clippy::unnecessary_cast)]
+        let gene = gene as f32;
+
+        self.min + (self.max - self.min) / (1.0 + (-gene).exp())
+    }
+}
+
+/// Bird's physical parameters, as expressed from its genes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Body {
+    pub(crate) speed_max: f32,
+    pub(crate) rotation_accel: f32,
+    pub(crate) eye_fov_range: f32,
+    pub(crate) eye_fov_angle: f32,
+
+    /// Genes this body has been expressed from
+    pub(crate) genes: Vec<ga::Float>,
+}
+
+impl Body {
+    pub(crate) fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let genes = (0..config.traits.gene_count())
+            .map(|_| rng.gen_range(-1.0..=1.0))
+            .collect();
+
+        Self::from_genes(config, genes)
+    }
+
+    pub(crate) fn from_genes(config: &Config, genes: Vec<ga::Float>) -> Self {
+        assert_eq!(genes.len(), config.traits.gene_count());
+
+        let traits = &config.traits;
+        let mut remaining = genes.iter();
+
+        let mut express = |bounds: Option<Bounds>, default: f32| match bounds {
+            Some(bounds) => bounds.express(*remaining.next().unwrap()),
+            None => default,
+        };
+
+        Self {
+            // Evolved (or staged) speed limits can fall below the minimum
+            // speed, which would leave no valid speed at all
+            speed_max: express(traits.speed_max, config.speed_max).max(config.speed_min),
+            rotation_accel: express(traits.rotation_accel, config.rotation_accel),
+            eye_fov_range: express(traits.eye_fov_range, config.eye_fov_range),
+            eye_fov_angle: express(traits.eye_fov_angle, config.eye_fov_angle),
+            genes,
+        }
+    }
+
+    pub(crate) fn eye(&self, config: &Config) -> Eye {
+        Eye::new(self.eye_fov_range, self.eye_fov_angle, config.eye_cells)
+    }
+
+    pub fn speed_max(&self) -> f32 {
+        self.speed_max
+    }
+
+    pub fn rotation_accel(&self) -> f32 {
+        self.rotation_accel
+    }
+}
+
+impl Traits {
+    pub(crate) fn gene_count(&self) -> usize {
+        [
+            self.speed_max,
+            self.rotation_accel,
+            self.eye_fov_range,
+            self.eye_fov_angle,
+        ]
+        .iter()
+        .filter(|bounds| bounds.is_some())
+        .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn express() {
+        let config = Config {
+            traits: Traits {
+                speed_max: Some(Bounds::new(0.002, 0.01)),
+                eye_fov_angle: Some(Bounds::new(1.0, 3.0)),
+                ..Traits::default()
+            },
+            ..Config::default()
+        };
+
+        let body = Body::from_genes(&config, vec![0.0, -100.0]);
+
+        assert_relative_eq!(body.speed_max, 0.006);
+        assert_relative_eq!(body.rotation_accel, config.rotation_accel);
+        assert_relative_eq!(body.eye_fov_range, config.eye_fov_range);
+        assert_relative_eq!(body.eye_fov_angle, 1.0);
+    }
+
+    #[test]
+    fn speed_max_is_at_least_speed_min() {
+        let config = Config {
+            speed_min: 0.004,
+            traits: Traits {
+                speed_max: Some(Bounds::new(0.002, 0.01)),
+                ..Traits::default()
+            },
+            ..Config::default()
+        };
+
+        let body = Body::from_genes(&config, vec![-100.0]);
+
+        assert_relative_eq!(body.speed_max, 0.004);
+    }
+
+    #[test]
+    fn bounds_validation() {
+        let config = Config {
+            traits: Traits {
+                rotation_accel: Some(Bounds::new(-1.0, 1.0)),
+                ..Traits::default()
+            },
+            ..Config::default()
+        };
+
+        assert_eq!(config.validate().unwrap_err().field, "traits");
+
+        let config = Config {
+            traits: Traits {
+                eye_fov_range: Some(Bounds::new(0.0, 0.5)),
+                ..Traits::default()
+            },
+            ..Config::default()
+        };
+
+        assert_eq!(config.validate().unwrap_err().field, "traits");
+    }
+
+    #[test]
+    #[should_panic]
+    fn inverted_bounds() {
+        Bounds::new(1.0, 0.0);
+    }
+}
@@ -0,0 +1,57 @@
+use crate::*;
+
+/// Air flow pushing flying birds around.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Wind {
+    #[default]
+    Calm,
+
+    /// Same everywhere (distance per step)
+    Constant { velocity: na::Vector2<f32> },
+
+    /// Circling counter-clockwise around `center`, with `strength` being
+    /// the speed at a distance of 0.5 (and the speed growing linearly with
+    /// the distance, like in a spinning disc)
+    Vortex {
+        center: na::Point2<f32>,
+        strength: f32,
+    },
+}
+
+impl Wind {
+    /// Returns the wind's velocity at given point.
+    pub fn at(&self, point: na::Point2<f32>) -> na::Vector2<f32> {
+        match *self {
+            Wind::Calm => na::Vector2::zeros(),
+            Wind::Constant { velocity } => velocity,
+            Wind::Vortex { center, strength } => {
+                let offset = point - center;
+                na::Vector2::new(-offset.y, offset.x) * (2.0 * strength)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn vortex() {
+        let wind = Wind::Vortex {
+            center: na::Point2::new(0.5, 0.5),
+            strength: 0.001,
+        };
+
+        assert_relative_eq!(
+            wind.at(na::Point2::new(1.0, 0.5)),
+            na::Vector2::new(0.0, 0.001)
+        );
+        assert_relative_eq!(
+            wind.at(na::Point2::new(0.5, 0.25)),
+            na::Vector2::new(0.0005, 0.0)
+        );
+        assert_relative_eq!(wind.at(na::Point2::new(0.5, 0.5)), na::Vector2::zeros());
+    }
+}
@@ -4,15 +4,54 @@ use crate::*;
 pub struct World {
     pub(crate) animals: Vec<Animal>,
     pub(crate) foods: Vec<Food>,
+    pub(crate) predators: Vec<Predator>,
+    pub(crate) obstacles: Vec<Obstacle>,
+    pub(crate) scent: Option<ScentField>,
+
+    /// Id the next bird entering the world gets
+    pub(crate) next_id: u64,
 }
 
 impl World {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let animals = (0..40).map(|_| Animal::random(rng)).collect();
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let mut animals: Vec<_> = (0..config.species)
+            .flat_map(|species| (0..config.population).map(move |_| species))
+            .map(|species| Animal::random(config, rng).with_species(species))
+            .collect();
+
+        animals.extend(spawn_baselines(config, rng));
+
+        let foods = (0..config.foods).map(|_| Food::random(rng)).collect();
+
+        let predators = config
+            .predators
+            .as_ref()
+            .map_or(0, |predators| predators.count);
+
+        let predators = (0..predators).map(|_| Predator::random(rng)).collect();
 
-        let foods = (0..40).map(|_| Food::random(rng)).collect();
+        let mut this = Self {
+            animals,
+            foods,
+            predators,
+            obstacles: config.obstacles.clone(),
+            scent: config
+                .scent
+                .as_ref()
+                .map(|scent| ScentField::new(scent.resolution)),
+            next_id: 0,
+        };
 
-        Self { animals, foods }
+        this.number_animals(0);
+        this
+    }
+
+    /// Gives fresh ids to birds from the `from`-th one onwards.
+    pub(crate) fn number_animals(&mut self, from: usize) {
+        for animal in &mut self.animals[from..] {
+            animal.id = AnimalId(self.next_id);
+            self.next_id += 1;
+        }
     }
 
     pub fn animals(&self) -> &[Animal] {
@@ -22,4 +61,140 @@ impl World {
     pub fn foods(&self) -> &[Food] {
         &self.foods
     }
+
+    pub fn predators(&self) -> &[Predator] {
+        &self.predators
+    }
+
+    pub fn obstacles(&self) -> &[Obstacle] {
+        &self.obstacles
+    }
+
+    pub fn scent(&self) -> Option<&ScentField> {
+        self.scent.as_ref()
+    }
+
+    /// Returns index of the bird with given id, as long as it's still in
+    /// the world.
+    pub fn find_animal(&self, id: AnimalId) -> Option<usize> {
+        self.animals.iter().position(|animal| animal.id == id)
+    }
+
+    /// Returns indices of living birds within the axis-aligned rectangle
+    /// spanning from `min` to `max`.
+    pub fn animals_in_rect(
+        &self,
+        min: na::Point2<f32>,
+        max: na::Point2<f32>,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.animals
+            .iter()
+            .enumerate()
+            .filter(move |(_, animal)| {
+                animal.alive
+                    && (min.x..=max.x).contains(&animal.position.x)
+                    && (min.y..=max.y).contains(&animal.position.y)
+            })
+            .map(|(idx, _)| idx)
+    }
+
+    /// Returns index of the living bird nearest to `point`, as long as
+    /// it's within `radius` - e.g. the one that's been clicked on.
+    pub fn animal_at(&self, point: na::Point2<f32>, radius: f32) -> Option<usize> {
+        nearest(
+            self.animals
+                .iter()
+                .map(|animal| animal.alive.then_some(animal.position)),
+            point,
+        )
+        .filter(|&(_, distance)| distance <= radius)
+        .map(|(idx, _)| idx)
+    }
+
+    /// Returns index of the available food nearest to `point`.
+    pub fn nearest_food(&self, point: na::Point2<f32>) -> Option<usize> {
+        nearest(
+            self.foods
+                .iter()
+                .map(|food| food.available.then_some(food.position)),
+            point,
+        )
+        .map(|(idx, _)| idx)
+    }
+}
+
+/// Returns index of (and distance to) the nearest of `positions`, skipping
+/// the `None`s.
+fn nearest(
+    positions: impl Iterator<Item = Option<na::Point2<f32>>>,
+    point: na::Point2<f32>,
+) -> Option<(usize, f32)> {
+    positions
+        .enumerate()
+        .filter_map(|(idx, position)| Some((idx, na::distance(&position?, &point))))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::mock::StepRng;
+
+    fn world(animals: &[(f32, f32)], foods: &[(f32, f32)]) -> World {
+        let config = Config::default();
+        let mut rng = StepRng::new(0, 1);
+
+        let mut world = World {
+            animals: animals
+                .iter()
+                .map(|&(x, y)| Animal {
+                    position: na::Point2::new(x, y),
+                    ..Animal::random(&config, &mut rng)
+                })
+                .collect(),
+            foods: foods
+                .iter()
+                .map(|&(x, y)| Food {
+                    position: na::Point2::new(x, y),
+                    ..Food::random(&mut rng)
+                })
+                .collect(),
+            predators: Vec::new(),
+            obstacles: Vec::new(),
+            scent: None,
+            next_id: 0,
+        };
+
+        world.number_animals(0);
+        world
+    }
+
+    #[test]
+    fn queries() {
+        let mut world = world(
+            &[(0.1, 0.1), (0.5, 0.5), (0.52, 0.5), (0.9, 0.9)],
+            &[(0.2, 0.2), (0.8, 0.8)],
+        );
+
+        let found: Vec<_> = world
+            .animals_in_rect(na::Point2::new(0.0, 0.0), na::Point2::new(0.6, 0.6))
+            .collect();
+
+        assert_eq!(found, [0, 1, 2]);
+
+        assert_eq!(world.animal_at(na::Point2::new(0.515, 0.5), 0.01), Some(2));
+        assert_eq!(world.animal_at(na::Point2::new(0.3, 0.3), 0.01), None);
+
+        world.animals[2].alive = false;
+        assert_eq!(world.animal_at(na::Point2::new(0.515, 0.5), 0.05), Some(1));
+
+        assert_eq!(world.nearest_food(na::Point2::new(0.6, 0.6)), Some(1));
+
+        world.foods[1].available = false;
+        assert_eq!(world.nearest_food(na::Point2::new(0.6, 0.6)), Some(0));
+
+        world.animals.remove(0);
+        assert_eq!(world.find_animal(AnimalId(3)), Some(2));
+        assert_eq!(world.find_animal(AnimalId(0)), None);
+    }
 }
@@ -37,46 +37,405 @@ CanvasRenderingContext2D.prototype.drawTriangle = function (
   this.fill();
 };
 
-const simulation = new sim.Simulation();
+// Pass `?seed=...` to replay a run, and e.g. `?population=20&foods=60` to
+// tweak the config
+const params = new URLSearchParams(window.location.search);
+const seed = params.get('seed');
+
+const knobs = [
+  'population',
+  'species',
+  'foods',
+  'eye_fov_range',
+  'eye_fov_angle',
+  'eye_cells',
+  'mutation_chance',
+  'mutation_coeff',
+  'generation_length',
+  'speed_min',
+  'speed_max',
+  'speed_accel',
+  'rotation_accel',
+  'substeps',
+];
+
+// Builds config out of knobs given with any of the prefixes (later ones
+// win)
+function readConfig(prefixes) {
+  const config = new sim.Config();
+
+  for (const prefix of prefixes) {
+    for (const knob of knobs) {
+      if (params.has(prefix + knob)) {
+        config[knob] = Number(params.get(prefix + knob));
+      }
+    }
+  }
+
+  return config;
+}
+
+let simulation = new sim.Simulation(
+  seed === null ? undefined : BigInt(seed),
+  readConfig([''])
+);
+console.log(`seed=${simulation.seed()}`);
+
+// Pass e.g. `?b.mutation_chance=0.05` to run another simulation next to
+// the first one - with the same seed and config, save for the knobs given -
+// and see how they compare
+const comparison = knobs.some((knob) => params.has('b.' + knob))
+  ? new sim.Simulation(simulation.seed(), readConfig(['', 'b.']))
+  : undefined;
+
 const viewport = document.getElementById('viewport');
+
+// Training happens on a copy of the simulation living inside a worker;
+// once it's done, the trained copy replaces the one shown on the screen
+const trainer = new Worker(new URL('./worker.js', import.meta.url), {
+  type: 'module',
+});
+
+trainer.onmessage = function (event) {
+  const reply = JSON.parse(event.data);
+
+  if (reply.error !== undefined) {
+    console.error(`${reply.command}: ${reply.error}`);
+  } else if (reply.command === 'train') {
+    for (const stats of reply.stats) {
+      console.log(
+        `generation=${stats.generation}, min=${stats.min_fitness.toFixed(2)}, ` +
+          `max=${stats.max_fitness.toFixed(2)}, avg=${stats.avg_fitness.toFixed(2)}`
+      );
+    }
+  } else if (reply.command === 'save') {
+    const paused = simulation.is_paused();
+
+    simulation = sim.Simulation.load(reply.snapshot);
+    simulation.set_speed(Number(document.getElementById('speed').value));
+
+    if (paused) {
+      simulation.pause();
+    }
+
+    document.getElementById('train').disabled = false;
+  }
+};
+
 document.getElementById('train').onclick = function () {
-  console.log(simulation.train());
+  this.disabled = true;
+
+  trainer.postMessage(
+    JSON.stringify({ command: 'load', snapshot: simulation.save() })
+  );
+  trainer.postMessage(JSON.stringify({ command: 'train' }));
+  trainer.postMessage(JSON.stringify({ command: 'save' }));
+};
+
+document.getElementById('save').onclick = function () {
+  localStorage.setItem('simulation', simulation.save());
+};
+
+document.getElementById('restore').onclick = function () {
+  const snapshot = localStorage.getItem('simulation');
+
+  if (snapshot !== null) {
+    simulation = sim.Simulation.load(snapshot);
+    simulation.set_speed(Number(document.getElementById('speed').value));
+  }
+};
+
+// Generations recorded natively (see `how-to-fly-cli train --replay`),
+// played back instead of the simulation until the page gets reloaded
+let replay;
+
+// Brains trained natively (see `how-to-fly-cli export`) come with their
+// config, so they get a fresh simulation of their own
+document.getElementById('import').onchange = async function () {
+  const brain = await this.files[0].text();
+
+  if ('clips' in JSON.parse(brain)) {
+    const player = sim.Replay.load(brain, viewportWidth, viewportHeight);
+
+    if (player.clips() > 0) {
+      replay = { player, clip: 0, step: 0 };
+    }
+
+    return;
+  }
+
+  const config = sim.Config.load(brain);
+
+  config.width = viewportWidth;
+  config.height = viewportHeight;
+
+  simulation = new sim.Simulation(undefined, config);
+  simulation.set_speed(Number(document.getElementById('speed').value));
+  simulation.import_brain(brain);
+};
+
+document.getElementById('pause').onclick = function () {
+  if (simulation.is_paused()) {
+    simulation.resume();
+    comparison?.resume();
+    this.textContent = 'pause';
+  } else {
+    simulation.pause();
+    comparison?.pause();
+    this.textContent = 'resume';
+  }
+};
+
+document.getElementById('step').onclick = function () {
+  simulation.step_once();
+  comparison?.step_once();
+};
+
+document.getElementById('speed').oninput = function () {
+  simulation.set_speed(Number(this.value));
+  comparison?.set_speed(Number(this.value));
+};
+
+// Mutations change once the current generation ends
+const mutationChance = document.getElementById('mutation-chance');
+const mutationCoeff = document.getElementById('mutation-coeff');
+
+mutationChance.value = simulation.mutation_chance().toFixed(2);
+mutationCoeff.value = simulation.mutation_coeff().toFixed(2);
+
+mutationChance.onchange = function () {
+  try {
+    simulation.set_mutation_chance(Number(this.value));
+  } catch (err) {
+    console.error(err);
+    this.value = simulation.mutation_chance().toFixed(2);
+  }
+};
+
+mutationCoeff.onchange = function () {
+  simulation.set_mutation_coeff(Number(this.value));
 };
 
 const viewportWidth = viewport.width;
 const viewportHeight = viewport.height;
 const viewportScale = window.devicePixelRatio || 1;
 
-viewport.width = viewportWidth * viewportScale;
-viewport.height = viewportHeight * viewportScale;
-viewport.style.width = viewportWidth + 'px';
-viewport.style.height = viewportHeight + 'px';
+function setUpViewport(viewport, simulation) {
+  viewport.width = viewportWidth * viewportScale;
+  viewport.height = viewportHeight * viewportScale;
+  viewport.style.width = viewportWidth + 'px';
+  viewport.style.height = viewportHeight + 'px';
+
+  // Lets the simulation speak in the canvas' pixels
+  simulation.resize(viewportWidth, viewportHeight);
+
+  const ctxt = viewport.getContext('2d');
+  ctxt.scale(viewportScale, viewportScale);
+
+  return ctxt;
+}
+
+const ctxt = setUpViewport(viewport, simulation);
+const comparisonViewport = document.getElementById('viewport-b');
+let comparisonCtxt;
+
+if (comparison !== undefined) {
+  comparisonViewport.hidden = false;
+  comparisonCtxt = setUpViewport(comparisonViewport, comparison);
+}
+
+// Click drops food, shift-click a bird; alt-click selects a bird, whose
+// brain gets shown next to the world
+viewport.onclick = function (event) {
+  const x = event.offsetX;
+  const y = event.offsetY;
+
+  if (event.altKey) {
+    simulation.select_animal_at(x, y);
+  } else if (event.shiftKey) {
+    simulation.add_animal(x, y);
+  } else {
+    simulation.add_food(x, y);
+  }
+};
+
+// Foods eaten during the current generation
+let eaten = 0;
+
+// Timestamp of the previous frame, so that the simulation advances with
+// real time regardless of the display's refresh rate
+let lastFrame;
 
-const ctxt = viewport.getContext('2d');
-ctxt.scale(viewportScale, viewportScale);
+// Interpolates between values wrapping around at `period` (e.g. birds
+// flying off the world's edge), taking the shorter way
+function lerp(from, to, alpha, period) {
+  let delta = to - from;
+  if (delta > period / 2) {
+    delta -= period;
+  } else if (delta < -period / 2) {
+    delta += period;
+  }
 
-function redraw() {
+  return from + delta * alpha;
+}
+
+function drawWorld(ctxt, simulation) {
   ctxt.clearRect(0, 0, viewportWidth, viewportHeight);
-  simulation.step();
 
-  const world = simulation.world();
+  // Birds and foods are read straight out of the wasm memory, sparing
+  // an object per each of them
+  const foods = new Float32Array(
+    sim.memory().buffer,
+    simulation.food_buffer(),
+    simulation.food_buffer_len()
+  );
+
+  for (let i = 0; i < foods.length; i += 2) {
+    ctxt.drawCircle(foods[i], foods[i + 1], (0.01 / 2.0) * viewportWidth);
+  }
+
+  const animals = new Float32Array(
+    sim.memory().buffer,
+    simulation.animal_buffer(),
+    simulation.animal_buffer_len()
+  );
+
+  const previousAnimals = new Float32Array(
+    sim.memory().buffer,
+    simulation.previous_animal_buffer(),
+    simulation.animal_buffer_len()
+  );
+
+  // Birds get drawn in between their previous and current transforms,
+  // so that they don't jitter when there's less steps than frames
+  const alpha = simulation.interpolation();
+
+  for (let i = 0; i < animals.length; i += 3) {
+    ctxt.drawTriangle(
+      lerp(previousAnimals[i], animals[i], alpha, viewportWidth),
+      lerp(previousAnimals[i + 1], animals[i + 1], alpha, viewportHeight),
+      0.01 * viewportWidth,
+      lerp(previousAnimals[i + 2], animals[i + 2], alpha, 2 * Math.PI)
+    );
+  }
+
+  for (const predator of simulation.predators()) {
+    ctxt.drawTriangle(
+      predator.x,
+      predator.y,
+      0.02 * viewportWidth,
+      predator.rotation
+    );
+  }
+}
+
+function drawReplay(ctxt, replay) {
+  const { player, clip } = replay;
+  const frame = player.frame(clip, Math.floor(replay.step));
+
+  document.getElementById('replay').textContent =
+    `generation ${player.generation(clip)} (${player.label(clip)})`;
 
-  for (const food of world.foods) {
+  ctxt.clearRect(0, 0, viewportWidth, viewportHeight);
+
+  for (let i = 0; i < frame.foods.length; i += 2) {
     ctxt.drawCircle(
-      food.x * viewportWidth,
-      food.y * viewportHeight,
+      frame.foods[i],
+      frame.foods[i + 1],
       (0.01 / 2.0) * viewportWidth
     );
   }
 
-  for (const animal of simulation.world().animals) {
+  for (let i = 0; i < frame.animals.length; i += 3) {
     ctxt.drawTriangle(
-      animal.x * viewportWidth,
-      animal.y * viewportHeight,
+      frame.animals[i],
+      frame.animals[i + 1],
       0.01 * viewportWidth,
-      animal.rotation
+      frame.animals[i + 2]
     );
   }
+
+  for (let i = 0; i < frame.predators.length; i += 3) {
+    ctxt.drawTriangle(
+      frame.predators[i],
+      frame.predators[i + 1],
+      0.02 * viewportWidth,
+      frame.predators[i + 2]
+    );
+  }
+}
+
+// Moves the replay by `dt` seconds, going over its generations in a loop;
+// like `Simulation.step_dt()`, it plays 60 steps per second (times speed).
+// `Replay.load()` skips clips without frames, so the loop always ends.
+function advanceReplay(replay, dt) {
+  if (!simulation.is_paused()) {
+    const speed = Number(document.getElementById('speed').value);
+    replay.step += Math.min(dt, 0.25) * 60 * speed;
+  }
+
+  while (replay.step >= replay.player.frames(replay.clip)) {
+    replay.step -= replay.player.frames(replay.clip);
+    replay.clip = (replay.clip + 1) % replay.player.clips();
+  }
+}
+
+function redraw(now) {
+  if (replay !== undefined) {
+    if (lastFrame !== undefined) {
+      advanceReplay(replay, (now - lastFrame) / 1000);
+    }
+
+    lastFrame = now;
+    drawReplay(ctxt, replay);
+    requestAnimationFrame(redraw);
+
+    return;
+  }
+
+  if (lastFrame !== undefined) {
+    simulation.step_dt((now - lastFrame) / 1000);
+    comparison?.step_dt((now - lastFrame) / 1000);
+  }
+
+  lastFrame = now;
+
+  for (const event of simulation.drain_events()) {
+    if (event.kind === sim.EventKind.FoodEaten) {
+      eaten += 1;
+    } else if (event.kind === sim.EventKind.GenerationEnded) {
+      console.log(
+        `generation=${event.stats.generation} ended, ` +
+          `avg=${event.stats.avg_fitness.toFixed(2)}`
+      );
+      eaten = 0;
+    }
+  }
+
+  document.getElementById('eaten').textContent = eaten;
+  drawWorld(ctxt, simulation);
+
+  const debug = simulation.selected_animal_debug();
+
+  document.getElementById('brain').textContent = debug
+    ? `vision: ${Array.from(debug.vision, (cell) => cell.toFixed(2)).join(' ')}\n` +
+      `layers: ${Array.from(debug.layer_sizes).join(' -> ')}\n` +
+      `speed: ${debug.speed.toFixed(3)}, rotation: ${debug.rotation.toFixed(3)}`
+    : '';
+
+  if (comparison !== undefined) {
+    for (const event of comparison.drain_events()) {
+      if (event.kind === sim.EventKind.GenerationEnded) {
+        console.log(
+          `(b) generation=${event.stats.generation} ended, ` +
+            `avg=${event.stats.avg_fitness.toFixed(2)}`
+        );
+      }
+    }
+
+    drawWorld(comparisonCtxt, comparison);
+  }
+
   // requestAnimationFrame() schedules code only for the next frame.
   //
   // Because we want for our simulation to continue forever, we've
@@ -84,4 +443,4 @@ function redraw() {
   requestAnimationFrame(redraw);
 }
 
-redraw();
+requestAnimationFrame(redraw);
@@ -0,0 +1,9 @@
+import * as sim from 'lib-simulation-wasm';
+
+// Trains simulations sent over from the main thread, so that long
+// generations don't freeze the page
+let simulation = new sim.Simulation();
+
+onmessage = function (event) {
+  postMessage(simulation.handle_command(event.data));
+};